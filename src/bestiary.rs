@@ -0,0 +1,405 @@
+use std::{fmt::Display, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::character::{Chr, Health, Stats};
+
+/// The directory templates are stored in, next to `saves/`.
+pub const BESTIARY_DIR: &str = "bestiary";
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("couldn't read bestiary directory `{0}` due to I/O error `{1}`")]
+    ReadDirError(String, #[source] io::Error),
+    #[error("couldn't load template at `{0}` due to I/O error `{1}`")]
+    LoadIOError(String, #[source] io::Error),
+    #[error("couldn't load template at `{0}` due to invalid JSON `{1}`")]
+    LoadCorruptTemplate(String, #[source] serde_json::Error),
+    #[error("couldn't save template at `{0}` due to serialisation error `{1}`")]
+    SerialisationError(String, #[source] serde_json::Error),
+    #[error("couldn't save template at `{0}` due to I/O error `{1}`")]
+    SaveIOError(String, #[source] io::Error),
+    #[error("couldn't save template with an empty id")]
+    EmptyIdError,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Makes [`id`] safe to use as a file name, replacing every character other
+/// than ASCII letters, digits, `_` and `-` with `-`.
+#[must_use]
+pub fn sanitize_id(id: &str) -> String {
+    id.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '-' { c } else { '-' }).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize, Hash)]
+pub struct Saves {
+    pub fortitude: i32,
+    pub reflex: i32,
+    pub will: i32,
+}
+
+/// A resistance or weakness to a type of damage, like `fire 5`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Hash)]
+pub struct Resistance {
+    pub kind: String,
+    pub value: u32,
+}
+
+/// The elite and weak adjustments of a creature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Hash)]
+pub enum Variant {
+    Elite,
+    Weak,
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Elite => write!(f, "Elite"),
+            Self::Weak => write!(f, "Weak"),
+        }
+    }
+}
+
+impl Variant {
+    /// The change to AC, saves, attacks, Perception and DCs.
+    #[must_use]
+    pub const fn stat_adjustment(self) -> i32 {
+        match self {
+            Self::Elite => 2,
+            Self::Weak => -2,
+        }
+    }
+
+    /// The change to the max HP of a creature of the given [`level`], before
+    /// adjusting the level.
+    #[must_use]
+    pub const fn hp_adjustment(self, level: i32) -> i32 {
+        match (self, level) {
+            (Self::Elite, ..=1) => 10,
+            (Self::Elite, 2..=4) => 15,
+            (Self::Elite, 5..=19) => 20,
+            (Self::Elite, 20..) => 30,
+            (Self::Weak, ..=2) => -10,
+            (Self::Weak, 3..=5) => -15,
+            (Self::Weak, 6..=20) => -20,
+            (Self::Weak, 21..) => -30,
+        }
+    }
+
+    /// The adjusted level of a creature of the given [`level`].
+    ///
+    /// Elite creatures of level -1 or 0 go up by 2 levels, and weak creatures
+    /// of level 1 go down by 2 levels, as there is no level 0 for them to be.
+    #[must_use]
+    pub const fn adjust_level(self, level: i32) -> i32 {
+        match (self, level) {
+            (Self::Elite, -1 | 0) => level + 2,
+            (Self::Elite, _) => level + 1,
+            (Self::Weak, 1) => -1,
+            (Self::Weak, _) => level - 1,
+        }
+    }
+
+    /// Adjusts a max HP of [`hp`] for a creature of the given [`level`],
+    /// leaving at least 1 HP.
+    #[must_use]
+    pub const fn adjust_hp(self, hp: u32, level: i32) -> u32 {
+        let hp = hp.saturating_add_signed(self.hp_adjustment(level));
+        if hp == 0 { 1 } else { hp }
+    }
+}
+
+impl Display for Resistance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.kind, self.value)
+    }
+}
+
+impl std::str::FromStr for Resistance {
+    type Err = String;
+
+    /// Parses a resistance given like `fire 5` or `cold iron 10`.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (kind, value) = s.trim().rsplit_once(char::is_whitespace)
+            .ok_or_else(|| format!("resistance `{s}` has no value"))?;
+        let value = value.parse().map_err(|_| format!("resistance `{s}` has an invalid value"))?;
+        Ok(Self { kind: kind.trim().to_lowercase(), value })
+    }
+}
+
+/// A creature template, from which characters can be added to the tracker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Hash)]
+pub struct Template {
+    /// The identifier of the template, which is also the name of its file.
+    pub id: String,
+    pub name: String,
+    pub level: i32,
+    pub hp: u32,
+    pub ac: i32,
+    #[serde(default)]
+    pub saves: Saves,
+    /// The initiative modifier of the creature, usually Perception.
+    pub init_mod: i32,
+    #[serde(default)]
+    pub resistances: Vec<Resistance>,
+    #[serde(default)]
+    pub weaknesses: Vec<Resistance>,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl Template {
+    /// Creates a template with the given stats, no resistances and no notes.
+    /// The id is sanitized with [`sanitize_id`].
+    pub fn new(id: impl AsRef<str>, name: impl Into<String>, level: i32, hp: u32, ac: i32, init_mod: i32) -> Self {
+        Self {
+            id: sanitize_id(id.as_ref()),
+            name: name.into(),
+            level,
+            hp,
+            ac,
+            saves: Saves::default(),
+            init_mod,
+            resistances: Vec::new(),
+            weaknesses: Vec::new(),
+            notes: String::new(),
+        }
+    }
+
+    /// Creates a non-player character from this [`Template`] with the given
+    /// initiative.
+    #[must_use]
+    pub fn to_chr(&self, init: i32) -> Chr {
+        Chr::builder(self.name.clone(), init, false)
+            .with_health(Health::new(self.hp))
+            .with_level(self.level)
+            .with_init_mod(self.init_mod)
+            .with_template(self.id.clone())
+            .with_stats(self.stats())
+            .build()
+    }
+
+    /// The AC, saves, Perception, resistances and weaknesses of the template.
+    #[must_use]
+    pub fn stats(&self) -> Stats {
+        Stats {
+            ac: self.ac,
+            saves: self.saves,
+            perception: self.init_mod,
+            resistances: self.resistances.clone(),
+            weaknesses: self.weaknesses.clone(),
+        }
+    }
+
+    /// A copy of this [`Template`] with the elite or weak adjustments
+    /// applied to its level, HP and stats.
+    #[must_use]
+    pub fn adjusted(&self, variant: Variant) -> Self {
+        let stat = variant.stat_adjustment();
+        Self {
+            level: variant.adjust_level(self.level),
+            hp: variant.adjust_hp(self.hp, self.level),
+            ac: self.ac + stat,
+            saves: Saves {
+                fortitude: self.saves.fortitude + stat,
+                reflex: self.saves.reflex + stat,
+                will: self.saves.will + stat,
+            },
+            init_mod: self.init_mod + stat,
+            ..self.clone()
+        }
+    }
+
+    /// Whether the id or name of the template contains [`query`], ignoring
+    /// case.
+    #[must_use]
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.id.to_lowercase().contains(&query) || self.name.to_lowercase().contains(&query)
+    }
+}
+
+/// A library of creature templates, stored as one JSON file per template.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Bestiary {
+    templates: Vec<Template>,
+}
+
+impl Bestiary {
+    #[must_use]
+    pub fn new(mut templates: Vec<Template>) -> Self {
+        templates.sort_by(|x, y| x.id.cmp(&y.id));
+        Self { templates }
+    }
+
+    /// Loads every `.json` template in the directory [`dir`], sanitizing
+    /// their ids with [`sanitize_id`]. A missing directory gives an empty
+    /// [`Bestiary`].
+    ///
+    /// Template files that can't be read or aren't valid templates are
+    /// skipped, and their errors are returned along with the [`Bestiary`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the directory can't be read.
+    pub fn load(dir: impl AsRef<Path>) -> Result<(Self, Vec<Error>)> {
+        let dir = dir.as_ref();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok((Self::default(), Vec::new())),
+            Err(err) => return Err(Error::ReadDirError(dir.display().to_string(), err)),
+        };
+
+        let mut templates = Vec::new();
+        let mut skipped = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|err| Error::ReadDirError(dir.display().to_string(), err))?.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue
+            }
+
+            match Self::load_template(&path) {
+                Ok(template) => templates.push(template),
+                Err(err) => skipped.push(err),
+            }
+        }
+
+        Ok((Self::new(templates), skipped))
+    }
+
+    fn load_template(path: &Path) -> Result<Template> {
+        let file = path.display().to_string();
+        let json = fs::read(path).map_err(|err| Error::LoadIOError(file.clone(), err))?;
+        let template: Template = serde_json::from_slice(&json).map_err(|err| Error::LoadCorruptTemplate(file, err))?;
+        Ok(Template { id: sanitize_id(&template.id), ..template })
+    }
+
+    /// Saves [`template`] to the directory [`dir`] as `<id>.json`, creating
+    /// the directory if needed. The id is sanitized with [`sanitize_id`], so
+    /// the file always ends up in [`dir`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - The id of the template is empty
+    /// - Serialisation of the template fails
+    /// - The file can't be written.
+    pub fn save_template(dir: impl AsRef<Path>, template: &Template) -> Result<()> {
+        if template.id.is_empty() {
+            return Err(Error::EmptyIdError)
+        }
+
+        let template = Template { id: sanitize_id(&template.id), ..template.clone() };
+        let path = dir.as_ref().join(format!("{}.json", template.id));
+        let file = path.display().to_string();
+        let json = serde_json::to_string_pretty(&template).map_err(|err| Error::SerialisationError(file.clone(), err))?;
+
+        fs::create_dir_all(dir.as_ref()).map_err(|err| Error::SaveIOError(file.clone(), err))?;
+        fs::write(&path, json).map_err(|err| Error::SaveIOError(file, err))?;
+        Ok(())
+    }
+
+    /// Adds [`template`] to the [`Bestiary`], replacing any template with the
+    /// same id.
+    pub fn insert(&mut self, template: Template) {
+        match self.templates.binary_search_by(|x| x.id.cmp(&template.id)) {
+            Ok(i) => self.templates[i] = template,
+            Err(i) => self.templates.insert(i, template),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Template> {
+        self.templates.iter().find(|template| template.id == id)
+    }
+
+    #[must_use]
+    pub fn get_templates(&self) -> &[Template] {
+        &self.templates
+    }
+
+    /// The templates whose id or name contain [`query`], ignoring case.
+    pub fn search<'a>(&'a self, query: &'a str) -> impl Iterator<Item = &'a Template> {
+        self.templates.iter().filter(move |template| template.matches(query))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::{sanitize_id, Bestiary, Template, Variant};
+
+    fn bestiary() -> Bestiary {
+        Bestiary::new(vec![
+            Template::new("goblin-warrior", "Goblin Warrior", -1, 6, 16, 2),
+            Template::new("wolf", "Wolf", 1, 24, 15, 7),
+        ])
+    }
+
+    #[test]
+    fn ids_keep_only_safe_characters() {
+        assert_eq!("goblin_warrior-2", sanitize_id("goblin_warrior-2"));
+        assert_eq!("---etc-passwd", sanitize_id("../etc/passwd"));
+        assert_eq!("Dire-Wolf", Template::new("Dire Wolf", "Dire Wolf", 3, 50, 18, 9).id);
+    }
+
+    #[test]
+    fn search_ignores_case() {
+        let bestiary = bestiary();
+        let found: Vec<&str> = bestiary.search("GOB").map(|t| t.id.as_str()).collect();
+        assert_eq!(vec!["goblin-warrior"], found);
+    }
+
+    #[test]
+    fn insert_replaces_same_id() {
+        let mut bestiary = bestiary();
+        bestiary.insert(Template::new("wolf", "Dire Wolf", 3, 50, 18, 9));
+        assert_eq!(2, bestiary.get_templates().len());
+        assert_eq!(Some(3), bestiary.get("wolf").map(|t| t.level));
+    }
+
+    #[test]
+    fn elite_adjusts_by_level_band() {
+        let elite = Template::new("wolf", "Wolf", 1, 24, 15, 7).adjusted(Variant::Elite);
+        assert_eq!((2, 34, 17, 9), (elite.level, elite.hp, elite.ac, elite.init_mod));
+
+        let elite = Template::new("ogre", "Ogre", 3, 50, 17, 5).adjusted(Variant::Elite);
+        assert_eq!((4, 65), (elite.level, elite.hp));
+    }
+
+    #[test]
+    fn elite_low_level_gains_two_levels() {
+        assert_eq!(1, Variant::Elite.adjust_level(-1));
+        assert_eq!(2, Variant::Elite.adjust_level(0));
+    }
+
+    #[test]
+    fn weak_adjusts_by_level_band() {
+        let weak = Template::new("troll", "Troll", 5, 115, 20, 11).adjusted(Variant::Weak);
+        assert_eq!((4, 100, 18, 9), (weak.level, weak.hp, weak.ac, weak.init_mod));
+    }
+
+    #[test]
+    fn weak_level_one_becomes_minus_one() {
+        assert_eq!(-1, Variant::Weak.adjust_level(1));
+    }
+
+    #[test]
+    fn weak_never_goes_below_one_hp() {
+        assert_eq!(1, Variant::Weak.adjust_hp(6, -1));
+    }
+
+    #[test]
+    fn template_parses_without_optional_fields() {
+        let json = r#"{"id": "wolf", "name": "Wolf", "level": 1, "hp": 24, "ac": 15, "init_mod": 7}"#;
+        let template: Template = serde_json::from_str(json).unwrap();
+        assert_eq!(Template::new("wolf", "Wolf", 1, 24, 15, 7), template);
+    }
+}
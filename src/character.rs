@@ -2,18 +2,23 @@ use std::{cmp::Ordering, fmt::Display};
 use egui::WidgetText;
 use serde::{Deserialize, Serialize};
 
+use crate::bestiary::{Resistance, Saves, Variant};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[derive(Serialize, Deserialize, Hash)]
 pub struct Health {
     pub current: u32,
     pub max: u32,
     pub temp: u32,
+    /// How much the max HP is currently lowered by the drained condition.
+    #[serde(default)]
+    pub drained: u32,
 }
 
 impl Health {
     #[must_use]
     pub const fn new(max: u32) -> Self {
-        Self { max, current: max, temp: 0 }
+        Self { max, current: max, temp: 0, drained: 0 }
     }
 
     fn heal(&mut self, x: u32) {
@@ -47,6 +52,49 @@ impl Health {
     fn add_temp(&mut self, hp: u32) {
         self.temp = self.temp + hp;
     } 
+
+    /// Lowers the max HP by [`reduction`] in place of the current drained
+    /// reduction.
+    ///
+    /// Raising the reduction loses the difference in current HP as well, while
+    /// lowering it only gives back max HP. The max HP is never lowered below 1.
+    fn set_drained(&mut self, reduction: u32) {
+        let base = self.max + self.drained;
+        let reduction = reduction.min(base.saturating_sub(1));
+
+        if reduction > self.drained {
+            self.current = self.current.saturating_sub(reduction - self.drained);
+        }
+
+        self.drained = reduction;
+        self.max = base - reduction;
+        self.current = self.current.min(self.max);
+    }
+}
+
+/// The defenses of a character, as found in a stat block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Hash)]
+pub struct Stats {
+    pub ac: i32,
+    #[serde(default)]
+    pub saves: Saves,
+    pub perception: i32,
+    #[serde(default)]
+    pub resistances: Vec<Resistance>,
+    #[serde(default)]
+    pub weaknesses: Vec<Resistance>,
+}
+
+impl Stats {
+    /// Adds [`bonus`] to the AC, saves and Perception.
+    const fn adjust(&mut self, bonus: i32) {
+        self.ac += bonus;
+        self.saves.fortitude += bonus;
+        self.saves.reflex += bonus;
+        self.saves.will += bonus;
+        self.perception += bonus;
+    }
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
@@ -155,6 +203,23 @@ pub struct Chr {
     pub init: i32,
     pub player: bool,
     pub health: Option<Health>,
+    #[serde(default)]
+    pub level: Option<i32>,
+    /// The name of the group the character is a member of, if any. Members of
+    /// a group share a single slot in the initiative order.
+    #[serde(default)]
+    pub group: Option<String>,
+    /// The modifier added when rolling initiative, usually Perception.
+    #[serde(default)]
+    pub init_mod: Option<i32>,
+    /// The id of the bestiary template the character was made from, if any.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Whether the elite or weak adjustments have been applied.
+    #[serde(default)]
+    pub variant: Option<Variant>,
+    #[serde(default)]
+    pub stats: Option<Stats>,
 }
 
 impl PartialOrd for Chr {
@@ -169,7 +234,8 @@ impl Ord for Chr {
         match (self.player, other.player) {
             (true, false) => Ordering::Greater,
             (false, true) => Ordering::Less,
-            _ => Ordering::Equal
+            // Keeps the members of a group next to each other.
+            _ => self.group.cmp(&other.group)
         }
     }
 }
@@ -230,6 +296,86 @@ impl Chr {
         }
         false
     }
+
+    /// Applies the max HP reduction of being drained by the given value.
+    ///
+    /// The reduction is the character's level (at least 1) times the drained
+    /// value. Characters without a level are unaffected.
+    pub fn set_drained(&mut self, drained: u8) -> bool {
+        let reduction = self.drained_reduction(drained);
+        if let Some(health) = &mut self.health {
+            health.set_drained(reduction);
+            return true
+        }
+        false
+    }
+
+    /// Records that the max HP of the character is already lowered by being
+    /// drained by the given value, as it is in characters imported from
+    /// elsewhere, so the max HP is given back when the condition ends.
+    pub fn mark_drained(&mut self, drained: u8) {
+        let reduction = self.drained_reduction(drained);
+        if let Some(health) = &mut self.health {
+            health.drained = reduction;
+        }
+    }
+
+    fn drained_reduction(&self, drained: u8) -> u32 {
+        self.level.map_or(0, |level| level.max(1).unsigned_abs() * u32::from(drained))
+    }
+
+    /// Applies the elite or weak adjustments to the level, max HP, stats and
+    /// initiative modifier of the character, healing them to full. Returns
+    /// whether the adjustments were applied, which they aren't if the
+    /// character already is elite or weak.
+    ///
+    /// The HP adjustment depends on the level, so characters without a level
+    /// keep their HP.
+    pub fn apply_variant(&mut self, variant: Variant) -> bool {
+        if self.variant.is_some() {
+            return false
+        }
+
+        if let (Some(level), Some(health)) = (self.level, self.health.as_mut()) {
+            *health = Health::new(variant.adjust_hp(health.max, level));
+        }
+        self.level = self.level.map(|level| variant.adjust_level(level));
+        self.init_mod = self.init_mod.map(|init_mod| init_mod + variant.stat_adjustment());
+        if let Some(stats) = self.stats.as_mut() {
+            stats.adjust(variant.stat_adjustment());
+        }
+        self.variant = Some(variant);
+        true
+    }
+
+    /// Updates the character with the level, health, stats and initiative
+    /// modifier of [`other`], such as when importing it anew.
+    ///
+    /// The name, initiative and group are kept, and so is the damage taken,
+    /// temporary HP and drained reduction.
+    pub fn update_from(&mut self, other: Self) {
+        self.health = match (self.health.take(), other.health) {
+            (Some(old), Some(new)) => {
+                let taken = old.max.saturating_sub(old.current);
+                let max = new.max.saturating_sub(old.drained).max(1);
+                Some(Health { current: max.saturating_sub(taken), max, temp: old.temp, drained: old.drained })
+            },
+            (_, new) => new,
+        };
+        self.player = other.player;
+        self.level = other.level;
+        self.init_mod = other.init_mod;
+        self.stats = other.stats;
+        self.template = other.template;
+        self.variant = other.variant;
+    }
+
+    /// Whether the character is down, that is, has 0 HP left. Characters
+    /// without health are never down.
+    #[must_use]
+    pub fn is_down(&self) -> bool {
+        self.health.as_ref().is_some_and(|health| health.current == 0)
+    }
 }
 
 pub struct ChrBuilder {
@@ -237,6 +383,11 @@ pub struct ChrBuilder {
     init: i32,
     player: bool,
     health: Option<Health>,
+    level: Option<i32>,
+    group: Option<String>,
+    init_mod: Option<i32>,
+    template: Option<String>,
+    stats: Option<Stats>,
 }
 
 impl ChrBuilder {
@@ -247,6 +398,11 @@ impl ChrBuilder {
             init,
             player,
             health: None,
+            level: None,
+            group: None,
+            init_mod: None,
+            template: None,
+            stats: None,
         }
     }
 
@@ -257,6 +413,12 @@ impl ChrBuilder {
             init: self.init,
             player: self.player,
             health: self.health,
+            level: self.level,
+            group: self.group,
+            init_mod: self.init_mod,
+            template: self.template,
+            variant: None,
+            stats: self.stats,
         }
     }
     
@@ -264,6 +426,31 @@ impl ChrBuilder {
     pub fn with_health(self, health: Health) -> Self {
         Self { health: Some(health), ..self }
     }
+
+    #[must_use]
+    pub fn with_level(self, level: i32) -> Self {
+        Self { level: Some(level), ..self }
+    }
+
+    #[must_use]
+    pub fn with_init_mod(self, init_mod: i32) -> Self {
+        Self { init_mod: Some(init_mod), ..self }
+    }
+
+    #[must_use]
+    pub fn with_template(self, template: impl Into<String>) -> Self {
+        Self { template: Some(template.into()), ..self }
+    }
+
+    #[must_use]
+    pub fn with_group(self, group: impl Into<String>) -> Self {
+        Self { group: Some(group.into()), ..self }
+    }
+
+    #[must_use]
+    pub fn with_stats(self, stats: Stats) -> Self {
+        Self { stats: Some(stats), ..self }
+    }
 }
 
 #[cfg(test)]
@@ -305,6 +492,19 @@ mod tests {
         assert_eq!(Ordering::Equal, c1.cmp(&c2));
     }
 
+    #[test]
+    fn chr_order_same_init_keeps_group_together() {
+        let mut chrs = [
+            Chr::builder("Goblin 1", 10, false).with_group("Goblin").build(),
+            Chr::builder("a", 10, false).build(),
+            Chr::builder("Goblin 2", 10, false).with_group("Goblin").build(),
+        ];
+        chrs.sort();
+
+        assert_eq!(Some("Goblin"), chrs[1].group.as_deref());
+        assert_eq!(Some("Goblin"), chrs[2].group.as_deref());
+    }
+
     #[test]
     fn damage_less_than_all() {
         let mut health = Health::new(100);
@@ -332,6 +532,69 @@ mod tests {
         assert_eq!(14, health.temp)
     }
 
+    #[test]
+    fn drained_lowers_max_and_current() {
+        let mut health = Health::new(50);
+        health.set_drained(6);
+
+        assert_eq!(44, health.max);
+        assert_eq!(44, health.current);
+    }
+
+    #[test]
+    fn raising_drained_only_loses_the_difference() {
+        let mut health = Health::new(50);
+        health.set_drained(3);
+        health.set_drained(6);
+
+        assert_eq!(44, health.max);
+        assert_eq!(44, health.current);
+    }
+
+    #[test]
+    fn removing_drained_restores_max_but_not_current() {
+        let mut health = Health::new(50);
+        health.set_drained(6);
+        health.set_drained(0);
+
+        assert_eq!(50, health.max);
+        assert_eq!(44, health.current);
+    }
+
+    #[test]
+    fn drained_never_lowers_max_below_one() {
+        let mut health = Health::new(5);
+        health.set_drained(20);
+
+        assert_eq!(1, health.max);
+        health.set_drained(0);
+        assert_eq!(5, health.max);
+    }
+
+    #[test]
+    fn drained_2_on_level_3_character_lowers_max_by_6() {
+        let mut chr = Chr::builder("a", 10, false).with_health(Health::new(40)).with_level(3).build();
+        chr.set_drained(2);
+
+        assert_eq!(Some(34), chr.health.map(|h| h.max));
+    }
+
+    #[test]
+    fn drained_on_level_0_character_counts_as_level_1() {
+        let mut chr = Chr::builder("a", 10, false).with_health(Health::new(40)).with_level(0).build();
+        chr.set_drained(2);
+
+        assert_eq!(Some(38), chr.health.map(|h| h.max));
+    }
+
+    #[test]
+    fn drained_without_level_does_nothing() {
+        let mut chr = Chr::builder("a", 10, false).with_health(Health::new(40)).build();
+        chr.set_drained(2);
+
+        assert_eq!(Some(40), chr.health.map(|h| h.max));
+    }
+
     #[test]
     fn damage_more_than_temp_rolls_over() {
         let mut health = Health::new(100);
@@ -73,6 +73,19 @@ impl Condition {
             }
         }
     }
+
+    /// Whether the condition lasts beyond the encounter it was gained in,
+    /// which is when it is removed manually or lasts for a minute or more.
+    #[must_use]
+    pub fn is_long(&self) -> bool {
+        match self {
+            Self::Valued { term: ValuedTerm::Manual, .. } |
+            Self::NonValued { term: NonValuedTerm::Manual, .. } => true,
+            Self::Valued { term: ValuedTerm::For(dur), .. } |
+            Self::NonValued { term: NonValuedTerm::For(dur), .. } => dur.in_minutes() >= 1,
+            _ => false
+        }
+    }
 }
 
 impl PartialEq for Condition {
@@ -104,6 +117,18 @@ pub enum TurnEvent {
     EndOfCurrentTurn(ChrName),
 }
 
+impl TurnEvent {
+    /// Returns the character whose turn triggers the event.
+    #[must_use]
+    pub const fn character(&self) -> &ChrName {
+        match self {
+            Self::StartOfNextTurn(name) |
+            Self::EndOfNextTurn(name) |
+            Self::EndOfCurrentTurn(name) => name,
+        }
+    }
+}
+
 impl Display for TurnEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -139,7 +164,7 @@ impl NonValuedTerm {
     pub fn to_long_string(&self) -> String {
         match self {
             Self::Manual => String::new(),
-            Self::For(dur) => format!("for {} turns", dur.in_turns()),
+            Self::For(dur) => format!("for {dur}"),
             Self::Until(event) => format!("until {event}"),
         }
     }
@@ -171,7 +196,7 @@ impl ValuedTerm {
     pub fn to_long_string(&self) -> String {
         match self {
             Self::Manual => String::new(),
-            Self::For(dur) => format!("for {} turns", dur.in_turns()),
+            Self::For(dur) => format!("for {dur}"),
             Self::Until(event) => format!("until {event}"),
             Self::Reduced(event, r) => format!("reduced by {r} at {event}"),
         }
@@ -183,7 +208,7 @@ impl Display for ValuedTerm {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Manual => write!(f, ""),
-            Self::For(dur) => write!(f, "for {} turns", dur.in_turns()),
+            Self::For(dur) => write!(f, "for {dur}"),
             Self::Until(event) => write!(f, "until {event}"),
             Self::Reduced(event, r) => write!(f, "reduced by {r} at {event}"),
         }
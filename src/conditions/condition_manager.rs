@@ -32,7 +32,9 @@ impl ConditionManager {
             });
 
         if !exists_ge {
-            self.conds.insert((character.clone(), cond.clone()));
+            // Conditions are equal by type alone, so `replace` is needed for
+            // a higher value to take the place of a lower one.
+            self.conds.replace((character.clone(), cond.clone()));
             // Since `new_conds` is only needed to avoid
             // ending `Until` conditions that were added to a
             // character during their own turn,
@@ -41,7 +43,7 @@ impl ConditionManager {
             // every new condition. It's not wrong to add every new condition
             // as long as we only check against those affecting the character
             // in question, but it's unnecessary to add them all.
-            self.new_conds.insert((character, cond));
+            self.new_conds.replace((character, cond));
         }
     }
 
@@ -107,6 +109,40 @@ impl ConditionManager {
         }
     }
 
+    /// Lets the given amount of time pass outside of turn order.
+    ///
+    /// Conditions lasting for a duration have it counted down and end once it
+    /// runs out. Conditions ending or being reduced on a turn event are
+    /// treated as if that event happened once for every full turn in [`dur`].
+    pub fn advance_time(&mut self, dur: Duration) {
+        let turns = dur.in_turns();
+        self.conds = self.conds.clone().into_iter()
+            .filter_map(|(affected, cond)| advance_cond(cond, dur, turns).map(|cond| (affected, cond)))
+            .collect();
+        self.new_conds.clear();
+    }
+
+    /// Applies the effects of a full night's rest to every character.
+    ///
+    /// Resting removes fatigued and reduces drained and doomed by 1.
+    pub fn rest(&mut self) {
+        self.conds = self.conds.clone().into_iter()
+            .filter_map(|(affected, cond)| match cond {
+                Condition::NonValued { cond: NonValuedCondition::Fatigued, .. } => None,
+                Condition::Valued { cond: cond @ (ValuedCondition::Drained | ValuedCondition::Doomed), term, level } =>
+                    reduce(1, level).map(|level| (affected, Condition::Valued { cond, term, level })),
+                cond => Some((affected, cond))
+            })
+            .collect();
+    }
+
+    /// Ends the encounter for the condition manager, keeping only the long
+    /// conditions of the characters for whom [`stays`] holds.
+    pub fn end_encounter(&mut self, stays: impl Fn(&ChrName) -> bool) {
+        self.conds.retain(|(affected, cond)| stays(affected) && cond.is_long());
+        self.new_conds.clear();
+    }
+
     fn handle_turn_event(&mut self, event: &TurnEvent) {
         let new_conds = self
             .conds
@@ -172,13 +208,34 @@ const fn reduce(reduction: u8, level: u8) -> Option<u8> {
     }
 }
 
-fn duration_turn(dur: Duration) -> Option<Duration> {
-    match dur.in_turns() {
-        0 | 1 => None,
-        n => Some(Duration::from_turns(n - 1))
+fn advance_cond(cond: Condition, dur: Duration, turns: u32) -> Option<Condition> {
+    match cond {
+        Condition::Valued { cond, term: ValuedTerm::For(left), level } =>
+            duration_left(left, dur).map(|left| Condition::Valued { cond, term: ValuedTerm::For(left), level }),
+        Condition::NonValued { cond, term: NonValuedTerm::For(left) } =>
+            duration_left(left, dur).map(|left| Condition::NonValued { cond, term: NonValuedTerm::For(left) }),
+        Condition::Valued { term: ValuedTerm::Until(_), .. } |
+        Condition::NonValued { term: NonValuedTerm::Until(_), .. } if turns > 0 =>
+            None,
+        Condition::Valued { cond, term: ValuedTerm::Reduced(event, reduction), level } if turns > 0 => {
+            let total = u32::from(reduction).saturating_mul(turns);
+            reduce(u8::try_from(total).unwrap_or(u8::MAX), level)
+                .map(|level| Condition::Valued { cond, term: ValuedTerm::Reduced(event, reduction), level })
+        },
+        cond => Some(cond)
     }
 }
 
+fn duration_left(left: Duration, passed: Duration) -> Option<Duration> {
+    left.checked_sub(passed).filter(|left| !left.is_zero())
+}
+
+/// Counts down a duration by one round of the affected character, which ends
+/// it once less than a full turn is left.
+fn duration_turn(dur: Duration) -> Option<Duration> {
+    dur.checked_sub(Duration::from_rounds(1)).filter(|left| left.in_turns() > 0)
+}
+
 
 fn cond_step_for_nonvalued(event: &TurnEvent, affected: ChrName, dur: Duration, cond: NonValuedCondition) -> Option<(ChrName, Condition)> {
     match &event {
@@ -0,0 +1,310 @@
+use std::{fmt::Display, str::FromStr};
+
+use rand::{rngs::SmallRng, RngExt, SeedableRng};
+use thiserror::Error;
+
+#[derive(Error)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("empty dice expression")]
+    Empty,
+    #[error("invalid dice term `{0}`: expected a number like `7` or dice like `2d6`")]
+    InvalidTerm(String),
+    #[error("dice term `{0}` has dice without sides")]
+    NoSides(String),
+    #[error("dice term `{0}` has dice with zero sides")]
+    ZeroSides(String),
+    #[error("dice term `{0}` is too large: at most {MAX_DICE} dice with at most {MAX_SIDES} sides can be rolled")]
+    TooLarge(String),
+}
+
+/// The most dice a single term may roll.
+pub const MAX_DICE: u32 = 1000;
+/// The most sides a die may have.
+pub const MAX_SIDES: u32 = 1000;
+
+/// A single term of a dice expression, either some dice or a flat number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Term {
+    Dice { count: u32, sides: u32 },
+    Flat(i32),
+}
+
+impl Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Dice { count, sides } => write!(f, "{count}d{sides}"),
+            Self::Flat(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// A dice expression like `1d20+7`, `2d6+3` or `4d8 fire`.
+///
+/// The expression is a sum of terms, each of which may be subtracted rather
+/// than added, optionally followed by a label such as a damage type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiceExpr {
+    terms: Vec<(bool, Term)>,
+    label: Option<String>,
+}
+
+impl DiceExpr {
+    /// The expression for rolling a d20 with the given modifier, such as for
+    /// initiative. A modifier of [`i32::MIN`] is taken as minus [`i32::MAX`],
+    /// as rolls saturate anyway.
+    #[must_use]
+    pub fn d20(modifier: i32) -> Self {
+        let terms = match modifier {
+            0 => vec![(false, Term::Dice { count: 1, sides: 20 })],
+            m => vec![(false, Term::Dice { count: 1, sides: 20 }), (m < 0, Term::Flat(m.saturating_abs()))],
+        };
+
+        Self { terms, label: None }
+    }
+
+    #[must_use]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+}
+
+impl Display for DiceExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (negative, term)) in self.terms.iter().enumerate() {
+            match (i, negative) {
+                (0, false) => write!(f, "{term}")?,
+                (_, true) => write!(f, "-{term}")?,
+                (_, false) => write!(f, "+{term}")?,
+            }
+        }
+
+        self.label.as_ref().map_or(Ok(()), |label| write!(f, " {label}"))
+    }
+}
+
+fn is_expr_word(word: &str) -> bool {
+    word.chars().all(|c| c.is_ascii_digit() || matches!(c, 'd' | 'D' | '+' | '-'))
+}
+
+fn parse_term(term: &str) -> Result<Term, ParseError> {
+    let invalid = || ParseError::InvalidTerm(term.to_string());
+
+    match term.split_once(['d', 'D']) {
+        Some((count, sides)) => {
+            let count = if count.is_empty() { 1 } else { count.parse().map_err(|_| invalid())? };
+            if sides.is_empty() {
+                return Err(ParseError::NoSides(term.to_string()))
+            }
+            let sides = sides.parse().map_err(|_| invalid())?;
+            if sides == 0 {
+                return Err(ParseError::ZeroSides(term.to_string()))
+            }
+            if count > MAX_DICE || sides > MAX_SIDES {
+                return Err(ParseError::TooLarge(term.to_string()))
+            }
+            Ok(Term::Dice { count, sides })
+        },
+        None => term.parse().map(Term::Flat).map_err(|_| invalid()),
+    }
+}
+
+impl FromStr for DiceExpr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        let split = words.iter().position(|word| !is_expr_word(word)).unwrap_or(words.len());
+        let (expr, label) = words.split_at(split);
+
+        let expr: String = expr.concat();
+        if expr.is_empty() {
+            return Err(ParseError::Empty)
+        }
+
+        let mut terms = Vec::new();
+        let mut negative = false;
+        let mut start = 0;
+        for (i, c) in expr.char_indices().chain(std::iter::once((expr.len(), '+'))) {
+            if c != '+' && c != '-' {
+                continue
+            }
+
+            let term = &expr[start..i];
+            match (term.is_empty(), i) {
+                // A sign in front of the first term.
+                (true, 0) => (),
+                (true, _) => return Err(ParseError::InvalidTerm(expr.clone())),
+                (false, _) => terms.push((negative, parse_term(term)?)),
+            }
+
+            negative = c == '-';
+            start = i + 1;
+        }
+
+        let label = if label.is_empty() { None } else { Some(label.join(" ")) };
+
+        Ok(Self { terms, label })
+    }
+}
+
+/// The outcome of rolling a [`DiceExpr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Roll {
+    pub expr: DiceExpr,
+    /// The result of each die rolled, in the order of the dice in the
+    /// expression.
+    pub dice: Vec<u32>,
+    pub total: i32,
+}
+
+impl Display for Roll {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let dice: Vec<String> = self.dice.iter().map(ToString::to_string).collect();
+        write!(f, "{} = {} [{}]", self.expr, self.total, dice.join(", "))
+    }
+}
+
+/// A dice roller, which may be seeded to roll the same results every time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Roller {
+    rng: SmallRng,
+}
+
+impl Default for Roller {
+    fn default() -> Self {
+        Self { rng: rand::make_rng() }
+    }
+}
+
+impl Roller {
+    #[must_use]
+    pub fn seeded(seed: u64) -> Self {
+        Self { rng: SmallRng::seed_from_u64(seed) }
+    }
+
+    /// Rolls a single die with the given number of sides.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`sides`] is zero, which parsed dice expressions never have.
+    pub fn roll_die(&mut self, sides: u32) -> u32 {
+        self.rng.random_range(1..=sides)
+    }
+
+    pub fn roll(&mut self, expr: &DiceExpr) -> Roll {
+        let mut dice = Vec::new();
+        let mut total: i32 = 0;
+
+        for (negative, term) in &expr.terms {
+            let value = match term {
+                Term::Dice { count, sides } => (0..*count)
+                    .map(|_| self.roll_die(*sides))
+                    .inspect(|die| dice.push(*die))
+                    .fold(0, |acc: i32, die| acc.saturating_add(i32::try_from(die).unwrap_or(i32::MAX))),
+                Term::Flat(n) => *n,
+            };
+
+            total = if *negative { total.saturating_sub(value) } else { total.saturating_add(value) };
+        }
+
+        Roll { expr: expr.clone(), dice, total }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::{DiceExpr, ParseError, Roller, Term};
+
+    #[test]
+    fn d20_plus_7_parses() {
+        let expr: DiceExpr = "1d20+7".parse().unwrap();
+        assert_eq!(vec![(false, Term::Dice { count: 1, sides: 20 }), (false, Term::Flat(7))], expr.terms);
+    }
+
+    #[test]
+    fn spaced_expression_parses() {
+        assert_eq!("2d6+3".parse::<DiceExpr>(), "2d6 + 3".parse());
+    }
+
+    #[test]
+    fn subtraction_parses() {
+        let expr: DiceExpr = "2d6-1".parse().unwrap();
+        assert_eq!((true, Term::Flat(1)), expr.terms[1]);
+    }
+
+    #[test]
+    fn d20_with_min_modifier_saturates() {
+        let expr = DiceExpr::d20(i32::MIN);
+        assert_eq!((true, Term::Flat(i32::MAX)), expr.terms[1]);
+        assert!(Roller::seeded(7).roll(&expr).total < -2_000_000_000);
+    }
+
+    #[test]
+    fn label_parses() {
+        let expr: DiceExpr = "4d8 fire".parse().unwrap();
+        assert_eq!(Some("fire"), expr.label());
+        assert_eq!("4d8 fire", expr.to_string());
+    }
+
+    #[test]
+    fn die_without_count_is_one_die() {
+        let expr: DiceExpr = "d20".parse().unwrap();
+        assert_eq!(vec![(false, Term::Dice { count: 1, sides: 20 })], expr.terms);
+    }
+
+    #[test]
+    fn empty_expression_fails() {
+        assert_eq!(Err(ParseError::Empty), "fire".parse::<DiceExpr>());
+    }
+
+    #[test]
+    fn dice_without_sides_fails() {
+        assert_eq!(Err(ParseError::NoSides(String::from("2d"))), "2d".parse::<DiceExpr>());
+    }
+
+    #[test]
+    fn dice_with_zero_sides_fails() {
+        assert_eq!(Err(ParseError::ZeroSides(String::from("2d0"))), "2d0".parse::<DiceExpr>());
+    }
+
+    #[test]
+    fn too_many_dice_fails() {
+        assert_eq!(Err(ParseError::TooLarge(String::from("1001d6"))), "1001d6".parse::<DiceExpr>());
+        assert_eq!(Err(ParseError::TooLarge(String::from("1d1001"))), "1d1001".parse::<DiceExpr>());
+        assert!("1000d1000".parse::<DiceExpr>().is_ok());
+    }
+
+    #[test]
+    fn double_sign_fails() {
+        assert!("1d20++7".parse::<DiceExpr>().is_err());
+    }
+
+    #[test]
+    fn d20_display_shows_modifier() {
+        assert_eq!("1d20+7", DiceExpr::d20(7).to_string());
+        assert_eq!("1d20-1", DiceExpr::d20(-1).to_string());
+        assert_eq!("1d20", DiceExpr::d20(0).to_string());
+    }
+
+    #[test]
+    fn seeded_rollers_roll_the_same() {
+        let expr: DiceExpr = "10d20+3".parse().unwrap();
+        assert_eq!(Roller::seeded(7).roll(&expr), Roller::seeded(7).roll(&expr));
+    }
+
+    #[test]
+    fn roll_total_adds_dice_and_modifier() {
+        let expr: DiceExpr = "3d6+2".parse().unwrap();
+        let roll = Roller::seeded(1).roll(&expr);
+        assert_eq!(3, roll.dice.len());
+        assert_eq!(roll.dice.iter().sum::<u32>() + 2, roll.total.unsigned_abs());
+    }
+
+    #[test]
+    fn rolls_stay_within_sides() {
+        let mut roller = Roller::seeded(3);
+        assert!((0..1000).map(|_| roller.roll_die(6)).all(|die| (1..=6).contains(&die)));
+    }
+}
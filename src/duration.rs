@@ -1,7 +1,42 @@
+use std::{fmt::Display, str::FromStr};
+
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 type Num = u32;
 
+#[derive(Error)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("empty duration")]
+    Empty,
+    #[error("missing unit after `{0}`")]
+    MissingUnit(Num),
+    #[error("missing number before `{0}`")]
+    MissingNumber(String),
+    #[error("invalid duration unit `{0}`: expected s, a, r, t, m, h or d")]
+    InvalidUnit(String),
+    #[error("expected `{arg}` to be a number")]
+    ParseInt {
+        arg: String,
+        #[source] source: std::num::ParseIntError,
+    },
+}
+
+const SECONDS_PER_ACTION: u64 = 2;
+const SECONDS_PER_TURN: u64 = 6;
+const SECONDS_PER_MINUTE: u64 = 60;
+const SECONDS_PER_HOUR: u64 = 60 * SECONDS_PER_MINUTE;
+const SECONDS_PER_DAY: u64 = 24 * SECONDS_PER_HOUR;
+
+/// An amount of game time.
+///
+/// Time is kept in seconds, actions, turns, minutes, hours and days, which are
+/// normalized into each other, where a turn is 6 seconds. Besides those,
+/// a duration can be given in rounds, which are counted in turns of the
+/// affected character rather than in time. Rounds are never normalized into
+/// the other units, but count as a turn of 6 seconds when time passes outside
+/// of combat.
 #[derive(Debug, Clone, Copy, Default)]
 #[derive(PartialEq, Eq, PartialOrd, Ord)]
 #[derive(Serialize, Deserialize, Hash)]
@@ -11,10 +46,23 @@ pub struct Duration {
     turns: Num,
     minutes: Num,
     hours: Num,
-    days: Num
+    days: Num,
+    #[serde(default)]
+    rounds: Num
 }
 
 impl Duration {
+    /// The longest duration there is. Arithmetic saturating upwards ends here.
+    pub const MAX: Self = Self {
+        seconds: 1,
+        actions: 2,
+        turns: 9,
+        minutes: 59,
+        hours: 23,
+        days: Num::MAX,
+        rounds: Num::MAX
+    };
+
     #[must_use]
     pub fn builder() -> Builder {
         Builder::default()
@@ -35,6 +83,11 @@ impl Duration {
         Self::builder().with_turns(n).build()
     }
 
+    #[must_use]
+    pub fn from_rounds(n: Num) -> Self {
+        Self::builder().with_rounds(n).build()
+    }
+
     #[must_use]
     pub fn from_minutes(n: Num) -> Self {
         Self::builder().with_minutes(n).build()
@@ -50,69 +103,217 @@ impl Duration {
         Self::builder().with_days(n).build()
     }
 
+    /// Creates a duration from an amount of time in seconds and a number of
+    /// rounds, or `None` if the time is too long to be kept in days.
+    fn from_parts(seconds: u64, rounds: Num) -> Option<Self> {
+        let days = Num::try_from(seconds / SECONDS_PER_DAY).ok()?;
+        let rest = Num::try_from(seconds % SECONDS_PER_DAY).ok()?;
+
+        Some(Self::builder().with_days(days).with_seconds(rest).with_rounds(rounds).build())
+    }
+
+    /// The time of the duration in seconds, not counting the rounds.
+    fn time_in_seconds(self) -> u64 {
+        u64::from(self.seconds)
+            + u64::from(self.actions) * SECONDS_PER_ACTION
+            + u64::from(self.turns) * SECONDS_PER_TURN
+            + u64::from(self.minutes) * SECONDS_PER_MINUTE
+            + u64::from(self.hours) * SECONDS_PER_HOUR
+            + u64::from(self.days) * SECONDS_PER_DAY
+    }
+
+    /// The whole duration in seconds, counting each round as a turn.
+    fn total_seconds(self) -> u64 {
+        self.time_in_seconds() + u64::from(self.rounds) * SECONDS_PER_TURN
+    }
+
+    fn in_unit(self, unit: u64) -> Num {
+        Num::try_from(self.total_seconds() / unit).unwrap_or(Num::MAX)
+    }
+
+    /// The duration in whole seconds, counting each round as a turn and
+    /// saturating at [`u32::MAX`].
+    #[must_use]
+    pub fn in_seconds(self) -> Num {
+        self.in_unit(1)
+    }
+
+    #[must_use]
+    pub fn in_actions(self) -> Num {
+        self.in_unit(SECONDS_PER_ACTION)
+    }
+
     #[must_use]
-    pub const fn in_seconds(self) -> Num {
-        self.seconds + 2 * self.in_actions()
+    pub fn in_turns(self) -> Num {
+        self.in_unit(SECONDS_PER_TURN)
     }
 
     #[must_use]
-    pub const fn in_actions(self) -> Num {
-        self.actions + 3 * self.in_turns()
+    pub const fn in_rounds(self) -> Num {
+        self.rounds
     }
 
     #[must_use]
-    pub const fn in_turns(self) -> Num {
-        self.turns + 10 * self.in_minutes()
+    pub fn in_minutes(self) -> Num {
+        self.in_unit(SECONDS_PER_MINUTE)
     }
 
     #[must_use]
-    pub const fn in_minutes(self) -> Num {
-        self.minutes + 60 * self.in_hours()
+    pub fn in_hours(self) -> Num {
+        self.in_unit(SECONDS_PER_HOUR)
     }
 
     #[must_use]
-    pub const fn in_hours(self) -> Num {
-        self.hours + 24 * self.in_days()
+    pub fn in_days(self) -> Num {
+        self.in_unit(SECONDS_PER_DAY)
     }
 
     #[must_use]
-    pub const fn in_days(self) -> Num {
-        self.days
+    pub fn is_zero(self) -> bool {
+        self.total_seconds() == 0
     }
 
+    /// Adds two durations, keeping the rounds of both apart from their time.
+    /// Returns `None` if the result is too long to be kept.
+    #[must_use]
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let rounds = self.rounds.checked_add(rhs.rounds)?;
+        let seconds = self.time_in_seconds() + rhs.time_in_seconds();
+
+        Self::from_parts(seconds, rounds)
+    }
+
+    /// Subtracts a duration from another.
+    ///
+    /// Rounds are taken from rounds first. Whatever is left is taken from the
+    /// time, and once the time runs out, from the remaining rounds as 6 seconds
+    /// each. Returns `None` if `rhs` is longer than `self`.
     #[must_use]
     pub fn checked_sub(self, rhs: Self) -> Option<Self> {
-        let x = self.in_seconds();
-        let y = rhs.in_seconds();
+        let shared_rounds = self.rounds.min(rhs.rounds);
+        let mut rounds = self.rounds - shared_rounds;
+        let mut seconds = self.time_in_seconds();
+        let taken = rhs.time_in_seconds() + u64::from(rhs.rounds - shared_rounds) * SECONDS_PER_TURN;
+
+        if seconds < taken {
+            let missing = (taken - seconds).div_ceil(SECONDS_PER_TURN);
+            let missing = Num::try_from(missing).ok().filter(|missing| *missing <= rounds)?;
+            rounds -= missing;
+            seconds += u64::from(missing) * SECONDS_PER_TURN;
+        }
 
-        x.checked_sub(y).map(Self::from_seconds)
+        Self::from_parts(seconds - taken, rounds)
+    }
+
+    #[must_use]
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        self.checked_add(rhs).unwrap_or(Self::MAX)
     }
 
     #[must_use]
     pub fn saturating_sub(self, rhs: Self) -> Self {
-        self.checked_sub(rhs).unwrap_or_else(|| Self::from_seconds(0))
+        self.checked_sub(rhs).unwrap_or_default()
     }
 }
 
+/// Saturates at [`Duration::MAX`] rather than overflowing.
 impl std::ops::Add for Duration {
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let x = self.in_seconds();
-        let y = rhs.in_seconds();
-
-        Self::from_seconds(x + y)
+        self.saturating_add(rhs)
     }
 }
 
+/// Saturates at a duration of nothing rather than underflowing.
 impl std::ops::Sub for Duration {
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let x = self.in_seconds();
-        let y = rhs.in_seconds();
+        self.saturating_sub(rhs)
+    }
+}
+
+/// Shows the duration in the largest units it has been normalized to, such as
+/// `1 h 10 min` or `2 rounds`, with any rounds last. A duration of nothing is shown as `0 rounds`.
+impl Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let plural = |n: Num| if n == 1 { "" } else { "s" };
+        let parts: Vec<String> = [
+            (self.days, format!("{} d", self.days)),
+            (self.hours, format!("{} h", self.hours)),
+            (self.minutes, format!("{} min", self.minutes)),
+            (self.turns, format!("{} turn{}", self.turns, plural(self.turns))),
+            (self.actions, format!("{} action{}", self.actions, plural(self.actions))),
+            (self.seconds, format!("{} s", self.seconds)),
+            (self.rounds, format!("{} round{}", self.rounds, plural(self.rounds))),
+        ]
+            .into_iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(_, part)| part)
+            .collect();
+
+        if parts.is_empty() {
+            write!(f, "0 rounds")
+        } else {
+            write!(f, "{}", parts.join(" "))
+        }
+    }
+}
+
+/// Parses a duration made up of one or more amounts of a unit, like `3 rounds`,
+/// `1h30m` or `1 h 10 min`. Amounts given in the same unit are added together.
+impl FromStr for Duration {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut raw = Builder::default();
+        let mut rest = s.trim_start();
+
+        if rest.is_empty() {
+            return Err(ParseError::Empty)
+        }
+
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+            let (number, tail) = rest.split_at(end);
+            let tail = tail.trim_start();
+
+            let end = tail.find(|c: char| c.is_ascii_digit() || c.is_whitespace()).unwrap_or(tail.len());
+            let (unit, tail) = tail.split_at(end);
+
+            if number.is_empty() {
+                return Err(ParseError::MissingNumber(unit.to_string()))
+            }
+
+            let number: Num = number.parse().map_err(|err| ParseError::ParseInt {
+                arg: number.to_string(),
+                source: err
+            })?;
+
+            raw = match unit.to_lowercase().as_str() {
+                "" => Err(ParseError::MissingUnit(number))?,
+                "s" | "sec" | "secs" | "second" | "seconds" =>
+                    raw.with_seconds(raw.seconds.unwrap_or(0).saturating_add(number)),
+                "a" | "action" | "actions" =>
+                    raw.with_actions(raw.actions.unwrap_or(0).saturating_add(number)),
+                "t" | "turn" | "turns" =>
+                    raw.with_turns(raw.turns.unwrap_or(0).saturating_add(number)),
+                "r" | "round" | "rounds" =>
+                    raw.with_rounds(raw.rounds.unwrap_or(0).saturating_add(number)),
+                "m" | "min" | "mins" | "minute" | "minutes" =>
+                    raw.with_minutes(raw.minutes.unwrap_or(0).saturating_add(number)),
+                "h" | "hour" | "hours" =>
+                    raw.with_hours(raw.hours.unwrap_or(0).saturating_add(number)),
+                "d" | "day" | "days" =>
+                    raw.with_days(raw.days.unwrap_or(0).saturating_add(number)),
+                unit => Err(ParseError::InvalidUnit(unit.to_string()))?,
+            };
+
+            rest = tail.trim_start();
+        }
 
-        Self::from_seconds(x - y)
+        Ok(raw.build())
     }
 }
 
@@ -125,7 +326,8 @@ pub struct Builder {
     turns: Option<Num>,
     minutes: Option<Num>,
     hours: Option<Num>,
-    days: Option<Num>
+    days: Option<Num>,
+    rounds: Option<Num>
 }
 
 impl Builder {
@@ -137,10 +339,11 @@ impl Builder {
         let minutes  = self.minutes.unwrap_or(0);
         let hours    = self.hours.unwrap_or(0);
         let days     = self.days.unwrap_or(0);
+        let rounds   = self.rounds.unwrap_or(0);
 
         let raw = RawDuration { seconds, actions, turns, minutes, hours, days };
 
-        raw.normalize()
+        Duration { rounds, ..raw.normalize() }
     }
 
     #[must_use]
@@ -158,6 +361,11 @@ impl Builder {
         Self { turns: Some(n), ..self }
     }
     
+    #[must_use]
+    pub const fn with_rounds(self, n: Num) -> Self {
+        Self { rounds: Some(n), ..self }
+    }
+
     #[must_use]
     pub const fn with_minutes(self, n: Num) -> Self {
         Self { minutes: Some(n), ..self }
@@ -198,44 +406,45 @@ impl RawDuration {
             turns: normal.turns,
             minutes: normal.minutes,
             hours: normal.hours,
-            days: normal.days
+            days: normal.days,
+            rounds: 0
         }
     }
 
     const fn normalize_seconds(self) -> Self {
         let seconds = self.seconds % 2;
-        let actions = self.actions + self.seconds / 2;
+        let actions = self.actions.saturating_add(self.seconds / 2);
         Self { seconds, actions, ..self }
     }
 
     const fn normalize_actions(self) -> Self {
         let actions = self.actions % 3;
-        let turns = self.turns + self.actions / 3;
+        let turns = self.turns.saturating_add(self.actions / 3);
         Self { actions, turns, ..self }
     }
 
     const fn normalize_turns(self) -> Self {
         let turns = self.turns % 10;
-        let minutes = self.minutes + self.turns / 10;
+        let minutes = self.minutes.saturating_add(self.turns / 10);
         Self { turns, minutes, ..self }
     }
 
     const fn normalize_minutes(self) -> Self {
         let minutes = self.minutes % 60;
-        let hours = self.hours + self.minutes / 60;
+        let hours = self.hours.saturating_add(self.minutes / 60);
         Self { minutes, hours, ..self }
     }
 
     const fn normalize_hours(self) -> Self {
         let hours = self.hours % 24;
-        let days = self.days + self.hours / 24;
+        let days = self.days.saturating_add(self.hours / 24);
         Self { hours, days, ..self }
     }
 }
 
 #[cfg(test)]
 mod tests{
-    use super::Duration;
+    use super::{Duration, ParseError};
 
     #[test]
     fn duration_from_5_turns_gives_duration_of_5_turns() {
@@ -266,4 +475,121 @@ mod tests{
     fn duration_from_1_day_gives_duration_of_14_400_turns() {
         assert_eq!(14_400, Duration::from_days(1).in_turns());
     }
+
+    #[test]
+    fn duration_of_70_minutes_displays_as_1_h_10_min() {
+        assert_eq!("1 h 10 min", Duration::from_minutes(70).to_string());
+    }
+
+    #[test]
+    fn duration_of_2_rounds_displays_as_2_rounds() {
+        assert_eq!("2 rounds", Duration::from_rounds(2).to_string());
+    }
+
+    #[test]
+    fn duration_of_1_turn_displays_as_1_turn() {
+        assert_eq!("1 turn", Duration::from_turns(1).to_string());
+    }
+
+    #[test]
+    fn empty_duration_displays_as_0_rounds() {
+        assert_eq!("0 rounds", Duration::default().to_string());
+    }
+
+    #[test]
+    fn compact_compound_duration_parses() {
+        let expected = Duration::builder().with_hours(1).with_minutes(30).build();
+        assert_eq!(Ok(expected), "1h30m".parse());
+    }
+
+    #[test]
+    fn spaced_duration_parses() {
+        assert_eq!(Ok(Duration::from_rounds(3)), "3 rounds".parse());
+    }
+
+    #[test]
+    fn displayed_duration_parses_back() {
+        let dur = Duration::builder().with_days(2).with_minutes(5).with_turns(1).with_actions(2).with_rounds(3).build();
+        assert_eq!(Ok(dur), dur.to_string().parse());
+    }
+
+    #[test]
+    fn repeated_unit_adds_up() {
+        assert_eq!(Ok(Duration::from_minutes(15)), "10m 5m".parse());
+    }
+
+    #[test]
+    fn number_without_unit_fails() {
+        assert_eq!(Err(ParseError::MissingUnit(5)), "5".parse::<Duration>());
+    }
+
+    #[test]
+    fn unit_without_number_fails() {
+        assert_eq!(Err(ParseError::MissingNumber(String::from("h"))), "h".parse::<Duration>());
+    }
+
+    #[test]
+    fn unknown_unit_fails() {
+        assert_eq!(Err(ParseError::InvalidUnit(String::from("weeks"))), "2 weeks".parse::<Duration>());
+    }
+
+    #[test]
+    fn empty_input_fails() {
+        assert_eq!(Err(ParseError::Empty), "  ".parse::<Duration>());
+    }
+
+    #[test]
+    fn subtracting_longer_duration_fails() {
+        assert_eq!(None, Duration::from_turns(1).checked_sub(Duration::from_minutes(1)));
+    }
+
+    #[test]
+    fn subtracting_longer_duration_saturates_at_zero() {
+        assert_eq!(Duration::default(), Duration::from_turns(1) - Duration::from_minutes(1));
+    }
+
+    #[test]
+    fn adding_past_max_fails() {
+        assert_eq!(None, Duration::MAX.checked_add(Duration::from_seconds(1)));
+    }
+
+    #[test]
+    fn adding_past_max_saturates_at_max() {
+        assert_eq!(Duration::MAX, Duration::from_days(u32::MAX) + Duration::from_days(1));
+    }
+
+    #[test]
+    fn many_days_in_seconds_saturates() {
+        assert_eq!(u32::MAX, Duration::from_days(100_000).in_seconds());
+    }
+
+    #[test]
+    fn rounds_are_not_normalized_into_minutes() {
+        let dur = Duration::from_rounds(10);
+        assert_eq!(10, dur.in_rounds());
+        assert_ne!(Duration::from_minutes(1), dur);
+    }
+
+    #[test]
+    fn round_counts_as_a_turn_of_time() {
+        assert_eq!(6, Duration::from_rounds(1).in_seconds());
+    }
+
+    #[test]
+    fn subtracting_a_round_takes_a_round_first() {
+        let dur = Duration::builder().with_minutes(1).with_rounds(2).build();
+        let expected = Duration::builder().with_minutes(1).with_rounds(1).build();
+        assert_eq!(Some(expected), dur.checked_sub(Duration::from_rounds(1)));
+    }
+
+    #[test]
+    fn subtracting_a_round_from_time_takes_a_turn() {
+        assert_eq!(Some(Duration::from_turns(9)), Duration::from_minutes(1).checked_sub(Duration::from_rounds(1)));
+    }
+
+    #[test]
+    fn subtracting_time_from_rounds_uses_up_whole_rounds() {
+        let expected = Duration::builder().with_seconds(4).with_rounds(1).build();
+        assert_eq!(Some(expected), Duration::from_rounds(2).checked_sub(Duration::from_seconds(2)));
+    }
 }
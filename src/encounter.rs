@@ -0,0 +1,275 @@
+use std::fmt::Display;
+
+use crate::character::Chr;
+
+/// The party size the XP budgets of the threat levels are given for.
+pub const BASE_PARTY_SIZE: u32 = 4;
+
+/// How dangerous an encounter is to the party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Threat {
+    Trivial,
+    Low,
+    Moderate,
+    Severe,
+    Extreme,
+}
+
+impl Display for Threat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Trivial => write!(f, "Trivial"),
+            Self::Low => write!(f, "Low"),
+            Self::Moderate => write!(f, "Moderate"),
+            Self::Severe => write!(f, "Severe"),
+            Self::Extreme => write!(f, "Extreme"),
+        }
+    }
+}
+
+impl Threat {
+    pub const ALL: [Self; 5] = [Self::Trivial, Self::Low, Self::Moderate, Self::Severe, Self::Extreme];
+
+    /// The XP budget of the threat level for a party of [`BASE_PARTY_SIZE`].
+    #[must_use]
+    pub const fn base_budget(self) -> u32 {
+        match self {
+            Self::Trivial => 40,
+            Self::Low => 60,
+            Self::Moderate => 80,
+            Self::Severe => 120,
+            Self::Extreme => 160,
+        }
+    }
+
+    /// The XP added to or removed from the budget for each character more or
+    /// less than [`BASE_PARTY_SIZE`].
+    #[must_use]
+    pub const fn character_adjustment(self) -> u32 {
+        match self {
+            Self::Trivial => 10,
+            Self::Low => 15,
+            Self::Moderate => 20,
+            Self::Severe => 30,
+            Self::Extreme => 40,
+        }
+    }
+
+    /// The XP budget of the threat level for a party of [`party_size`].
+    #[must_use]
+    pub const fn budget(self, party_size: u32) -> u32 {
+        let base = self.base_budget();
+        let adjustment = self.character_adjustment();
+        if party_size >= BASE_PARTY_SIZE {
+            base + (party_size - BASE_PARTY_SIZE) * adjustment
+        } else {
+            base.saturating_sub((BASE_PARTY_SIZE - party_size) * adjustment)
+        }
+    }
+
+    /// The threat of an encounter worth [`xp`] to a party of [`party_size`],
+    /// which is the highest threat level whose budget it reaches, or trivial
+    /// if it reaches none.
+    #[must_use]
+    pub fn from_xp(xp: u32, party_size: u32) -> Self {
+        Self::ALL.into_iter()
+            .rfind(|threat| xp >= threat.budget(party_size))
+            .unwrap_or(Self::Trivial)
+    }
+}
+
+/// The XP a creature of [`creature_level`] is worth to a party of
+/// [`party_level`].
+///
+/// Creatures more than 4 levels below the party are worth nothing, while
+/// creatures more than 4 levels above it are worth as much as one 4 levels
+/// above, which is already an extreme threat on its own.
+#[must_use]
+pub const fn creature_xp(creature_level: i32, party_level: i32) -> u32 {
+    match creature_level.saturating_sub(party_level) {
+        ..=-5 => 0,
+        -4 => 10,
+        -3 => 15,
+        -2 => 20,
+        -1 => 30,
+        0 => 40,
+        1 => 60,
+        2 => 80,
+        3 => 120,
+        4.. => 160,
+    }
+}
+
+/// The level and size of the party, made up of the player characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Party {
+    pub level: i32,
+    pub size: u32,
+}
+
+impl Party {
+    /// The party of the player characters among [`chrs`], whose level is the
+    /// average level of those with a level, rounded to the nearest.
+    ///
+    /// Returns `None` if none of the player characters have a level.
+    pub fn from_chrs<'a>(chrs: impl IntoIterator<Item = &'a Chr>) -> Option<Self> {
+        let players: Vec<&Chr> = chrs.into_iter().filter(|chr| chr.player).collect();
+        let levels: Vec<i32> = players.iter().filter_map(|chr| chr.level).collect();
+        if levels.is_empty() {
+            return None
+        }
+
+        let count = i32::try_from(levels.len()).ok()?;
+        let sum: i32 = levels.iter().sum();
+        let level = (2 * sum + count).div_euclid(2 * count);
+        let size = u32::try_from(players.len()).ok()?;
+
+        Some(Self { level, size })
+    }
+}
+
+/// The XP and threat of an encounter to a party.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rating {
+    pub party: Party,
+    pub xp: u32,
+    pub threat: Threat,
+    /// The number of creatures without a level, which aren't counted.
+    pub unknown: usize,
+}
+
+impl Rating {
+    /// Rates the encounter of the non-player characters among [`chrs`] for
+    /// [`party`].
+    pub fn new<'a>(party: Party, chrs: impl IntoIterator<Item = &'a Chr>) -> Self {
+        let creatures: Vec<&Chr> = chrs.into_iter().filter(|chr| !chr.player).collect();
+        let xp = creatures.iter()
+            .filter_map(|chr| chr.level)
+            .map(|level| creature_xp(level, party.level))
+            .sum();
+        let unknown = creatures.iter().filter(|chr| chr.level.is_none()).count();
+
+        Self { party, xp, threat: Threat::from_xp(xp, party.size), unknown }
+    }
+}
+
+impl Display for Rating {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} XP, {} for a party of {} at level {}", self.xp, self.threat, self.party.size, self.party.level)?;
+        match self.unknown {
+            0 => Ok(()),
+            1 => write!(f, " (1 creature without a level isn't counted)"),
+            n => write!(f, " ({n} creatures without a level aren't counted)"),
+        }
+    }
+}
+
+/// An encounter planned ahead of time, which can be added to the tracker in
+/// one go.
+///
+/// Each entry is a creature and how many of it there are, named like with
+/// [`crate::tracker::Tracker::add_many`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Encounter {
+    entries: Vec<(Chr, u32)>,
+}
+
+impl Encounter {
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    #[must_use]
+    pub fn with(mut self, chr: Chr, count: u32) -> Self {
+        self.add(chr, count);
+        self
+    }
+
+    pub fn add(&mut self, chr: Chr, count: u32) {
+        self.entries.push((chr, count));
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.entries.remove(index);
+        }
+    }
+
+    #[must_use]
+    pub fn get_entries(&self) -> &[(Chr, u32)] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Rates the encounter for [`party`], counting every creature of each
+    /// entry.
+    #[must_use]
+    pub fn rate(&self, party: Party) -> Rating {
+        let chrs = self.entries.iter()
+            .flat_map(|(chr, count)| std::iter::repeat_n(chr, usize::try_from(*count).unwrap_or(0)));
+        Rating::new(party, chrs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::character::Chr;
+
+    use super::{creature_xp, Party, Rating, Threat};
+
+    #[test]
+    fn creature_xp_follows_level_difference() {
+        assert_eq!(0, creature_xp(0, 5));
+        assert_eq!(10, creature_xp(1, 5));
+        assert_eq!(40, creature_xp(5, 5));
+        assert_eq!(160, creature_xp(9, 5));
+        assert_eq!(160, creature_xp(12, 5));
+    }
+
+    #[test]
+    fn budget_adjusts_for_party_size() {
+        assert_eq!(80, Threat::Moderate.budget(4));
+        assert_eq!(100, Threat::Moderate.budget(5));
+        assert_eq!(45, Threat::Low.budget(3));
+    }
+
+    #[test]
+    fn threat_is_highest_budget_reached() {
+        assert_eq!(Threat::Trivial, Threat::from_xp(0, 4));
+        assert_eq!(Threat::Trivial, Threat::from_xp(59, 4));
+        assert_eq!(Threat::Low, Threat::from_xp(60, 4));
+        assert_eq!(Threat::Low, Threat::from_xp(79, 4));
+        assert_eq!(Threat::Moderate, Threat::from_xp(80, 4));
+        assert_eq!(Threat::Moderate, Threat::from_xp(119, 4));
+        assert_eq!(Threat::Severe, Threat::from_xp(121, 4));
+        assert_eq!(Threat::Extreme, Threat::from_xp(160, 4));
+        assert_eq!(Threat::Extreme, Threat::from_xp(400, 4));
+    }
+
+    #[test]
+    fn party_level_is_rounded_average() {
+        let chrs = [
+            Chr::builder("A", 0, true).with_level(3).build(),
+            Chr::builder("B", 0, true).with_level(4).build(),
+            Chr::builder("C", 0, true).build(),
+            Chr::builder("Orc", 0, false).with_level(9).build(),
+        ];
+        assert_eq!(Some(Party { level: 4, size: 3 }), Party::from_chrs(&chrs));
+    }
+
+    #[test]
+    fn rating_counts_creatures_without_level_as_unknown() {
+        let party = Party { level: 1, size: 4 };
+        let chrs = [
+            Chr::builder("Wolf", 0, false).with_level(1).build(),
+            Chr::builder("Bat", 0, false).build(),
+        ];
+        let rating = Rating::new(party, &chrs);
+        assert_eq!((40, Threat::Trivial, 1), (rating.xp, rating.threat, rating.unknown));
+    }
+}
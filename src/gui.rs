@@ -1,4 +1,15 @@
+use crate::duration::Duration;
+
 pub mod terminalgui;
 pub mod windowgui;
+pub mod order;
+pub mod protocol;
 
 pub type Result<E> = std::result::Result<(), E>;
+
+/// Formats the world clock of a tracker as the day and time of day, counting
+/// from the first day at midnight.
+#[must_use]
+pub fn format_clock(clock: Duration) -> String {
+    format!("day {}, {:02}:{:02}", clock.in_days() + 1, clock.in_hours() % 24, clock.in_minutes() % 60)
+}
@@ -0,0 +1,96 @@
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::{character::Health, conditions::CondFormat, saver::Saver, tracker::Tracker};
+
+use super::format_clock;
+
+/// The initiative order of a tracker as plain data, for printing it as text
+/// or JSON outside of an interface.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Order {
+    /// The world clock, as given by [`format_clock`].
+    pub clock: String,
+    pub chrs: Vec<Entry>,
+}
+
+/// A character in the [`Order`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub name: String,
+    pub init: i32,
+    pub player: bool,
+    pub in_turn: bool,
+    pub health: Option<Health>,
+    /// The conditions of the character, sorted by name.
+    pub conditions: Vec<String>,
+    /// The modifiers the conditions give, if any.
+    pub modifiers: Option<String>,
+}
+
+impl Order {
+    #[must_use]
+    pub fn new<S: Saver>(t: &Tracker<S>) -> Self {
+        let format = CondFormat::default().set_version(t.get_pf2e_version_setting());
+        let chrs = t.get_chrs().iter().map(|chr| {
+            let mut conditions: Vec<String> = t.get_conditions(&chr.name).into_iter()
+                .map(|c| c.to_string(format).trim_end().to_string())
+                .collect();
+            conditions.sort();
+            let modifiers = t.get_modifiers(&chr.name);
+
+            Entry {
+                name: chr.name.to_string(),
+                init: chr.init,
+                player: chr.player,
+                in_turn: t.get_in_turn_slot().contains(chr),
+                health: chr.health.clone(),
+                conditions,
+                modifiers: (!modifiers.is_empty()).then(|| modifiers.to_string()),
+            }
+        }).collect();
+
+        Self { clock: format_clock(t.get_clock()), chrs }
+    }
+}
+
+/// Writes the order as a table, one character per line, like the table of
+/// the terminal GUI.
+impl Display for Order {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name_width = self.chrs.iter()
+            .map(|entry| entry.name.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+
+        writeln!(f, "  Init P {:<name_width$} {:>11} Condition(s)", "Name", "HP")?;
+        for entry in &self.chrs {
+            let mut conds = entry.conditions.clone();
+            conds.extend(entry.modifiers.as_ref().map(|modifiers| format!("({modifiers})")));
+
+            let line = format!(
+                "{:<1} {:>4} {:<1} {:<name_width$} {:>11} {}",
+                if entry.in_turn { ">" } else { "" },
+                entry.init,
+                if entry.player { "*" } else { "" },
+                entry.name,
+                entry.health.as_ref().map_or_else(|| "---".into(), health_text),
+                conds.join(", "),
+            );
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        write!(f, "Time: {}", self.clock)
+    }
+}
+
+/// The health of a character as `current+temp/max`, with the max HP lost to
+/// drained in parentheses.
+#[must_use]
+pub fn health_text(health: &Health) -> String {
+    let max = if health.drained > 0 { format!("{}(-{})", health.max, health.drained) } else { health.max.to_string() };
+    let temp = if health.temp > 0 { format!("+{}", health.temp) } else { String::new() };
+    format!("{}{temp}/{max}", health.current)
+}
+
@@ -0,0 +1,318 @@
+use std::{io::{self, BufRead, Write}, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{bestiary::Variant, character::{Chr, ChrName, Health}, conditions::Condition, dice::DiceExpr, duration, saver::Saver, settings::Pf2eVersion, tracker::{self, MovedStatus, Tracker}};
+
+use super::{order::Order, terminalgui};
+
+/// A request, one per line, such as
+/// `{"op":"damage","name":"Goblin","amount":5}`. The [`id`] is given back
+/// with the answer, so answers can be told apart from events.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Envelope {
+    #[serde(default)]
+    pub id: Option<Value>,
+    #[serde(flatten)]
+    pub request: Request,
+}
+
+/// The operations of the protocol, one for each operation of [`Tracker`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Request {
+    /// Gives the state without changing anything.
+    State,
+    EndTurn,
+    Undo,
+    Redo,
+    Clear,
+    Add {
+        name: String,
+        init: i32,
+        #[serde(default)]
+        player: bool,
+        health: Option<u32>,
+        level: Option<i32>,
+        init_mod: Option<i32>,
+        /// Adds a group of this many members sharing one turn.
+        group: Option<u32>,
+        /// Adds this many characters with their own turns.
+        count: Option<u32>,
+        variant: Option<Variant>,
+    },
+    AddFromTemplate {
+        template: String,
+        #[serde(default = "one")]
+        count: u32,
+        variant: Option<Variant>,
+    },
+    Remove { name: ChrName },
+    RemoveGroup { group: String },
+    Rename { name: ChrName, new_name: String },
+    ChangeInit { name: ChrName, init: i32 },
+    SetPlayer { name: ChrName, player: bool },
+    SetLevel { name: ChrName, level: Option<i32> },
+    SetInitMod { name: ChrName, init_mod: Option<i32> },
+    SetMaxHealth { name: ChrName, max: u32 },
+    SetHealth { name: ChrName, hp: u32 },
+    SetTempHealth { name: ChrName, hp: u32 },
+    AddTempHealth { name: ChrName, amount: u32 },
+    Damage { name: ChrName, amount: u32 },
+    Heal { name: ChrName, amount: u32 },
+    /// Adds a condition, given as in the save files, to the characters.
+    AddCondition { names: Vec<ChrName>, condition: Condition },
+    RemoveCondition { name: ChrName, condition: Condition },
+    /// Lets the time pass, given as amounts of each unit, like
+    /// `{"hours":1,"minutes":30}`.
+    AdvanceTime { duration: duration::Builder },
+    Rest,
+    Refocus,
+    Roll { expr: String },
+    /// Rolls initiative for the characters, or for every enemy if none are
+    /// given.
+    RollInitiative {
+        #[serde(default)]
+        names: Vec<ChrName>,
+    },
+    Threat,
+    NewEncounter { party: Vec<Member> },
+    ImportPathbuilder { path: PathBuf },
+    ImportFoundry { path: PathBuf },
+    ExportFoundry { path: PathBuf },
+    SetVersion { version: Pf2eVersion },
+    SetUndoSize { size: usize },
+    /// Runs a command of the terminal GUI, like `cond add prone on Goblin`.
+    Command { text: String },
+}
+
+/// A party member joining a new encounter.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Member {
+    pub name: ChrName,
+    pub init: i32,
+}
+
+const fn one() -> u32 {
+    1
+}
+
+/// A line written back, either answering a request or telling of a change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// The request succeeded, giving back what the operation returns, if
+    /// anything, and the state afterwards.
+    Result { id: Option<Value>, value: Value, state: Order },
+    Error { id: Option<Value>, code: ErrorCode, message: String },
+    /// The turn moved to other characters, sent unasked for.
+    Turn { slot: Vec<String> },
+}
+
+/// What went wrong with a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// The line isn't a valid request.
+    InvalidRequest,
+    /// The text of a `command` request isn't a valid terminal command.
+    InvalidCommand,
+    InvalidDice,
+    DuplicateName,
+    EmptyGroup,
+    TooMany,
+    NoSuchCharacter,
+    NoSuchTemplate,
+    NothingToUndo,
+    NothingToRedo,
+    Save,
+    Import,
+    Io,
+}
+
+impl From<&tracker::Error> for ErrorCode {
+    fn from(value: &tracker::Error) -> Self {
+        match value {
+            tracker::Error::AddDupError(_) | tracker::Error::RenameDupError { .. } => Self::DuplicateName,
+            tracker::Error::RmNoneError(_) | tracker::Error::ChangeNoneError(_) => Self::NoSuchCharacter,
+            tracker::Error::EmptyGroupError(_) => Self::EmptyGroup,
+            tracker::Error::TooManyError(_) => Self::TooMany,
+            tracker::Error::NoTemplateError(_) => Self::NoSuchTemplate,
+            tracker::Error::UndoNothingError => Self::NothingToUndo,
+            tracker::Error::RedoNothingError => Self::NothingToRedo,
+            tracker::Error::LoadError(_) => Self::Save,
+            tracker::Error::ImportError(_) => Self::Import,
+        }
+    }
+}
+
+impl From<&terminalgui::Error> for ErrorCode {
+    fn from(value: &terminalgui::Error) -> Self {
+        match value {
+            terminalgui::Error::ParseError(_) => Self::InvalidCommand,
+            terminalgui::Error::IoError(_) => Self::Io,
+            terminalgui::Error::TrackerError(err) => err.into(),
+        }
+    }
+}
+
+/// Answers requests read from [`input`], one per line, writing the answers
+/// and events to [`output`] as one JSON object per line, until [`input`]
+/// ends.
+///
+/// # Errors
+///
+/// This function will return an error if reading or writing fails.
+pub fn run<S: Saver>(t: &mut Tracker<S>, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+
+        for msg in handle(t, &line) {
+            serde_json::to_writer(&mut output, &msg)?;
+            writeln!(output)?;
+        }
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Answers the request on [`line`], followed by a [`Message::Turn`] if the
+/// turn moved.
+pub fn handle<S: Saver>(t: &mut Tracker<S>, line: &str) -> Vec<Message> {
+    let envelope: Envelope = match serde_json::from_str(line) {
+        Ok(envelope) => envelope,
+        Err(err) => {
+            // Gives back the id even if the rest of the request is invalid.
+            let id = serde_json::from_str::<Value>(line).ok().and_then(|value| value.get("id").cloned());
+            return vec![Message::Error { id, code: ErrorCode::InvalidRequest, message: err.to_string() }]
+        },
+    };
+
+    let before = slot_names(t);
+    let answer = match execute(t, envelope.request) {
+        Ok(value) => Message::Result { id: envelope.id, value, state: Order::new(t) },
+        Err((code, message)) => Message::Error { id: envelope.id, code, message },
+    };
+    let after = slot_names(t);
+
+    let mut msgs = vec![answer];
+    if before != after {
+        msgs.push(Message::Turn { slot: after });
+    }
+    msgs
+}
+
+fn slot_names<S: Saver>(t: &Tracker<S>) -> Vec<String> {
+    t.get_in_turn_slot().iter().map(|chr| chr.name.to_string()).collect()
+}
+
+type ExecuteResult = Result<Value, (ErrorCode, String)>;
+
+fn tracker_err(err: &tracker::Error) -> (ErrorCode, String) {
+    (err.into(), err.to_string())
+}
+
+/// Executes the request on the tracker, giving back what the operation
+/// returns as JSON.
+fn execute<S: Saver>(t: &mut Tracker<S>, request: Request) -> ExecuteResult {
+    let res = match request {
+        Request::State => Ok(Value::Null),
+        Request::EndTurn => t.end_turn().map(|chr| json!(chr.map(|chr| chr.name.to_string()))),
+        Request::Undo => t.undo().map(|()| Value::Null),
+        Request::Redo => t.redo().map(|()| Value::Null),
+        Request::Clear => {
+            t.clear();
+            t.auto_save().map(|()| Value::Null)
+        },
+        Request::Add { name, init, player, health, level, init_mod, group, count, variant } => {
+            let mut builder = Chr::builder(name, init, player);
+            if let Some(health) = health {
+                builder = builder.with_health(Health::new(health));
+            }
+            if let Some(level) = level {
+                builder = builder.with_level(level);
+            }
+            if let Some(init_mod) = init_mod {
+                builder = builder.with_init_mod(init_mod);
+            }
+            let mut chr = builder.build();
+            if let Some(variant) = variant {
+                chr.apply_variant(variant);
+            }
+            match (group, count) {
+                (Some(count), _) => t.add_group(chr, count).map(|()| Value::Null),
+                (None, Some(count)) => t.add_many(chr, count).map(|names| json!(names)),
+                (None, None) => t.add_chr(chr).map(|()| Value::Null),
+            }
+        },
+        Request::AddFromTemplate { template, count, variant } => t.add_variant_from_template(&template, variant, count).map(|names| json!(names)),
+        Request::Remove { name } => t.rm_chr(&name).map(|()| Value::Null),
+        Request::RemoveGroup { group } => t.rm_group(&group).map(|()| Value::Null),
+        Request::Rename { name, new_name } => t.rename(&name, new_name).map(|()| Value::Null),
+        Request::ChangeInit { name, init } => t.change_init(&name, init).map(|moved| match moved {
+            Some(MovedStatus::Skipped(chr)) => json!({ "skipped": chr.name }),
+            Some(MovedStatus::TwoTurns(chr)) => json!({ "two_turns": chr.name }),
+            None => Value::Null,
+        }),
+        Request::SetPlayer { name, player } => t.set_player(&name, player).map(|()| Value::Null),
+        Request::SetLevel { name, level } => t.set_level(&name, level).map(|()| Value::Null),
+        Request::SetInitMod { name, init_mod } => t.set_init_mod(&name, init_mod).map(|()| Value::Null),
+        Request::SetMaxHealth { name, max } => t.change_max_health(&name, max).map(|()| Value::Null),
+        Request::SetHealth { name, hp } => t.set_current_health(&name, hp).map(|()| Value::Null),
+        Request::SetTempHealth { name, hp } => t.set_temp_health(&name, hp).map(|()| Value::Null),
+        Request::AddTempHealth { name, amount } => t.add_temp_health(&name, amount).map(|()| Value::Null),
+        Request::Damage { name, amount } => t.damage(&name, amount).map(|()| Value::Null),
+        Request::Heal { name, amount } => t.heal(&name, amount).map(|()| Value::Null),
+        Request::AddCondition { names, condition } => t.add_condition_many(names.into_iter().map(|name| (name, condition.clone())).collect()).map(|()| Value::Null),
+        Request::RemoveCondition { name, condition } => {
+            t.rm_condition(&name, &condition);
+            Ok(Value::Null)
+        },
+        Request::AdvanceTime { duration } => t.advance_time(duration.build()).map(|()| Value::Null),
+        Request::Rest => t.rest().map(|()| Value::Null),
+        Request::Refocus => t.refocus().map(|()| Value::Null),
+        Request::Roll { expr } => {
+            let expr: DiceExpr = expr.parse().map_err(|err: crate::dice::ParseError| (ErrorCode::InvalidDice, err.to_string()))?;
+            let roll = t.roll(&expr);
+            Ok(json!({ "expr": roll.expr.to_string(), "dice": roll.dice, "total": roll.total }))
+        },
+        Request::RollInitiative { names } => {
+            let rolls = if names.is_empty() { t.roll_enemy_initiative() } else { t.roll_initiative(&names) };
+            rolls.map(|rolls| rolls.into_iter()
+                .map(|(name, roll)| json!({ "name": name, "total": roll.total }))
+                .collect())
+        },
+        Request::Threat => Ok(t.get_threat().map_or(Value::Null, |rating| json!({
+            "xp": rating.xp,
+            "threat": rating.threat.to_string(),
+            "party_level": rating.party.level,
+            "party_size": rating.party.size,
+            "unknown": rating.unknown,
+        }))),
+        Request::NewEncounter { party } => {
+            let party: Vec<(ChrName, i32)> = party.into_iter().map(|member| (member.name, member.init)).collect();
+            t.new_encounter(&party).map(|()| Value::Null)
+        },
+        Request::ImportPathbuilder { path } => t.import_pathbuilder(path).map(|name| json!(name)),
+        Request::ImportFoundry { path } => t.import_foundry(path).map(|()| Value::Null),
+        Request::ExportFoundry { path } => t.export_foundry(path).map(|()| Value::Null),
+        Request::SetVersion { version } => {
+            t.set_pf2e_version_setting(version);
+            t.auto_save().map(|()| Value::Null)
+        },
+        Request::SetUndoSize { size } => {
+            t.set_undo_size_setting(size);
+            t.auto_save().map(|()| Value::Null)
+        },
+        Request::Command { text } => return terminalgui::run_command(t, &text)
+            .map(|msg| json!(msg))
+            .map_err(|err| ((&err).into(), err.to_string())),
+    };
+
+    res.map_err(|err| tracker_err(&err))
+}
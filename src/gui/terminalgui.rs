@@ -1,28 +1,19 @@
-use std::io;
-use const_format::concatcp;
+use std::{io, path::PathBuf};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
 use help::Topic;
+use ratatui::DefaultTerminal;
 use thiserror::Error;
-use crate::{character::{Chr, ChrName, Health}, conditions::{CondFormat, Condition}, saver::Saver, tracker::{self, Tracker}};
+use crate::{bestiary::Variant, character::{Chr, ChrName, Health}, conditions::Condition, dice::DiceExpr, duration::Duration, encounter::Threat, saver::Saver, settings::Pf2eVersion, tracker::{self, Tracker}};
 
 mod parser;
 mod help;
+mod view;
+mod completion;
+mod history;
+pub mod script;
 
-const CLEAR: &str  = "\x1B[2J\n";
-const SPACER: &str = "  ";
-const LINE: &str   = "-------------------------------------------------------";    
-const DLINE: &str  = "=======================================================";
-const TITLE: &str  = "| VΛVΛVΛV    <>~<>~<>~PATHTRACKER~<>~<>~<>    VΛVΛVΛV |";
-const COLUMN: &str = concatcp!(
-    "   ", "Init", SPACER, "P", SPACER, "Name      ", 
-    SPACER, "HP     ", SPACER, "Condition(s)");
-const HEADER: &str = concatcp!(
-    DLINE, "\n",
-    TITLE, "\n",
-    DLINE, "\n",
-    COLUMN, "\n",
-    LINE);
-const PROLOG: &str = concatcp!(CLEAR, HEADER);
-const EPILOG: &str = DLINE;
+/// How many rows Page Up and Page Down scroll the initiative table by.
+const PAGE: usize = 10;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -38,58 +29,97 @@ pub enum Error {
 
 pub struct TerminalGui;
 
-/// Runs the Tracker using the terminal GUI.
+/// Runs the Tracker using the full-screen terminal GUI, until Ctrl+C or
+/// Ctrl+D is pressed.
 ///
 /// # Errors
 ///
-/// This function will return an error if
-/// - Reading terminal input fails
-/// - Parsing terminal input fails
-/// - [`Tracker<S>`] fails when executing a command.
+/// This function will return an error if drawing to or reading from the
+/// terminal fails.
 pub fn run<S: Saver>(mut t: Tracker<S>) -> Result<(), Error> {
-    let mut buff = String::new();
-    let stdin = io::stdin();
-    let mut error: Option<Error> = None;
-    let format = CondFormat::default().set_version(t.get_pf2e_version_setting());
+    let mut terminal = ratatui::init();
+    let res = run_app(&mut terminal, &mut t);
+    ratatui::restore();
+    res
+}
+
+fn run_app<S: Saver>(terminal: &mut DefaultTerminal, t: &mut Tracker<S>) -> Result<(), Error> {
+    let mut app = view::App::default();
+    match history::History::load(history::HISTORY_FILE) {
+        Ok(history) => app.set_history(history),
+        Err(err) => app.set_warning(format!("the command history could not be loaded: {err}")),
+    }
+
     loop {
-        println!("{PROLOG}");
-        for chr in t.get_chrs() {
-            let mut conds: Vec<String> = t.get_conditions(&chr.name).into_iter()
-                .map(|c| c.to_string(format))
-                .collect();
-            conds.sort();
-            let conds_string = conds
-                .into_iter()
-                .intersperse(format!("\n{:^38}", ""))
-                .fold(String::new(), |acc, cond| acc + &cond);
-            println!(
-                "{:^3}{:>4}{SPACER}{:^1}{SPACER}{:<10}{SPACER}{:>3}/{:>3}{SPACER}{}", 
-                if t.get_in_turn() == Some(chr) { ">" } else { "" },
-                chr.init, 
-                if chr.player {"*"} else {""},
-                chr.name,
-                chr.health.as_ref().map_or("---".to_string(), |x| x.current.to_string()),
-                chr.health.as_ref().map_or("---".to_string(), |x| x.max.to_string()),
-                conds_string
-            );
-        }
-        println!("{EPILOG}");
+        terminal.draw(|frame| view::draw(frame, t, &mut app))?;
 
-        if let Some(err) = error.as_ref() {
-            println!("Error: {err}");
-            error = None;
+        // Other events, such as the terminal being resized, only need the
+        // redraw at the top of the loop.
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue
         }
 
-        stdin.read_line(&mut buff)?;
-        let res: Result<_, Error> = parser::parse_input(&std::mem::take(&mut buff)).map_err(Into::into)
-            .and_then(|cmd| execute_command(&mut t, cmd).map_err(Into::into));
-
-        if let Err(err) = res {
-            error = Some(err);
+        match key.code {
+            KeyCode::Char(c) if key.modifiers.contains(KeyModifiers::CONTROL) => match c {
+                'c' | 'd' => return Ok(()),
+                'a' => app.move_home(),
+                'e' => app.move_end(),
+                'w' => app.delete_word(),
+                'u' => app.delete_to_start(),
+                'k' => app.delete_to_end(),
+                _ => (),
+            },
+            KeyCode::Left if key.modifiers.contains(KeyModifiers::CONTROL) => app.move_word_left(),
+            KeyCode::Right if key.modifiers.contains(KeyModifiers::CONTROL) => app.move_word_right(),
+            KeyCode::Tab => {
+                let names: Vec<String> = t.get_chrs().iter().map(|chr| chr.name.to_string()).collect();
+                app.complete(&names);
+            },
+            KeyCode::Enter => {
+                let input = app.take_input();
+                if input.trim().is_empty() {
+                    continue
+                }
+                // The command runs even if the history can't be saved, which
+                // is only warned about.
+                let saved = app.push_history(&input);
+                app.set_result(run_command(t, &input));
+                if let Err(err) = saved {
+                    app.set_warning(format!("the command history could not be saved: {err}"));
+                }
+            },
+            KeyCode::Esc => app.clear(),
+            KeyCode::Char(c) => app.insert(c),
+            KeyCode::Backspace => app.backspace(),
+            KeyCode::Delete => app.delete(),
+            KeyCode::Left => app.move_left(),
+            KeyCode::Right => app.move_right(),
+            KeyCode::Home => app.move_home(),
+            KeyCode::End => app.move_end(),
+            KeyCode::Up => app.history_prev(),
+            KeyCode::Down => app.history_next(),
+            KeyCode::PageUp => app.scroll_up(PAGE),
+            KeyCode::PageDown => app.scroll_down(PAGE),
+            _ => (),
         }
     }
 }
 
+/// Parses and executes a terminal command on the tracker, returning a
+/// message to show if the command has one.
+///
+/// # Errors
+///
+/// This function will return an error if
+/// - The command can't be parsed
+/// - The tracker fails to execute it.
+pub fn run_command<S: Saver>(t: &mut Tracker<S>, input: &str) -> Result<Option<String>, Error> {
+    let chrs: Vec<ChrName> = t.get_chrs().iter().map(|chr| chr.name.clone()).collect();
+    let cmd = parser::parse_input(input, &chrs)?;
+    Ok(execute_command(t, cmd)?)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Command {
     EndTurn,
@@ -97,36 +127,97 @@ enum Command {
         name: ChrName,
         init: i32, 
         player: bool, 
-        health: Option<u32> 
+        health: Option<u32>,
+        level: Option<i32>,
+        group: Option<u32>,
+        count: Option<u32>,
+        init_mod: Option<i32>,
+        variant: Option<Variant>,
+    },
+    AddFromTemplate {
+        template: String,
+        count: u32,
+        variant: Option<Variant>,
     },
     RmChr { name: ChrName },
-    AddCond { character: ChrName, cond: Condition },
+    AddCond { characters: Vec<ChrName>, cond: parser::condition_parser::NewCondition },
     Mod { 
         name: ChrName,
         new_name: Option<String>,
         init: Option<i32>, 
         player: Option<bool>,
-        health: Option<u32>
+        health: Option<u32>,
+        level: Option<i32>,
+        init_mod: Option<i32>,
     },
     RmCond { character: ChrName, cond: Condition },
+    AdvanceTime(Duration),
+    Rest,
+    Refocus,
+    Roll(DiceExpr),
+    /// Rolls initiative for the given characters, or for every enemy if none
+    /// are given.
+    RollInit(Vec<ChrName>),
+    /// Rates the encounter of the enemies against the party.
+    Threat,
+    ImportPathbuilder(PathBuf),
+    /// Replaces the encounter with a Foundry VTT combat document.
+    ImportFoundry(PathBuf),
+    /// Exports the encounter as a Foundry VTT combat document.
+    ExportFoundry(PathBuf),
+    Damage { name: ChrName, amount: u32 },
+    Heal { name: ChrName, amount: u32 },
+    /// Adds temporary HP to the character.
+    TempHp { name: ChrName, amount: u32 },
+    Undo,
+    Redo,
+    /// Removes every character and condition.
+    Clear,
+    SetVersion(Pf2eVersion),
+    SetUndoSize(usize),
     Help(Topic),
 }
 
-fn execute_command<S: Saver>(t: &mut Tracker<S>, cmd: Command) -> tracker::Result<()> {
+/// Executes the command on the tracker, returning a message to show if the
+/// command has one.
+fn execute_command<S: Saver>(t: &mut Tracker<S>, cmd: Command) -> tracker::Result<Option<String>> {
     match cmd {
-        Command::EndTurn => t.end_turn().map(|_| ()),
-        Command::AddChr { name, init, player, health} => {
+        Command::EndTurn => t.end_turn().map(|_| None),
+        Command::AddChr { name, init, player, health, level, group, count, init_mod, variant } => {
             let builder = Chr::builder(name, init, player);
             let builder = match health {
                 None => builder,
                 Some(health) => builder.with_health(Health::new(health))
             };
-            t.add_chr(builder.build())
+            let builder = match level {
+                None => builder,
+                Some(level) => builder.with_level(level)
+            };
+            let builder = match init_mod {
+                None => builder,
+                Some(init_mod) => builder.with_init_mod(init_mod)
+            };
+            let mut chr = builder.build();
+            if let Some(variant) = variant {
+                chr.apply_variant(variant);
+            }
+            match (group, count) {
+                (Some(count), _) => t.add_group(chr, count),
+                (None, Some(count)) => t.add_many(chr, count).map(|_| ()),
+                (None, None) => t.add_chr(chr),
+            }.map(|()| None)
+        },
+        Command::AddFromTemplate { template, count, variant } => t.add_variant_from_template(&template, variant, count).map(|_| None),
+        Command::RmChr { name } => t.rm_chr(&name).map(|()| None),
+        Command::AddCond { characters, cond } => {
+            let conds = characters.into_iter().map(|name| {
+                let cond = cond.build(&name);
+                (name, cond)
+            }).collect();
+            t.add_condition_many(conds).map(|()| None)
         },
-        Command::RmChr { name } => t.rm_chr(&name),
-        Command::AddCond { character, cond } => t.add_condition(character, cond),
-        Command::RmCond { character, cond } => { t.rm_condition(&character, &cond); Ok(()) },
-        Command::Mod { name, new_name, init, player, health } => {
+        Command::RmCond { character, cond } => { t.rm_condition(&character, &cond); Ok(None) },
+        Command::Mod { name, new_name, init, player, health, level, init_mod } => {
             if let Some(init) = init {
                 t.change_init(&name, init)?;
             }
@@ -139,16 +230,61 @@ fn execute_command<S: Saver>(t: &mut Tracker<S>, cmd: Command) -> tracker::Resul
                 t.change_max_health(&name, health)?;
             }
 
+            if let Some(level) = level {
+                t.set_level(&name, Some(level))?;
+            }
+
+            if let Some(init_mod) = init_mod {
+                t.set_init_mod(&name, Some(init_mod))?;
+            }
+
             if let Some(new_name) = new_name {
                 t.rename(&name, new_name)?;
             }
 
-            Ok(())
+            Ok(None)
         },
-        Command::Help(topic) => {
-            topic.help();
-            Ok(())
-        }
+        Command::AdvanceTime(dur) => t.advance_time(dur).map(|()| None),
+        Command::Rest => t.rest().map(|()| None),
+        Command::Refocus => t.refocus().map(|()| None),
+        Command::Roll(expr) => Ok(Some(t.roll(&expr).to_string())),
+        Command::RollInit(names) => {
+            let rolls = if names.is_empty() {
+                t.roll_enemy_initiative()?
+            } else {
+                t.roll_initiative(&names)?
+            };
+            let rolls: Vec<String> = rolls.into_iter().map(|(name, roll)| format!("{name}: {roll}")).collect();
+            Ok(Some(rolls.join("\n")))
+        },
+        Command::Threat => Ok(Some(t.get_threat().map_or_else(
+            || "There is no party to rate the encounter for. Give the player characters a level with -l.".into(),
+            |rating| {
+                let budgets: Vec<String> = Threat::ALL.iter().map(|threat| format!("{threat} {}", threat.budget(rating.party.size))).collect();
+                format!("{rating}\nBudgets: {}", budgets.join(", "))
+            }
+        ))),
+        Command::ImportPathbuilder(file) => t.import_pathbuilder(file).map(|name| Some(format!("Imported {name}."))),
+        Command::ImportFoundry(file) => t.import_foundry(&file).map(|()| Some(format!("Imported the encounter from {}.", file.display()))),
+        Command::ExportFoundry(file) => t.export_foundry(&file).map(|()| Some(format!("Exported the encounter to {}.", file.display()))),
+        Command::Damage { name, amount } => t.damage(&name, amount).map(|()| None),
+        Command::Heal { name, amount } => t.heal(&name, amount).map(|()| None),
+        Command::TempHp { name, amount } => t.add_temp_health(&name, amount).map(|()| None),
+        Command::Undo => t.undo().map(|()| None),
+        Command::Redo => t.redo().map(|()| None),
+        Command::Clear => {
+            t.clear();
+            t.auto_save().map(|()| None)
+        },
+        Command::SetVersion(version) => {
+            t.set_pf2e_version_setting(version);
+            t.auto_save().map(|()| None)
+        },
+        Command::SetUndoSize(size) => {
+            t.set_undo_size_setting(size);
+            t.auto_save().map(|()| None)
+        },
+        Command::Help(topic) => Ok(Some(topic.text().to_string())),
     }
 }
 
@@ -0,0 +1,178 @@
+use super::parser::{command_strs, condition_parser::{nonvalued_conditions, valued_conditions}};
+
+/// The keywords of condition terminations and turn events.
+const TERM_KEYWORDS: [&str; 10] = ["for", "until", "reduced", "by", "at", "start", "end", "of", "turn", "on"];
+
+/// The words after which a character name follows in a condition command.
+const NAME_KEYWORDS: [&str; 3] = ["on", "from", "of"];
+
+/// The ways a line can be completed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// Where in the line, in bytes, the completed text starts.
+    pub start: usize,
+    pub candidates: Vec<String>,
+}
+
+impl Completion {
+    /// The longest text every candidate starts with.
+    #[must_use]
+    pub fn common_prefix(&self) -> Option<String> {
+        let (first, rest) = self.candidates.split_first()?;
+        let mut prefix = first.clone();
+        for candidate in rest {
+            while !candidate.to_lowercase().starts_with(&prefix.to_lowercase()) {
+                prefix.pop();
+            }
+        }
+        Some(prefix)
+    }
+}
+
+/// Completes the end of [`line`], the input up to the cursor, from the
+/// command keywords, the names of [`chrs`], the condition names and the
+/// termination keywords, depending on where in the command it is.
+#[must_use]
+pub fn complete(line: &str, chrs: &[String]) -> Completion {
+    let start = line.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    let words: Vec<&str> = line[..start].split_whitespace().collect();
+    let partial = &line[start..];
+
+    if let Some(name_start) = name_start(line, &words) {
+        let candidates = matching(chrs.iter().map(String::as_str), &line[name_start..]);
+        // Keywords can follow a name, like `turn` in `end of Sarah turn`.
+        if !candidates.is_empty() {
+            return Completion { start: name_start, candidates }
+        }
+    }
+
+    let candidates: Vec<&str> = match words.as_slice() {
+        [] | [command_strs::HELP] => command_strs::ALL.to_vec(),
+        [command_strs::CONDITION] => vec!["add", "rm"],
+        [command_strs::CONDITION, "add" | "rm"] => valued_conditions::ALL.iter().chain(nonvalued_conditions::ALL.iter()).copied().collect(),
+        [command_strs::CONDITION, "add", ..] => TERM_KEYWORDS.to_vec(),
+        [command_strs::CONDITION, "rm", _, ..] => vec!["from"],
+        [command_strs::TIME] => vec!["rest", "refocus"],
+        [command_strs::ROLL] => vec!["init"],
+        [command_strs::IMPORT] => vec!["pathbuilder", "foundry"],
+        [command_strs::EXPORT] => vec!["foundry"],
+        [command_strs::SET] => vec!["version", "undo"],
+        [command_strs::SET, "version"] => vec!["remastered", "legacy"],
+        _ => vec![],
+    };
+
+    Completion { start, candidates: matching(candidates, partial) }
+}
+
+/// Where the character name being typed at the end of [`line`] starts, if
+/// a name is being typed. Names may have spaces in them, and several names
+/// can be given separated by commas.
+fn name_start(line: &str, words: &[&str]) -> Option<usize> {
+    let after_keyword = match words {
+        [command_strs::REMOVE | command_strs::MODIFY, ..] => 1,
+        [command_strs::ROLL, "init", ..] | [command_strs::DAMAGE | command_strs::HEAL | command_strs::TEMP_HP, _, ..] => 2,
+        [command_strs::CONDITION, ..] => words.iter().rposition(|word| NAME_KEYWORDS.contains(word))? + 1,
+        _ => return None,
+    };
+
+    // Finds the byte offset of the first name word, which is the end of the
+    // keyword before it.
+    let mut offset = 0;
+    for word in &words[..after_keyword] {
+        offset = line[offset..].find(word)? + offset + word.len();
+    }
+    let rest = &line[offset..];
+    let is_opt = |word: &str| word.strip_prefix('-').is_some_and(|opt| opt.starts_with(char::is_alphabetic));
+    if rest.split_whitespace().any(is_opt) {
+        // Options like `-hp 10` come after the name.
+        return None
+    }
+    let rest_start = offset + rest.rfind(',').map_or(0, |i| i + 1);
+    let trimmed = line[rest_start..].len() - line[rest_start..].trim_start().len();
+
+    Some(rest_start + trimmed)
+}
+
+fn matching<'a>(candidates: impl IntoIterator<Item = &'a str>, partial: &str) -> Vec<String> {
+    let partial = partial.to_lowercase();
+    let mut candidates: Vec<String> = candidates.into_iter()
+        .filter(|candidate| candidate.to_lowercase().starts_with(&partial))
+        .map(ToString::to_string)
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::complete;
+
+    fn chrs() -> Vec<String> {
+        vec!["Goblin Warchanter".into(), "Goblin Warrior".into(), "Sarah".into()]
+    }
+
+    #[test]
+    fn completes_command_keywords() {
+        let completion = complete("thr", &chrs());
+        assert_eq!((0, vec!["threat".to_string()]), (completion.start, completion.candidates));
+    }
+
+    #[test]
+    fn completes_condition_names() {
+        let completion = complete("cond add frig", &chrs());
+        assert_eq!((9, vec!["frightened".to_string()]), (completion.start, completion.candidates));
+    }
+
+    #[test]
+    fn completes_term_keywords() {
+        let completion = complete("cond add frightened 2 red", &chrs());
+        assert_eq!(vec!["reduced".to_string()], completion.candidates);
+    }
+
+    #[test]
+    fn completes_names_with_spaces() {
+        let completion = complete("cond add frightened 2 on goblin war", &chrs());
+        assert_eq!(25, completion.start);
+        assert_eq!(vec!["Goblin Warchanter".to_string(), "Goblin Warrior".to_string()], completion.candidates);
+        assert_eq!(Some("Goblin War".to_string()), completion.common_prefix());
+    }
+
+    #[test]
+    fn completes_names_after_comma() {
+        let completion = complete("cond add prone on Sarah, gob", &chrs());
+        assert_eq!(25, completion.start);
+        assert_eq!(2, completion.candidates.len());
+    }
+
+    #[test]
+    fn completes_names_of_removed() {
+        let completion = complete("rm Sa", &chrs());
+        assert_eq!((3, vec!["Sarah".to_string()]), (completion.start, completion.candidates));
+    }
+
+    #[test]
+    fn completes_keywords_after_names() {
+        let completion = complete("cond add frightened 2 reduced by 1 at end of Sarah tu", &chrs());
+        assert_eq!(vec!["turn".to_string()], completion.candidates);
+    }
+
+    #[test]
+    fn completes_names_after_amount() {
+        let completion = complete("dmg 5 gob", &chrs());
+        assert_eq!((6, 2), (completion.start, completion.candidates.len()));
+    }
+
+    #[test]
+    fn no_names_after_options() {
+        let completion = complete("mod Sarah -hp 1", &chrs());
+        assert!(completion.candidates.is_empty());
+    }
+
+    #[test]
+    fn completes_names_with_dashes() {
+        let chrs = vec!["Half-Orc".to_string()];
+        let completion = complete("rm Half-O", &chrs);
+        assert_eq!((3, vec!["Half-Orc".to_string()]), (completion.start, completion.candidates));
+    }
+}
@@ -1,7 +1,4 @@
-use std::io;
-
 use const_format::concatcp;
-use super::CLEAR;
 
 use crate::gui::terminalgui::parser::command_strs;
 
@@ -21,7 +18,7 @@ const HELP_END_TURN: &str = concatcp!(
 const HELP_ADD: &str = concatcp!(
     ITEM, 
     command_strs::ADD, 
-    ": adds a character.",
+    " [from <template>]: adds a character, optionally from the bestiary.",
 );
 const HELP_REMOVE: &str = concatcp!(
     ITEM,
@@ -38,6 +35,57 @@ const HELP_CONDITION: &str = concatcp!(
     command_strs::CONDITION,
     " <condition command>: do `help cond` for details.",
 );
+const HELP_TIME: &str = concatcp!(
+    ITEM,
+    command_strs::TIME,
+    " <time> | rest | refocus: lets time pass outside of combat.",
+);
+const HELP_ROLL: &str = concatcp!(
+    ITEM,
+    command_strs::ROLL,
+    " <dice> | init [<character>, ...]: rolls dice or initiative.",
+);
+
+const HELP_THREAT: &str = concatcp!(
+    ITEM,
+    command_strs::THREAT,
+    ": rates the XP and threat of the encounter.",
+);
+
+const HELP_IMPORT: &str = concatcp!(
+    ITEM,
+    command_strs::IMPORT,
+    " pathbuilder <file>: imports a player character.\n",
+    ITEM,
+    command_strs::IMPORT,
+    " foundry <file>, ",
+    command_strs::EXPORT,
+    " foundry <file>: moves the encounter to and from Foundry VTT.",
+);
+
+const HELP_HEALTH: &str = concatcp!(
+    ITEM,
+    command_strs::DAMAGE, ", ", command_strs::HEAL, ", ", command_strs::TEMP_HP,
+    " <amount> <character>: damages, heals or gives temporary HP to a character.",
+);
+
+const HELP_UNDO: &str = concatcp!(
+    ITEM,
+    command_strs::UNDO, ", ", command_strs::REDO,
+    ": undoes or redoes the last change.",
+);
+
+const HELP_CLEAR: &str = concatcp!(
+    ITEM,
+    command_strs::CLEAR,
+    ": removes every character from the tracker.",
+);
+
+const HELP_SET: &str = concatcp!(
+    ITEM,
+    command_strs::SET,
+    " version remastered | version legacy | undo <size>: changes a setting.",
+);
 
 const HELP: &str = concatcp!(
     HELP_HEADER, "\n", 
@@ -46,7 +94,15 @@ const HELP: &str = concatcp!(
     HELP_ADD, "\n",
     HELP_REMOVE, "\n",
     HELP_MODIFY, "\n",
-    HELP_CONDITION
+    HELP_CONDITION, "\n",
+    HELP_TIME, "\n",
+    HELP_ROLL, "\n",
+    HELP_THREAT, "\n",
+    HELP_IMPORT, "\n",
+    HELP_HEALTH, "\n",
+    HELP_UNDO, "\n",
+    HELP_CLEAR, "\n",
+    HELP_SET
 );
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -58,13 +114,17 @@ pub enum Topic {
     Remove,
     Modify,
     Condition,
-}
-
-fn pause() {
-    let mut buff = String::new();
-    let stdin = io::stdin();
-
-    let _ = stdin.read_line(&mut buff);
+    Time,
+    Roll,
+    Threat,
+    Import,
+    Damage,
+    Heal,
+    TempHp,
+    Undo,
+    Redo,
+    Clear,
+    Set,
 }
 
 const HELP_WITH_HELP: &str = concatcp!(
@@ -95,8 +155,32 @@ const HELP_WITH_ADD: &str = concatcp!(
      - health/h <max health>: adds health tracking\n\
      - player/p: marks the character as a player character\n\
      - enemy/e: marks the character as an enemy character\n\
+     - level/l <level>: sets the level of the character, which is needed for drained to lower max HP\n\
+     - group/g <count>: adds a group of that many members sharing one turn, numbered from 1\n\
+     - count/n <count>: adds that many characters with their own turns, named like Wolf, Wolf 2, ...\n\
+     - initmod/m <modifier>: sets the initiative modifier, usually Perception, used when rolling initiative\n\
+     - elite: applies the elite adjustments, raising level, HP by level and initiative modifier by 2\n\
+     - weak: applies the weak adjustments, lowering level, HP by level and initiative modifier by 2\n\
+    \n\
+    Example: add 24 Sarah -player -health 20\n\
+    Example: add 15 Goblin -health 6 -group 4\n\
+    Example: add 15 Wolf -h 24 -n 4\n\
     \n\
-    Example: add 24 Sarah -player -health 20
+    Names with words like `on` or `from`, commas or a leading `-` can be given\n\
+    in double or single quotes, where `\\\"`, `\\'` and `\\\\` escape a quote or a\n\
+    backslash. Quotes work for names in every command.\n\
+    \n\
+    Example: add 12 Half-Orc -h 30\n\
+    Example: add 8 \"Man from Nowhere\"\n\
+    \n\
+    ", command_strs::ADD, " from <template> [-n <count>] [-elite | -weak]:\n\
+    \n\
+    Adds characters from a creature template in the bestiary, found as JSON files\n\
+    in the bestiary directory next to saves. Each character gets the name, level,\n\
+    HP and initiative modifier of the template and rolls their own initiative.\n\
+    \n\
+    Example: add from goblin-warrior -n 3\n\
+    Example: add from wolf -elite
     "
 );
 
@@ -107,8 +191,15 @@ const HELP_WITH_REMOVE: &str = concatcp!(
     \n\
     If it's the to-be-removed characters turn then upon removal their turn\n\
     ends as if the ", command_strs::END_TURN, " command had been invoked.\n\
+    Removing the last member of a group that isn't down removes the whole group.\n\
+    \n\
+    Existing characters, here and in other commands, can be given by the start\n\
+    of their name in any case, as long as only one name starts that way, or by\n\
+    their position in the order like #3.\n\
     \n\
-    Example: ", command_strs::REMOVE, " Carlile"
+    Example: ", command_strs::REMOVE, " Carlile\n\
+    Example: ", command_strs::REMOVE, " carl\n\
+    Example: ", command_strs::REMOVE, " #3"
 );
 
 const HELP_WITH_MODIFY: &str = concatcp!(
@@ -123,6 +214,8 @@ const HELP_WITH_MODIFY: &str = concatcp!(
      - health/h <max health>: adds health tracking to character and/or changes max health.\n\
      - player/p: marks the character as a player character\n\
      - enemy/e: marks the character as an enemy character\n\
+     - level/l <level>: changes the level of the character\n\
+     - initmod/m <modifier>: changes the initiative modifier of the character\n\
     \n\
     Example: ", command_strs::MODIFY, " Sarah -h 23 -p"
 );
@@ -131,10 +224,11 @@ const HELP_WITH_CONDITION: &str = concatcp!(
     command_strs::CONDITION, " <cond command>:\n\
     \n\
     Allows adding and removing conditions via the following condition commands:\n\
-     - add <condition> [<condition level>] [<term criteria>] on <character>: adds the given condition to the given character.\n\
+     - add <condition> [<condition level>] [<term criteria>] on <character>[, <character>...]: adds the given condition to the given characters.\n\
      - rm <condition> from <character>: removes the given condition from the given character.\n\
     \n\
     Example: ", command_strs::CONDITION, " add clumsy 2 until end of turn on Clara\n\
+    Example: ", command_strs::CONDITION, " add frightened 1 on Goblin 1, Goblin 2, Orc\n\
     \n\
     Conditions (<condition>):\n\
     \n\
@@ -159,12 +253,16 @@ const HELP_WITH_CONDITION: &str = concatcp!(
     character whose condition it is. For instance, if we add 'slowed 2' to\n\
     'Clara' with the trigger 'until start of turn' then 'Clara' is slowed 2 until\n\
     the start of her turn. If we instead wrote 'until start of Mathias turn' then\n\
-    Clara will be slowed 2 until the start of Mathias turn.\n\
+    Clara will be slowed 2 until the start of Mathias turn. When adding a condition to\n\
+    several characters, each of them gets a trigger relative to themselves.\n\
     \n\
     Time (<time>):\n\
     \n\
-    For time based termination criteria, we can specify time in actions, turns,\n\
-    seconds, minutes and even days. Note however, that the tracker doesn't track time\n\
+    For time based termination criteria, we can specify time in seconds (s), actions (a),\n\
+    turns of 6 seconds (t), minutes (m, min), hours (h) and even days (d), or in rounds (r)\n\
+    which count turns of the affected character and only become time outside of combat.\n\
+    Several amounts can be combined, with or without spaces, such as '3 rounds', '1h30m'\n\
+    or '1 h 10 min'. Note however, that the tracker doesn't track time\n\
     on a finer granularity than turns, so something that lasts for 2 actions, for instance\n\
     will terminate after a turn. Be aware that we track the time of a condition relative\n\
     to the end of the turn of the character who has the condition.\n\
@@ -181,23 +279,165 @@ const HELP_WITH_CONDITION: &str = concatcp!(
     by instead using the trigger 'for 1 turn' in this situation."
 );
 
-impl Topic {
-    pub fn help(self) {
-        println!("{CLEAR}");
-        match self {
-            Self::Summary => println!("{HELP}"),
-            Self::Help => println!("{HELP_WITH_HELP}"),
-            Self::EndTurn => println!("{HELP_WITH_END_TURN}"),
-            Self::Add => println!("{HELP_WITH_ADD}"),
-            Self::Remove => println!("{HELP_WITH_REMOVE}"),
-            Self::Modify => println!("{HELP_WITH_MODIFY}"),
-            Self::Condition => println!("{HELP_WITH_CONDITION}"),
-        };
+const HELP_WITH_TIME: &str = concatcp!(
+    command_strs::TIME, " <time> | rest | refocus:\n\
+    \n\
+    Lets time pass outside of combat, moving the clock of the tracker forward.\n\
+    \n\
+    Conditions lasting for a time count down with the clock, while conditions\n\
+    ending or being reduced at the start or end of a turn act as if a turn passed\n\
+    for every 6 seconds. Time is given like for conditions, such as '1h30m'.\n\
+    \n\
+    The following shortcuts are also available:\n\
+     - rest: rests for 8 hours, removing fatigued and reducing drained and doomed by 1\n\
+     - refocus: refocuses for 10 minutes\n\
+    \n\
+    Example: ", command_strs::TIME, " 1 hour"
+);
 
-        println!();
+const HELP_WITH_ROLL: &str = concatcp!(
+    command_strs::ROLL, " <dice> | init [<character>, ...]:\n\
+    \n\
+    Rolls dice, showing the total and the result of each die.\n\
+    \n\
+    Dice are given as a sum of dice and numbers, like 1d20+7 or 2d6 - 1,\n\
+    optionally followed by a label such as a damage type, like 4d8 fire.\n\
+    \n\
+    With init, rolls initiative for the given characters, separated by commas,\n\
+    or for every enemy if none are given. Each rolls 1d20 plus their initiative\n\
+    modifier, which is set with the initmod/m option of ", command_strs::ADD, " and ", command_strs::MODIFY, ".\n\
+    A group rolls once for all its members.\n\
+    \n\
+    Example: ", command_strs::ROLL, " 2d6+3\n\
+    Example: ", command_strs::ROLL, " init Goblin 1, Orc"
+);
+
+const HELP_WITH_THREAT: &str = concatcp!(
+    command_strs::THREAT, ":\n\
+    \n\
+    Rates the encounter, giving the XP of the enemies and whether it is a Trivial,\n\
+    Low, Moderate, Severe or Extreme threat to the party, along with the XP budget\n\
+    of each threat.\n\
+    \n\
+    The party is made up of the player characters, at their average level, so\n\
+    they need a level, given with the level/l option of ", command_strs::ADD, " and ", command_strs::MODIFY, ".\n\
+    Enemies without a level aren't counted.\n\
+    \n\
+    Example: ", command_strs::THREAT
+);
+
+const HELP_WITH_IMPORT: &str = concatcp!(
+    command_strs::IMPORT, " pathbuilder <file>:\n\
+    \n\
+    Imports a player character from a Pathbuilder 2e JSON export, with their\n\
+    name, level, max HP, AC, saves, Perception and resistances. Perception is\n\
+    used as their initiative modifier.\n\
+    \n\
+    Importing a character already in the tracker updates them in place, keeping\n\
+    their initiative, conditions and the damage they have taken.\n\
+    \n\
+    Example: ", command_strs::IMPORT, " pathbuilder exports/sarah.json\n\
+    \n\
+    ", command_strs::IMPORT, " foundry <file>:\n\
+    ", command_strs::EXPORT, " foundry <file>:\n\
+    \n\
+    Replaces the encounter with the one in a Foundry VTT combat JSON document,\n\
+    or exports the encounter as one. The combatants keep their initiative, HP\n\
+    and conditions, which are mapped to the status ids of the PF2e system.\n\
+    Foundry doesn't know when conditions end, so imported conditions are\n\
+    removed manually. Importing is a single change, which can be undone.\n\
+    \n\
+    Example: ", command_strs::EXPORT, " foundry exports/ambush.json"
+);
 
-        println!("... press Enter to return");
+const HELP_WITH_DAMAGE: &str = concatcp!(
+    command_strs::DAMAGE, " <amount> <name>:\n\
+    \n\
+    Damages a character by the given amount, taking it from their temporary HP\n\
+    first. The character needs health tracking, given with the health/h option\n\
+    of ", command_strs::ADD, " and ", command_strs::MODIFY, ".\n\
+    \n\
+    Example: ", command_strs::DAMAGE, " 7 Goblin 2"
+);
 
-        pause();    
+const HELP_WITH_HEAL: &str = concatcp!(
+    command_strs::HEAL, " <amount> <name>:\n\
+    \n\
+    Heals a character by the given amount, up to their max HP.\n\
+    \n\
+    Example: ", command_strs::HEAL, " 12 Sarah"
+);
+
+const HELP_WITH_TEMP_HP: &str = concatcp!(
+    command_strs::TEMP_HP, " <amount> <name>:\n\
+    \n\
+    Adds the given amount of temporary HP to a character, which is lost before\n\
+    their HP when they take damage.\n\
+    \n\
+    Example: ", command_strs::TEMP_HP, " 5 Sarah"
+);
+
+const HELP_WITH_UNDO: &str = concatcp!(
+    command_strs::UNDO, ":\n\
+    \n\
+    Undoes the last change to the tracker. How many changes can be undone is\n\
+    set with `", command_strs::SET, " undo <size>`.\n\
+    \n\
+    Example: ", command_strs::UNDO
+);
+
+const HELP_WITH_REDO: &str = concatcp!(
+    command_strs::REDO, ":\n\
+    \n\
+    Redoes the last change undone with ", command_strs::UNDO, ". Making a new change\n\
+    forgets the undone changes.\n\
+    \n\
+    Example: ", command_strs::REDO
+);
+
+const HELP_WITH_CLEAR: &str = concatcp!(
+    command_strs::CLEAR, ":\n\
+    \n\
+    Removes every character and condition from the tracker. This can be undone\n\
+    with ", command_strs::UNDO, ".\n\
+    \n\
+    Example: ", command_strs::CLEAR
+);
+
+const HELP_WITH_SET: &str = concatcp!(
+    command_strs::SET, " <setting> <value>:\n\
+    \n\
+    Changes a setting, which is saved along with the tracker. The settings are:\n\
+     - version remastered | legacy: the version of the Pathfinder 2e rules to follow\n\
+     - undo <size>: how many changes can be undone\n\
+    \n\
+    Example: ", command_strs::SET, " version remastered\n\
+    Example: ", command_strs::SET, " undo 32"
+);
+
+impl Topic {
+    /// The help text of the topic.
+    #[must_use]
+    pub const fn text(self) -> &'static str {
+        match self {
+            Self::Summary => HELP,
+            Self::Help => HELP_WITH_HELP,
+            Self::EndTurn => HELP_WITH_END_TURN,
+            Self::Add => HELP_WITH_ADD,
+            Self::Remove => HELP_WITH_REMOVE,
+            Self::Modify => HELP_WITH_MODIFY,
+            Self::Condition => HELP_WITH_CONDITION,
+            Self::Time => HELP_WITH_TIME,
+            Self::Roll => HELP_WITH_ROLL,
+            Self::Threat => HELP_WITH_THREAT,
+            Self::Import => HELP_WITH_IMPORT,
+            Self::Damage => HELP_WITH_DAMAGE,
+            Self::Heal => HELP_WITH_HEAL,
+            Self::TempHp => HELP_WITH_TEMP_HP,
+            Self::Undo => HELP_WITH_UNDO,
+            Self::Redo => HELP_WITH_REDO,
+            Self::Clear => HELP_WITH_CLEAR,
+            Self::Set => HELP_WITH_SET,
+        }
     }
 }
@@ -0,0 +1,135 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+/// The file the command history is saved to, next to `saves/`.
+pub const HISTORY_FILE: &str = "terminal.history";
+
+/// The most commands the history keeps.
+const MAX_ENTRIES: usize = 500;
+
+/// The commands entered in the terminal GUI, oldest first, which can be
+/// browsed through like in a shell.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct History {
+    entries: Vec<String>,
+    /// The entry being looked at while browsing, if browsing.
+    pos: Option<usize>,
+    /// The input from before browsing started, given back when browsing past
+    /// the newest entry.
+    draft: String,
+    /// The file the history is saved to, if any.
+    path: Option<PathBuf>,
+}
+
+impl History {
+    /// Loads the history saved at [`path`], saving it there from then on. A
+    /// missing file gives an empty history.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file exists but can't be
+    /// read.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let entries = match fs::read_to_string(path) {
+            Ok(text) => text.lines().filter(|line| !line.trim().is_empty()).map(ToString::to_string).collect(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+
+        Ok(Self { entries, pos: None, draft: String::new(), path: Some(path.to_path_buf()) })
+    }
+
+    /// Adds [`entry`] as the newest command and saves the history, unless it
+    /// is empty or the same as the newest command.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the history can't be saved.
+    pub fn push(&mut self, entry: &str) -> io::Result<()> {
+        self.pos = None;
+        let entry = entry.trim();
+        if entry.is_empty() || self.entries.last().is_some_and(|last| last == entry) {
+            return Ok(())
+        }
+
+        self.entries.push(entry.to_string());
+        let overflow = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..overflow);
+
+        match &self.path {
+            Some(path) => fs::write(path, self.entries.join("\n") + "\n"),
+            None => Ok(()),
+        }
+    }
+
+    /// Steps back to the previous command, keeping [`input`] to come back to.
+    pub fn prev(&mut self, input: &str) -> Option<&str> {
+        let pos = match self.pos {
+            None => {
+                self.draft = input.to_string();
+                self.entries.len().checked_sub(1)?
+            },
+            Some(pos) => pos.saturating_sub(1),
+        };
+        self.pos = Some(pos);
+        self.entries.get(pos).map(String::as_str)
+    }
+
+    /// Steps forward to the next command, or back to the input from before
+    /// browsing after the newest.
+    pub fn next(&mut self) -> Option<&str> {
+        let pos = self.pos?;
+        if pos + 1 < self.entries.len() {
+            self.pos = Some(pos + 1);
+            self.entries.get(pos + 1).map(String::as_str)
+        } else {
+            self.pos = None;
+            Some(&self.draft)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::History;
+
+    fn history(entries: &[&str]) -> History {
+        let mut history = History::default();
+        for entry in entries {
+            history.push(entry).unwrap();
+        }
+        history
+    }
+
+    #[test]
+    fn browses_back_and_forth() {
+        let mut history = history(&["n", "add 12 Goblin"]);
+
+        assert_eq!(Some("add 12 Goblin"), history.prev("cond"));
+        assert_eq!(Some("n"), history.prev("cond"));
+        assert_eq!(Some("n"), history.prev("cond"));
+        assert_eq!(Some("add 12 Goblin"), history.next());
+        assert_eq!(Some("cond"), history.next());
+        assert_eq!(None, history.next());
+    }
+
+    #[test]
+    fn skips_repeats_and_empty() {
+        let history = history(&["n", "n", " ", "threat"]);
+
+        assert_eq!(vec!["n".to_string(), "threat".to_string()], history.entries);
+    }
+
+    #[test]
+    fn saves_across_sessions() {
+        let path = std::env::temp_dir().join(format!("pathtracker_history_{}", std::process::id()));
+        let mut history = History::load(&path).unwrap();
+        history.push("threat").unwrap();
+
+        let loaded = History::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(vec!["threat".to_string()], loaded.entries);
+    }
+}
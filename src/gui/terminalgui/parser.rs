@@ -1,29 +1,57 @@
 use anymap2::AnyMap;
 use thiserror::Error;
 
-use crate::character::ChrName;
+use crate::{bestiary::Variant, character::ChrName, dice, settings::Pf2eVersion};
 
 use super::{Command, Topic};
 
-mod condition_parser;
+pub mod condition_parser;
+mod cursor;
+mod lexer;
+mod resolver;
 use condition_parser as cond_parser;
+use cursor::Cursor;
+use lexer::Token;
 
+/// A [`ErrorKind`] at a place in the input.
 #[derive(Debug, Error)]
-pub enum Error {
+#[error("column {column}: {kind}")]
+pub struct Error {
+    /// The column the error is at, counting characters from 1.
+    pub column: usize,
+    pub kind: ErrorKind,
+}
+
+impl Error {
+    /// An error at byte [`at`] of [`input`].
+    fn at(input: &str, at: usize, kind: ErrorKind) -> Self {
+        let column = input.get(..at).map_or(at, |before| before.chars().count()) + 1;
+        Self { column, kind }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ErrorKind {
     #[error("empty input.")]
     EmptyInput,
 
-    #[error("missing command.")]
-    MissingCommand,
-
     #[error("invalid keyword `{0}`.")]
     InvalidKeyWord(String),
 
-    #[error("invalid number of args `{0}` for `{1}` command.")]
-    InvalidNumberOfArgs(usize, String),
+    #[error("missing {0}.")]
+    Missing(&'static str),
+
+    #[error("expected {expected} but got `{found}`.")]
+    Unexpected {
+        expected: &'static str,
+        found: String,
+    },
 
-    #[error("invalid condition syntax: expected `<cond> [<value>] <term> on <character>` but got `{0}`")]
-    InvalidCondSyntax(String),
+    #[error("unterminated quote.")]
+    UnterminatedQuote,
+
+    #[error("invalid escape `\\{0}` in quotes, expected `\\\"`, `\\'` or `\\\\`.")]
+    InvalidEscape(char),
 
     #[error("initiative (first arg) is expected to be a number, but provided arg `{arg}` is not.")]
     ParseInt {
@@ -31,6 +59,33 @@ pub enum Error {
         #[source] source: std::num::ParseIntError,
     },
 
+    #[error("amount (first arg) is expected to be a whole number, but provided arg `{arg}` is not.")]
+    ParseAmount {
+        arg: String,
+        #[source] source: std::num::ParseIntError,
+    },
+
+    #[error("undo size is expected to be a whole number, but provided arg `{arg}` is not.")]
+    ParseUndoSize {
+        arg: String,
+        #[source] source: std::num::ParseIntError,
+    },
+
+    #[error("invalid setting `{0}`, expected `version remastered`, `version legacy` or `undo <size>`.")]
+    InvalidSetting(String),
+
+    #[error("`{name}` could be any of {}, give more of the name or its `#` position.", join_names(.candidates))]
+    AmbiguousName {
+        name: String,
+        candidates: Vec<ChrName>,
+    },
+
+    #[error("there is no character at position #{0}.")]
+    NoChrAtPosition(usize),
+
+    #[error("unknown option `-{0}`.")]
+    UnknownOption(String),
+
     #[error(transparent)]
     InvalidExtraArg(#[from] ExtraArgError),
 
@@ -38,10 +93,24 @@ pub enum Error {
     CondParser(#[from] cond_parser::Error),
 
     #[error("invalid arg `{0}` for help command.")]
-    InvalidHelpArg(String)
+    InvalidHelpArg(String),
+
+    #[error(transparent)]
+    InvalidDice(#[from] dice::ParseError),
+
+    #[error("invalid import `{0}`, expected `pathbuilder <file>` or `foundry <file>`.")]
+    InvalidImport(String),
+
+    #[error("invalid export `{0}`, expected `foundry <file>`.")]
+    InvalidExport(String),
 }
 
-pub type ParseResult = Result<Command, Error>;
+fn join_names(names: &[ChrName]) -> String {
+    names.iter().map(AsRef::as_ref).collect::<Vec<&str>>().join(", ")
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+pub type ParseResult = Result<Command>;
 
 pub mod command_strs {
     pub const END_TURN: &str = "n";
@@ -49,87 +118,252 @@ pub mod command_strs {
     pub const REMOVE: &str = "rm";
     pub const MODIFY: &str = "mod";
     pub const CONDITION: &str = "cond";
+    pub const TIME: &str = "time";
+    pub const ROLL: &str = "roll";
+    pub const THREAT: &str = "threat";
+    pub const IMPORT: &str = "import";
+    pub const EXPORT: &str = "export";
+    pub const DAMAGE: &str = "dmg";
+    pub const HEAL: &str = "heal";
+    pub const TEMP_HP: &str = "thp";
+    pub const UNDO: &str = "undo";
+    pub const REDO: &str = "redo";
+    pub const CLEAR: &str = "clear";
+    pub const SET: &str = "set";
     pub const HELP: &str = "help";
+
+    /// Every command keyword, for completion.
+    pub const ALL: [&str; 18] = [
+        END_TURN, ADD, REMOVE, MODIFY, CONDITION, TIME, ROLL, THREAT, IMPORT, EXPORT,
+        DAMAGE, HEAL, TEMP_HP, UNDO, REDO, CLEAR, SET, HELP,
+    ];
 }
 
 
-pub fn parse_input(input: &str) -> ParseResult {
-    let sentences: Vec<&str> = input.split('-').map(str::trim).collect();
-    let main: &str = sentences[0];
-    let opts = &sentences[1..];
+/// Parses a line of input into a [`Command`], resolving character names
+/// against [`chrs`], the characters in initiative order.
+///
+/// Names are the words up to the next keyword, comma or option, joined by
+/// single spaces. Names containing keywords, commas or leading dashes can be
+/// given in quotes, like `rm "Dances on Water"`. Names of existing
+/// characters can be shortened as described in [`resolver::resolve`].
+///
+/// # Errors
+///
+/// This function will return an error if the input isn't a valid command,
+/// with the column where it went wrong.
+pub fn parse_input(input: &str, chrs: &[ChrName]) -> ParseResult {
+    let mut cursor = Cursor::new(input, chrs)?;
+    let keyword = cursor.next().ok_or_else(|| cursor.error(ErrorKind::EmptyInput))?;
+
+    let cmd = match keyword.text.as_str() {
+        command_strs::END_TURN => Command::EndTurn,
+        command_strs::THREAT => Command::Threat,
+        command_strs::UNDO => Command::Undo,
+        command_strs::REDO => Command::Redo,
+        command_strs::CLEAR => Command::Clear,
+        command_strs::ADD if cursor.eat_word("from") => parse_add_from(&mut cursor)?,
+        command_strs::ADD => parse_add(&mut cursor)?,
+        command_strs::REMOVE => Command::RmChr { name: chr_name(&mut cursor)? },
+        command_strs::MODIFY => parse_mod(&mut cursor)?,
+        command_strs::CONDITION => cond_parser::parse(&mut cursor)?,
+        command_strs::TIME => parse_time(&mut cursor)?,
+        command_strs::ROLL => parse_roll(&mut cursor)?,
+        command_strs::IMPORT => parse_import(&mut cursor)?,
+        command_strs::EXPORT => parse_export(&mut cursor)?,
+        command_strs::DAMAGE | command_strs::HEAL | command_strs::TEMP_HP => parse_health(&keyword.text, &mut cursor)?,
+        command_strs::SET => parse_set(&mut cursor)?,
+        command_strs::HELP => parse_help(&mut cursor)?,
+        word => return Err(cursor.error_at(&keyword, ErrorKind::InvalidKeyWord(word.to_string()))),
+    };
+    cursor.end()?;
+
+    Ok(cmd)
+}
+
+/// Takes the name of a single character.
+fn chr_name(cursor: &mut Cursor) -> Result<ChrName> {
+    cursor.chr_name(&[])?.ok_or_else(|| cursor.unexpected("<character>"))
+}
+
+fn parse_add_from(cursor: &mut Cursor) -> ParseResult {
+    let template = cursor.name(&[]).ok_or_else(|| cursor.unexpected("<template>"))?;
+    let mut map = AnyMap::new();
+    for (opt, values) in cursor.opts() {
+        parse_add_arg(cursor, &mut map, &opt, &values)?;
+    }
+
+    Ok(Command::AddFromTemplate {
+        template,
+        count: map.get::<CountArg>().map_or(1, |x| x.0),
+        variant: map.get::<VariantArg>().map(|x| x.0),
+    })
+}
+
+fn parse_add(cursor: &mut Cursor) -> ParseResult {
+    let init: i32 = cursor.parse("<init>", |arg, source| ErrorKind::ParseInt { arg, source })?;
+    // The name of a new character is taken as written.
+    let name = cursor.name(&[]).map(ChrName::new).ok_or_else(|| cursor.unexpected("<name>"))?;
+
+    let mut map = AnyMap::new();
+    for (opt, values) in cursor.opts() {
+        parse_add_arg(cursor, &mut map, &opt, &values)?;
+    }
+
+    Ok(Command::AddChr {
+        name,
+        init,
+        player: map.get::<PlayerArg>().is_some_and(|x| x.0),
+        health: map.get::<HealthArg>().map(|x| x.0),
+        level: map.get::<LevelArg>().map(|x| x.0),
+        group: map.get::<GroupArg>().map(|x| x.0),
+        count: map.get::<CountArg>().map(|x| x.0),
+        init_mod: map.get::<InitModArg>().map(|x| x.0),
+        variant: map.get::<VariantArg>().map(|x| x.0),
+    })
+}
+
+fn parse_mod(cursor: &mut Cursor) -> ParseResult {
+    let name = chr_name(cursor)?;
+
+    let mut map = AnyMap::new();
+    for (opt, values) in cursor.opts() {
+        parse_extra_arg(cursor, &mut map, &opt, &values)?;
+    }
+
+    Ok(Command::Mod {
+        name,
+        new_name: map.get::<NameArg>().map(|x| x.0.clone()),
+        init: map.get::<InitArg>().map(|x| x.0),
+        player: map.get::<PlayerArg>().map(|x| x.0),
+        health: map.get::<HealthArg>().map(|x| x.0),
+        level: map.get::<LevelArg>().map(|x| x.0),
+        init_mod: map.get::<InitModArg>().map(|x| x.0),
+    })
+}
+
+fn parse_time(cursor: &mut Cursor) -> ParseResult {
+    if cursor.eat_word("rest") {
+        return Ok(Command::Rest)
+    }
+    if cursor.eat_word("refocus") {
+        return Ok(Command::Refocus)
+    }
+
+    let start = cursor.error(ErrorKind::Missing("<time>"));
+    let words: Vec<Token> = cursor.words_until(&[]);
+    let words: Vec<&str> = words.iter().map(|token| token.text.as_str()).collect();
+    cond_parser::parse_duration(&words)
+        .map(Command::AdvanceTime)
+        .map_err(|err| Error { column: start.column, kind: err.into() })
+}
+
+fn parse_help(cursor: &mut Cursor) -> ParseResult {
+    let Some(topic) = cursor.next() else { return Ok(Command::Help(Topic::Summary)) };
+    let topic = match topic.text.as_str() {
+        command_strs::HELP => Topic::Help,
+        command_strs::END_TURN => Topic::EndTurn,
+        command_strs::ADD => Topic::Add,
+        command_strs::REMOVE => Topic::Remove,
+        command_strs::MODIFY => Topic::Modify,
+        command_strs::CONDITION => Topic::Condition,
+        command_strs::TIME => Topic::Time,
+        command_strs::ROLL => Topic::Roll,
+        command_strs::THREAT => Topic::Threat,
+        command_strs::IMPORT | command_strs::EXPORT => Topic::Import,
+        command_strs::DAMAGE => Topic::Damage,
+        command_strs::HEAL => Topic::Heal,
+        command_strs::TEMP_HP => Topic::TempHp,
+        command_strs::UNDO => Topic::Undo,
+        command_strs::REDO => Topic::Redo,
+        command_strs::CLEAR => Topic::Clear,
+        command_strs::SET => Topic::Set,
+        other => return Err(cursor.error_at(&topic, ErrorKind::InvalidHelpArg(other.to_string()))),
+    };
+    // The rest, such as `help cond add`, is ignored.
+    cursor.words_until(&[]);
+
+    Ok(Command::Help(topic))
+}
+
+/// Parses the health command [`keyword`], which takes `<amount> <character>`.
+fn parse_health(keyword: &str, cursor: &mut Cursor) -> ParseResult {
+    let amount = cursor.parse("<amount>", |arg, source| ErrorKind::ParseAmount { arg, source })?;
+    let name = chr_name(cursor)?;
 
-    let words: Vec<&str> = main.split_whitespace().collect();
-    let keyword = *words.first().ok_or(Error::EmptyInput)?;
-    let args = &words[1..];
     match keyword {
-        command_strs::END_TURN => Ok(Command::EndTurn),
-        command_strs::ADD => match args {
-            [init, name @ ..] => {
-                let init: i32 = init.parse().map_err(|err| Error::ParseInt { arg: (*init).to_string(), source: err })?;
-                let name = ChrName::new(unparse(name));
-
-                let mut map = AnyMap::new();
-
-                for opt in opts {
-                    parse_extra_arg(&mut map, opt)?;
-                }
-
-                Ok(Command::AddChr { 
-                    name,
-                    init,
-                    player: map.get::<PlayerArg>().is_some_and(|x| x.0), 
-                    health: map.get::<HealthArg>().map(|x| x.0)
-                })
-            },
-            _ => Err(Error::InvalidNumberOfArgs(args.len(), "add".into())) },
-        command_strs::REMOVE => {
-            let name = ChrName::new(args.iter().intersperse(&" ").fold(String::new(), |x, y| x + y));
-            Ok(Command::RmChr { name }) }
-        command_strs::MODIFY => {
-            let name = ChrName::new(args.iter().intersperse(&" ").fold(String::new(), |x, y| x + y));
-
-            let mut map = AnyMap::new();
-
-            for opt in opts {
-                parse_extra_arg(&mut map, opt)?;
-            }
-
-            
-            Ok(Command::Mod {
-                name,
-                new_name: map.get::<NameArg>().map(|x| x.0.clone()),
-                init: map.get::<InitArg>().map(|x| x.0),
-                player: map.get::<PlayerArg>().map(|x| x.0),
-                health: map.get::<HealthArg>().map(|x| x.0),
-            })
+        command_strs::DAMAGE => Ok(Command::Damage { name, amount }),
+        command_strs::HEAL => Ok(Command::Heal { name, amount }),
+        _ => Ok(Command::TempHp { name, amount }),
+    }
+}
+
+fn parse_set(cursor: &mut Cursor) -> ParseResult {
+    let invalid = |cursor: &mut Cursor| {
+        let err = cursor.error(ErrorKind::Missing("<setting>"));
+        let words: Vec<String> = cursor.words_until(&[]).into_iter().map(|token| token.text).collect();
+        Error { column: err.column, kind: ErrorKind::InvalidSetting(words.join(" ")) }
+    };
+
+    if cursor.eat_word("version") {
+        if cursor.eat_word("remastered") {
+            return Ok(Command::SetVersion(Pf2eVersion::Remastered))
         }
-        command_strs::CONDITION => {
-            let command = cond_parser::parse(args)?;
-            Ok(command)
-        },
-        command_strs::HELP => match args {
-            [command_strs::HELP, ..] => Ok(Command::Help(Topic::Help)),
-            [command_strs::END_TURN, ..] => Ok(Command::Help(Topic::EndTurn)),
-            [command_strs::ADD, ..] => Ok(Command::Help(Topic::Add)),
-            [command_strs::REMOVE, ..] => Ok(Command::Help(Topic::Remove)),
-            [command_strs::MODIFY, ..] => Ok(Command::Help(Topic::Modify)),
-            [command_strs::CONDITION, ..] => Ok(Command::Help(Topic::Condition)),
-            [] => Ok(Command::Help(Topic::Summary)),
-            other => Err(Error::InvalidHelpArg(unparse(other))),
-        },
+        if cursor.eat_word("legacy") || cursor.eat_word("old") {
+            return Ok(Command::SetVersion(Pf2eVersion::Old))
+        }
+        return Err(cursor.unexpected("`remastered` or `legacy`"))
+    }
+    if cursor.eat_word("undo") {
+        let size = cursor.parse("<size>", |arg, source| ErrorKind::ParseUndoSize { arg, source })?;
+        return Ok(Command::SetUndoSize(size))
+    }
 
-        word => Err(Error::InvalidKeyWord(word.to_string()))
+    Err(invalid(cursor))
+}
+
+fn parse_import(cursor: &mut Cursor) -> ParseResult {
+    let at = cursor.error(ErrorKind::Missing("<format>"));
+    let format = cursor.next();
+    let file = cursor.rest();
+    match format.as_ref().map(|token| token.text.as_str()) {
+        Some("pathbuilder") if !file.is_empty() => Ok(Command::ImportPathbuilder(file.into())),
+        Some("foundry") if !file.is_empty() => Ok(Command::ImportFoundry(file.into())),
+        format => Err(Error { column: at.column, kind: ErrorKind::InvalidImport(format!("{} {file}", format.unwrap_or_default()).trim().to_string()) }),
     }
 }
 
-fn unparse(name: &[&str]) -> String {
-    name.iter().intersperse(&" ").fold(String::new(), |acc, x| acc + x)
+fn parse_export(cursor: &mut Cursor) -> ParseResult {
+    let at = cursor.error(ErrorKind::Missing("<format>"));
+    let format = cursor.next();
+    let file = cursor.rest();
+    match format.as_ref().map(|token| token.text.as_str()) {
+        Some("foundry") if !file.is_empty() => Ok(Command::ExportFoundry(file.into())),
+        format => Err(Error { column: at.column, kind: ErrorKind::InvalidExport(format!("{} {file}", format.unwrap_or_default()).trim().to_string()) }),
+    }
+}
+
+fn parse_roll(cursor: &mut Cursor) -> ParseResult {
+    if cursor.eat_word("init") {
+        let names = cursor.chr_names(&[])?;
+        return Ok(Command::RollInit(names))
+    }
+
+    let at = cursor.error(ErrorKind::Missing("<dice>"));
+    cursor.rest().parse()
+        .map(Command::Roll)
+        .map_err(|err: dice::ParseError| Error { column: at.column, kind: err.into() })
 }
 
 struct HealthArg(u32);
 struct NameArg(String);
 struct InitArg(i32);
 struct PlayerArg(bool);
+struct LevelArg(i32);
+struct GroupArg(u32);
+struct CountArg(u32);
+struct InitModArg(i32);
+struct VariantArg(Variant);
 
 #[derive(Debug, Error)]
 pub enum ExtraArgError {
@@ -149,38 +383,308 @@ pub enum ExtraArgError {
     },
 }
 
-type ExtraArgResult = Result<(), ExtraArgError>;
+/// Parses the options of the add command, where `-n` is the number of
+/// characters to add rather than a new name.
+fn parse_add_arg(cursor: &Cursor, map: &mut AnyMap, opt: &Token, values: &[Token]) -> Result<()> {
+    match (opt.text.as_str(), values) {
+        ("n" | "count", [x]) => {
+            let x: u32 = x.text.parse().map_err(|err| cursor.error_at(x, ExtraArgError::ParseIntError { typ: "-n/-count".into(), val: x.text.clone(), source: err }))?;
+            map.insert(CountArg(x));
+        },
+        ("elite", []) => {
+            map.insert(VariantArg(Variant::Elite));
+        },
+        ("weak", []) => {
+            map.insert(VariantArg(Variant::Weak));
+        },
+        _ => parse_extra_arg(cursor, map, opt, values)?,
+    }
+
+    Ok(())
+}
+
+fn parse_extra_arg(cursor: &Cursor, map: &mut AnyMap, opt: &Token, values: &[Token]) -> Result<()> {
+    let int_err = |x: &Token, typ: &str, err| cursor.error_at(x, ExtraArgError::ParseIntError { typ: typ.into(), val: x.text.clone(), source: err });
+    let bool_err = |x: &Token, typ: &str, err| cursor.error_at(x, ExtraArgError::ParseBoolError { typ: typ.into(), val: x.text.clone(), source: err });
 
-fn parse_extra_arg(map: &mut AnyMap, opt: &&str) -> ExtraArgResult {
-    let words: Vec<&str> = opt.split_whitespace().collect();
-    match &words[..] {
-        ["h" | "health", x] => {
-            let x: u32 = x.parse().map_err(|err| ExtraArgError::ParseIntError { typ: "-h/-health".into(), val: (*x).to_string(), source: err })?;
+    match (opt.text.as_str(), values) {
+        ("h" | "health", [x]) => {
+            let x: u32 = x.text.parse().map_err(|err| int_err(x, "-h/-health", err))?;
             map.insert(HealthArg(x));
         },
-        ["n" | "name", x] => {
-            map.insert(NameArg((*x).to_string()));
+        ("n" | "name", [_, ..]) => {
+            let name: Vec<&str> = values.iter().map(|token| token.text.as_str()).collect();
+            map.insert(NameArg(name.join(" ")));
         },
-        ["i" | "init", x] => {
-            let x: i32 = x.parse().map_err(|err| ExtraArgError::ParseIntError { typ: "-i/-init".into(), val: (*x).to_string(), source: err })?;
+        ("i" | "init", [x]) => {
+            let x: i32 = x.text.parse().map_err(|err| int_err(x, "-i/-init", err))?;
             map.insert(InitArg(x));
         },
-        ["p" | "player", x] => {
-            let x: bool = x.parse().map_err(|err| ExtraArgError::ParseBoolError { typ: "-p/-player".into(), val: (*x).to_string(), source: err })?;
+        ("p" | "player", [x]) => {
+            let x: bool = x.text.parse().map_err(|err| bool_err(x, "-p/-player", err))?;
             map.insert(PlayerArg(x));
         },
-        ["p" | "player"] => {
+        ("p" | "player", []) => {
             map.insert(PlayerArg(true));
         },
-        ["e" | "enemy", x] => {
-            let x: bool = x.parse().map_err(|err| ExtraArgError::ParseBoolError { typ: "-e/-enemy".into(), val: (*x).to_string(), source: err })?;
+        ("e" | "enemy", [x]) => {
+            let x: bool = x.text.parse().map_err(|err| bool_err(x, "-e/-enemy", err))?;
             map.insert(PlayerArg(!x));
         },
-        ["e" | "enemy"] => {
+        ("e" | "enemy", []) => {
             map.insert(PlayerArg(false));
         }
-        _ => ()
+        ("l" | "level", [x]) => {
+            let x: i32 = x.text.parse().map_err(|err| int_err(x, "-l/-level", err))?;
+            map.insert(LevelArg(x));
+        },
+        ("m" | "initmod", [x]) => {
+            let x: i32 = x.text.parse().map_err(|err| int_err(x, "-m/-initmod", err))?;
+            map.insert(InitModArg(x));
+        },
+        ("g" | "group", [x]) => {
+            let x: u32 = x.text.parse().map_err(|err| int_err(x, "-g/-group", err))?;
+            map.insert(GroupArg(x));
+        },
+        (_, [_, extra, ..]) => return Err(cursor.error_at(extra, ErrorKind::Unexpected { expected: "the next option", found: extra.text.clone() })),
+        (opt_name, _) => return Err(cursor.error_at(opt, ErrorKind::UnknownOption(opt_name.to_string()))),
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{character::ChrName, gui::terminalgui::{Command, Topic}, settings::Pf2eVersion};
+    use crate::conditions::{Condition, NonValuedCondition};
+    use super::{cond_parser::NewCondition, parse_input, Error, ErrorKind};
+
+    #[test]
+    fn dmg_5_on_goblin_warrior_parses_correctly() -> Result<(), Error> {
+        let command = parse_input("dmg 5 Goblin Warrior", &[])?;
+        assert_eq!(Command::Damage { name: ChrName::new("Goblin Warrior"), amount: 5 }, command);
+
+        Ok(())
+    }
+
+    #[test]
+    fn heal_12_on_sarah_parses_correctly() -> Result<(), Error> {
+        let command = parse_input("heal 12 Sarah", &[])?;
+        assert_eq!(Command::Heal { name: ChrName::new("Sarah"), amount: 12 }, command);
+
+        Ok(())
+    }
+
+    #[test]
+    fn thp_4_on_sarah_parses_correctly() -> Result<(), Error> {
+        let command = parse_input("thp 4 Sarah", &[])?;
+        assert_eq!(Command::TempHp { name: ChrName::new("Sarah"), amount: 4 }, command);
+
+        Ok(())
+    }
+
+    #[test]
+    fn dmg_without_character_is_invalid() {
+        let res = parse_input("dmg 5", &[]);
+        assert!(matches!(res, Err(Error { column: 6, kind: ErrorKind::Missing(_) })));
+    }
+
+    #[test]
+    fn negative_heal_is_invalid() {
+        let res = parse_input("heal -5 Sarah", &[]);
+        assert!(matches!(res, Err(Error { kind: ErrorKind::ParseAmount { .. }, .. })));
+    }
+
+    #[test]
+    fn dmg_with_word_amount_is_invalid() {
+        let res = parse_input("dmg five Sarah", &[]);
+        assert!(matches!(res, Err(Error { column: 5, kind: ErrorKind::ParseAmount { .. } })));
+    }
+
+    #[test]
+    fn undo_redo_and_clear_parse_correctly() -> Result<(), Error> {
+        assert_eq!(Command::Undo, parse_input("undo", &[])?);
+        assert_eq!(Command::Redo, parse_input("redo", &[])?);
+        assert_eq!(Command::Clear, parse_input("clear", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_version_parses_correctly() -> Result<(), Error> {
+        assert_eq!(Command::SetVersion(Pf2eVersion::Remastered), parse_input("set version remastered", &[])?);
+        assert_eq!(Command::SetVersion(Pf2eVersion::Old), parse_input("set version legacy", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_undo_32_parses_correctly() -> Result<(), Error> {
+        assert_eq!(Command::SetUndoSize(32), parse_input("set undo 32", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn set_undo_with_word_size_is_invalid() {
+        let res = parse_input("set undo abc", &[]);
+        assert!(matches!(res, Err(Error { column: 10, kind: ErrorKind::ParseUndoSize { .. } })));
+    }
+
+    #[test]
+    fn set_unknown_setting_is_invalid() {
+        let res = parse_input("set colour blue", &[]);
+        assert!(matches!(res, Err(Error { kind: ErrorKind::InvalidSetting(setting), .. }) if setting == "colour blue"));
+    }
+
+    #[test]
+    fn help_with_new_commands_parses_correctly() -> Result<(), Error> {
+        assert_eq!(Command::Help(Topic::Damage), parse_input("help dmg", &[])?);
+        assert_eq!(Command::Help(Topic::Heal), parse_input("help heal", &[])?);
+        assert_eq!(Command::Help(Topic::TempHp), parse_input("help thp", &[])?);
+        assert_eq!(Command::Help(Topic::Undo), parse_input("help undo", &[])?);
+        assert_eq!(Command::Help(Topic::Redo), parse_input("help redo", &[])?);
+        assert_eq!(Command::Help(Topic::Clear), parse_input("help clear", &[])?);
+        assert_eq!(Command::Help(Topic::Set), parse_input("help set", &[])?);
+
+        Ok(())
+    }
+
+    fn add_chr(name: &str, init: i32, health: Option<u32>) -> Command {
+        Command::AddChr {
+            name: ChrName::new(name),
+            init,
+            player: false,
+            health,
+            level: None,
+            group: None,
+            count: None,
+            init_mod: None,
+            variant: None,
+        }
+    }
+
+    fn prone_on(names: &[&str]) -> Command {
+        Command::AddCond {
+            characters: names.iter().map(|name| ChrName::new(*name)).collect(),
+            cond: NewCondition::NonValued { cond: NonValuedCondition::Prone, term: None },
+        }
+    }
+
+    #[test]
+    fn add_half_orc_keeps_the_dash() -> Result<(), Error> {
+        assert_eq!(add_chr("Half-Orc", 12, None), parse_input("add 12 Half-Orc", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_jean_luc_with_health_keeps_the_dash() -> Result<(), Error> {
+        assert_eq!(add_chr("Jean-Luc", 12, Some(20)), parse_input("add 12 Jean-Luc -h 20", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_name_with_from_in_quotes() -> Result<(), Error> {
+        assert_eq!(add_chr("Man from Nowhere", 12, None), parse_input("add 12 \"Man from Nowhere\"", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rm_name_with_dash() -> Result<(), Error> {
+        assert_eq!(Command::RmChr { name: ChrName::new("Half-Orc") }, parse_input("rm Half-Orc", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cond_add_on_leon_is_not_split_on_on() -> Result<(), Error> {
+        assert_eq!(prone_on(&["Leon"]), parse_input("cond add prone on Leon", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cond_add_on_quoted_name_with_on() -> Result<(), Error> {
+        assert_eq!(prone_on(&["Dances on Water", "Leon"]), parse_input("cond add prone on \"Dances on Water\", Leon", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cond_rm_from_quoted_name_with_from() -> Result<(), Error> {
+        let expected = Command::RmCond {
+            character: ChrName::new("Man from Nowhere"),
+            cond: Condition::builder().condition(NonValuedCondition::Prone).build(),
+        };
+        assert_eq!(expected, parse_input("cond rm prone from \"Man from Nowhere\"", &[])?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mod_rename_to_quoted_name() -> Result<(), Error> {
+        let command = parse_input("mod Goblin -n \"Goblin -the Sneak\"", &[])?;
+        assert!(matches!(command, Command::Mod { new_name: Some(name), .. } if name == "Goblin -the Sneak"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_option_points_at_the_option() {
+        let res = parse_input("add 12 Half-Orc -x 3", &[]);
+        assert!(matches!(res, Err(Error { column: 17, kind: ErrorKind::UnknownOption(opt) }) if opt == "x"));
+    }
+
+    #[test]
+    fn invalid_health_points_at_the_value() {
+        let res = parse_input("add 12 Orc -h lots", &[]);
+        assert!(matches!(res, Err(Error { column: 15, kind: ErrorKind::InvalidExtraArg(_) })));
+    }
+
+    #[test]
+    fn trailing_words_point_at_the_first_one() {
+        let res = parse_input("undo twice", &[]);
+        assert!(matches!(res, Err(Error { column: 6, kind: ErrorKind::Unexpected { .. } })));
+    }
+
+    #[test]
+    fn unterminated_quote_points_at_the_quote() {
+        let res = parse_input("rm \"Sarah", &[]);
+        assert!(matches!(res, Err(Error { column: 4, kind: ErrorKind::UnterminatedQuote })));
+    }
+
+    fn party() -> Vec<ChrName> {
+        vec![ChrName::new("Goblin Warrior"), ChrName::new("Goblin Warchanter"), ChrName::new("Sarah")]
+    }
+
+    #[test]
+    fn rm_resolves_prefix_and_position() -> Result<(), Error> {
+        assert_eq!(Command::RmChr { name: ChrName::new("Sarah") }, parse_input("rm sa", &party())?);
+        assert_eq!(Command::RmChr { name: ChrName::new("Goblin Warchanter") }, parse_input("rm #2", &party())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn cond_resolves_every_target() -> Result<(), Error> {
+        assert_eq!(prone_on(&["Goblin Warrior", "Sarah"]), parse_input("cond add prone on goblin warr, SARAH", &party())?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mod_with_ambiguous_prefix_lists_candidates() {
+        let res = parse_input("mod gob -h 10", &party());
+        assert!(matches!(res, Err(Error { column: 5, kind: ErrorKind::AmbiguousName { candidates, .. } }) if candidates.len() == 2));
+    }
+
+    #[test]
+    fn add_keeps_new_name_as_written() -> Result<(), Error> {
+        assert_eq!(add_chr("sa", 12, None), parse_input("add 12 sa", &party())?);
+
+        Ok(())
+    }
+}
@@ -1,13 +1,11 @@
 use thiserror::Error;
 use crate::character::ChrName;
-use crate::conditions::{Condition, NonValuedTerm, TurnEvent, ValuedTerm};
-use crate::duration::Duration;
-use super::Command;
+use crate::conditions::{Condition, NonValuedCondition, NonValuedTerm, TurnEvent, ValuedCondition, ValuedTerm};
+use crate::duration::{self, Duration};
+use super::{cursor::Cursor, lexer::TokenKind, Command, ParseResult};
 
-use super::unparse;
-
-mod nonvalued_conditions;
-mod valued_conditions;
+pub mod nonvalued_conditions;
+pub mod valued_conditions;
 
 
 #[derive(Error)]
@@ -37,184 +35,195 @@ pub enum Error {
         arg: String,
         #[source] source: std::num::ParseIntError,
     },
+    #[error("invalid duration: {0}")]
+    InvalidDuration(#[from] duration::ParseError),
+    #[error("`cond mod` is not supported yet, remove the condition and add it again instead")]
+    ModUnsupported,
     #[error("expected syntax `cond add <condition> [<value>] [<termination>] on <name>`, but input was missing `<name>` or `on <name>`")]
     MissingChr,
 }
 
 type Result<T> = std::result::Result<T,Error>;
 
-pub fn parse(args: &[&str]) -> Result<Command> {
-    match args.first() {
-        Some(&"add") => {
-            let split: Vec<_> = args.split(|s| s == &"on").collect();
-            let cond_args = split.first().expect("Internal error: illegal state reached due to internal logical error");
-            let character_str = unparse(split.get(1).ok_or(Error::MissingChr)?);
-            let character = ChrName::new(character_str);
-
-            match &cond_args.get(1..) {
-                Some([cond_name, value]) => {
-                    let cond_type = valued_conditions::parse(cond_name)?;
-                    let value = parse_value(value)?;
-                    let cond = Condition::builder().condition(cond_type).value(value).build();
-                    Ok(Command::AddCond { character, cond })
-                },
-                Some([cond_name, value, term_type @ ("for" | "until" | "reduced"), term_trigger @ ..]) => {
-                    let value = parse_value(value)?;
-                    let cond_type = valued_conditions::parse(cond_name)?;
-                    let term = parse_valued_term(character.clone(), term_type, term_trigger)?;
-                    let cond = Condition::builder()
-                        .condition(cond_type)
-                        .value(value)
-                        .term(term)
-                        .build();
-
-                    Ok(Command::AddCond { character, cond })
-                }
-                Some([cond_name]) => {
-                    let cond_type = nonvalued_conditions::parse(cond_name)?;
-                    let cond = Condition::builder().condition(cond_type).build();
-                    Ok(Command::AddCond { character, cond })
-                },
-                Some([cond_name, term_type @ ("for" | "until"), term_trigger @ ..]) => {
-                    let cond_type = nonvalued_conditions::parse(cond_name)?;
-                    let term = parse_nonvalued_term(character.clone(), term_type, term_trigger)?;
-                    let cond = Condition::builder()
-                        .condition(cond_type)
-                        .term(term)
-                        .build();
-
-                    Ok(Command::AddCond{ character, cond })
-                }
-                _ => Err(Error::InvalidSyntax{
-                    ty: "condition",
-                    expected: "cond add <condition> [<value>] [<termination>] on <character>",
-                    actual: args.iter().intersperse(&" ").fold(String::from("add "), |acc,word| acc + word)
-                })
-            }
-        },
-        Some(&"rm") => {
-            match args.get(1..) {
-                Some([cond, _, "from", character @ ..] | [cond, "from", character @ ..]) => {
-                    nonvalued_conditions::parse(cond)
-                        .map(|cond| Condition::builder().condition(cond).build())
-                        .or_else(|_| valued_conditions::parse(cond).map(|cond| Condition::builder().condition(cond).value(1).build()))
-                        .map(|cond| Command::RmCond { cond, character: ChrName::new(unparse(character)) })
-                },
-                Some(s) => Err(Error::InvalidSyntax {
-                    ty: "condition",
-                    expected: "cond rm <condition> from <character>", 
-                    actual: s.iter().intersperse(&" ").fold(String::from("cond "), |acc, x| acc + x)
-                }),
-                None => Err(Error::InvalidSyntax { 
-                    ty: "condition",
-                    expected: "cond rm <condition> from <character>",
-                    actual: String::from("cond rm")
-                }),
-            }
-        },
-        Some(&"mod") => unimplemented!(),
-        Some(s) => Err(Error::InvalidKeyword {
-            ty: "condition",
-            expected: "add, rm or mod",
-            actual: (*s).to_string(),
-        }),
-        None => Err(Error::MissingKeyword("condition"))
-    }
+/// A turn event whose character may be left out, in which case it is each
+/// of the characters getting the condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub start: bool,
+    pub character: Option<ChrName>,
 }
 
-fn parse_nonvalued_term(character: ChrName, term_type: &str, term_action: &[&str]) -> Result<NonValuedTerm> {
-    match term_type {
-        "for" => parse_duration(term_action).map(NonValuedTerm::For),
-        "until" => parse_turn_event(character, term_action).map(NonValuedTerm::Until),
-        s => Err(Error::InvalidSyntax { 
-            ty: "condition termination", 
-            expected: "for <duration> | until <event>", 
-            actual: s.to_string()
-        })
+impl Event {
+    fn resolve(&self, affected: &ChrName) -> TurnEvent {
+        let character = self.character.clone().unwrap_or_else(|| affected.clone());
+        if self.start { TurnEvent::StartOfNextTurn(character) } else { TurnEvent::EndOfNextTurn(character) }
     }
 }
 
-fn parse_valued_term(character: ChrName, term_type: &str, term_action: &[&str]) -> Result<ValuedTerm> {
-    match term_type {
-        "for" => parse_duration(term_action).map(ValuedTerm::For),
-        "until" => parse_turn_event(character, term_action).map(ValuedTerm::Until),
-        "reduced" => match term_action {
-            ["by", n, "at", turn_event @ ..] | 
-            ["by", n, turn_event @ ..] => {
-                let n = parse_value(n)?;
-                let event = parse_turn_event(character, turn_event)?;
-
-                Ok(ValuedTerm::Reduced(event, n))
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    For(Duration),
+    Until(Event),
+    Reduced(Event, u8),
+}
+
+/// A condition to add, which becomes a [`Condition`] once it is known who
+/// gets it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NewCondition {
+    Valued { cond: ValuedCondition, level: u8, term: Option<Term> },
+    NonValued { cond: NonValuedCondition, term: Option<Term> },
+}
+
+impl NewCondition {
+    /// Builds the condition [`affected`] gets, with turn events that don't
+    /// name a character waiting for the turn of [`affected`].
+    pub fn build(&self, affected: &ChrName) -> Condition {
+        match self {
+            Self::Valued { cond, level, term } => {
+                let builder = Condition::builder().condition(*cond).value(*level);
+                match term {
+                    Some(Term::For(duration)) => builder.term(ValuedTerm::For(*duration)).build(),
+                    Some(Term::Until(event)) => builder.term(ValuedTerm::Until(event.resolve(affected))).build(),
+                    Some(Term::Reduced(event, n)) => builder.term(ValuedTerm::Reduced(event.resolve(affected), *n)).build(),
+                    None => builder.build(),
+                }
             },
-            _ => Err(Error::InvalidSyntax { 
-                ty: "reduction termination",
-                expected: "reduced by <value> at <turn event>",
-                actual: format!("reduced {}", &unparse(term_action))
-            })
-        },
-        _ => Err(Error::InvalidKeyword { 
-            ty: "termination", 
-            expected: "for, until or reduced", 
-            actual: term_type.to_string()
-        })
+            Self::NonValued { cond, term } => {
+                let builder = Condition::builder().condition(*cond);
+                match term {
+                    Some(Term::For(duration)) => builder.term(NonValuedTerm::For(*duration)).build(),
+                    Some(Term::Until(event)) => builder.term(NonValuedTerm::Until(event.resolve(affected))).build(),
+                    Some(Term::Reduced(..)) | None => builder.build(),
+                }
+            },
+        }
     }
 }
 
-fn parse_value(n: &str) -> Result<u8> {
-    n.parse().map_err(|err| Error::ParseInt { 
-        arg: n.to_string(), 
-        source: err
-    })
+/// Parses the condition command, following the `cond` keyword.
+///
+/// # Errors
+///
+/// This function will return an error if the command isn't
+/// `cond add <condition> [<value>] [<termination>] on <names>` or
+/// `cond rm <condition> [<value>] from <name>`.
+pub fn parse(cursor: &mut Cursor) -> ParseResult {
+    let Some(keyword) = cursor.next() else { return Err(cursor.error(Error::MissingKeyword("condition"))) };
+    match keyword.text.as_str() {
+        "add" if keyword.kind == TokenKind::Word => parse_add(cursor),
+        "rm" if keyword.kind == TokenKind::Word => parse_rm(cursor),
+        "mod" if keyword.kind == TokenKind::Word => Err(cursor.error_at(&keyword, Error::ModUnsupported)),
+        s => Err(cursor.error_at(&keyword, Error::InvalidKeyword {
+            ty: "condition",
+            expected: "add, rm or mod",
+            actual: s.to_string(),
+        })),
+    }
 }
 
-fn parse_turn_event(character: ChrName, term_action: &[&str]) -> Result<TurnEvent> {
-    match term_action {
-        ["start", "of", "turn"] => Ok(TurnEvent::StartOfNextTurn(character)),
-        ["end", "of", "turn"] => Ok(TurnEvent::EndOfNextTurn(character)),
-        ["start", "of", character_str @ .., "turn"] => {
-            let character = ChrName::new(unparse(character_str));
-            Ok(TurnEvent::StartOfNextTurn(character))
+fn parse_add(cursor: &mut Cursor) -> ParseResult {
+    let cond_name = cursor.word("<condition>")?;
+    let has_value = cursor.peek().is_some_and(|token| {
+        token.kind == TokenKind::Word && !["for", "until", "reduced", "on"].contains(&token.text.as_str())
+    });
+    let value = if has_value { Some(parse_value(cursor)?) } else { None };
+    let term = parse_term(cursor, value.is_some())?;
+
+    if !cursor.eat_word("on") {
+        return Err(if cursor.at_end() { cursor.error(Error::MissingChr) } else { cursor.unexpected("`on`") })
+    }
+    let at = cursor.error(Error::MissingChr);
+    let characters = cursor.chr_names(&[])?;
+    if characters.is_empty() {
+        return Err(at)
+    }
+
+    let cond = match value {
+        Some(level) => {
+            let cond = valued_conditions::parse(&cond_name.text).map_err(|e| cursor.error_at(&cond_name, e))?;
+            NewCondition::Valued { cond, level, term }
         },
-        ["end", "of", character_str @ .., "turn"] => {
-            let character = ChrName::new(unparse(character_str));
-            Ok(TurnEvent::EndOfNextTurn(character))
+        None => {
+            let cond = nonvalued_conditions::parse(&cond_name.text).map_err(|e| cursor.error_at(&cond_name, e))?;
+            NewCondition::NonValued { cond, term }
         },
-        s => Err(Error::InvalidSyntax { 
-            ty: "turn event", 
-            expected: "(start | end) of <character> turn", 
-            actual: s.iter().intersperse(&" ").fold(String::new(), |acc, x| acc + x)
-        })
+    };
+
+    Ok(Command::AddCond { characters, cond })
+}
+
+fn parse_rm(cursor: &mut Cursor) -> ParseResult {
+    let cond_name = cursor.word("<condition>")?;
+    // The value is allowed, but not needed to remove the condition.
+    if cursor.peek().is_some_and(|token| token.kind == TokenKind::Word && token.text != "from") {
+        cursor.next();
     }
-    
+    cursor.expect_word("from")?;
+    let character = cursor.chr_name(&[])?.ok_or_else(|| cursor.unexpected("<character>"))?;
+
+    let cond = nonvalued_conditions::parse(&cond_name.text)
+        .map(|cond| Condition::builder().condition(cond).build())
+        .or_else(|_| valued_conditions::parse(&cond_name.text).map(|cond| Condition::builder().condition(cond).value(1).build()))
+        .map_err(|e| cursor.error_at(&cond_name, e))?;
+
+    Ok(Command::RmCond { cond, character })
 }
 
-fn parse_duration(term_action: &[&str]) -> Result<Duration> {
-    match term_action {
-        [n, unit] => {
-            let number = n.parse().map_err(|err| Error::ParseInt { 
-                arg: (*n).to_string(), 
-                source: err
-            })?;
-            let dur = match *unit {
-                "t" | "turn" | "turns" => Duration::from_turns(number),
-                "m" | "min" | "mins" | "minute" | "minutes" => 
-                    Duration::from_minutes(number),
-                "h" | "hour" | "hours" => Duration::from_hours(number),
-                unit => Err(Error::InvalidKeyword { 
-                    ty: "duration unit", 
-                    expected: "t, turn, turns, m, min, mins, minute, minutes, h, hour or hours", 
-                    actual:  unit.to_string()
-                })?
-            };
-
-            Ok(dur)
-        },
-        s => Err(Error::InvalidSyntax { 
-            ty: "duration",
-            expected: "<number> <unit>", 
-            actual: s.iter().intersperse(&" ").fold(String::new(), |acc, x| acc + x)
-        })
+/// Parses the optional termination of a condition, where `reduced by` is
+/// only allowed for [`valued`] conditions.
+fn parse_term(cursor: &mut Cursor, valued: bool) -> super::Result<Option<Term>> {
+    if cursor.eat_word("for") {
+        let at = cursor.error(Error::MissingChr);
+        let words = cursor.words_until(&["on"]);
+        let words: Vec<&str> = words.iter().map(|token| token.text.as_str()).collect();
+        return parse_duration(&words)
+            .map(|duration| Some(Term::For(duration)))
+            .map_err(|e| super::Error { column: at.column, kind: e.into() })
+    }
+    if cursor.eat_word("until") {
+        return parse_turn_event(cursor).map(|event| Some(Term::Until(event)))
+    }
+    if cursor.at_word("reduced") {
+        let reduced = cursor.next().expect("Internal error: `reduced` was just peeked");
+        if !valued {
+            return Err(cursor.error_at(&reduced, Error::InvalidKeyword {
+                ty: "termination",
+                expected: "for or until",
+                actual: reduced.text.clone(),
+            }))
+        }
+        cursor.expect_word("by")?;
+        let n = parse_value(cursor)?;
+        cursor.eat_word("at");
+        return parse_turn_event(cursor).map(|event| Some(Term::Reduced(event, n)))
     }
+
+    Ok(None)
+}
+
+fn parse_value(cursor: &mut Cursor) -> super::Result<u8> {
+    cursor.parse("<value>", |arg, source| Error::ParseInt { arg, source }.into())
+}
+
+/// Parses `(start | end) of [<character>] turn`.
+fn parse_turn_event(cursor: &mut Cursor) -> super::Result<Event> {
+    let start = if cursor.eat_word("start") {
+        true
+    } else if cursor.eat_word("end") {
+        false
+    } else {
+        return Err(cursor.unexpected("`start` or `end`"))
+    };
+    cursor.expect_word("of")?;
+    let character = cursor.chr_name(&["turn"])?;
+    cursor.expect_word("turn")?;
+
+    Ok(Event { start, character })
+}
+
+pub fn parse_duration(term_action: &[&str]) -> Result<Duration> {
+    Ok(term_action.join(" ").parse()?)
 }
 
 #[cfg(test)]
@@ -223,19 +232,25 @@ mod tests {
     use crate::duration::Duration;
     use crate::gui::terminalgui::Command;
     use crate::conditions::{Condition, DamageType, NonValuedCondition, NonValuedTerm, TurnEvent, ValuedCondition, ValuedTerm};
-    use super::Error;
+    use crate::gui::terminalgui::parser::{parse_input, ErrorKind};
+    use super::{Error, Event, NewCondition, Term};
     use super::{nonvalued_conditions as nv_conds, valued_conditions as v_conds};
-    use super::parse;
+
+    /// Parses `cond` followed by [`args`], unwrapping condition errors.
+    fn parse(args: &[&str]) -> super::Result<Command> {
+        parse_input(&format!("cond {}", args.join(" ")), &[]).map_err(|err| match err.kind {
+            ErrorKind::CondParser(e) => e,
+            kind => panic!("{kind}"),
+        })
+    }
 
     #[test]
     fn add_blinded_on_alice_parses_correctly() -> super::Result<()> {
         let input = ["add",nv_conds::BLINDED,"on","Alice"];
         let command = parse(&input)?;
         let expected = Command::AddCond {
-            character: ChrName::new(String::from("Alice")),
-            cond: Condition::builder()
-                .condition(NonValuedCondition::Blinded)
-                .build()
+            characters: vec![ChrName::new(String::from("Alice"))],
+            cond: NewCondition::NonValued { cond: NonValuedCondition::Blinded, term: None },
         };
 
         assert_eq!(expected, command);
@@ -248,11 +263,8 @@ mod tests {
         let input = ["add",v_conds::PERSISTENT_BLEED,"5","on","Bob"];
         let command = parse(&input)?;
         let expected = Command::AddCond {
-            character: ChrName::new(String::from("Bob")),
-            cond: Condition::builder()
-                .condition(ValuedCondition::PersistentDamage(DamageType::Bleed))
-                .value(5)
-                .build()
+            characters: vec![ChrName::new(String::from("Bob"))],
+            cond: NewCondition::Valued { cond: ValuedCondition::PersistentDamage(DamageType::Bleed), level: 5, term: None },
         };
 
         assert_eq!(expected, command);
@@ -265,11 +277,11 @@ mod tests {
         let input = ["add",nv_conds::DAZZLED,"until","end","of","Bob","turn","on","Alice"];
         let command = parse(&input)?;
         let expected = Command::AddCond {
-            character: ChrName::new(String::from("Alice")),
-            cond: Condition::builder()
-                .condition(NonValuedCondition::Dazzled)
-                .term(NonValuedTerm::Until(TurnEvent::EndOfNextTurn(ChrName::new(String::from("Bob")))))
-                .build()
+            characters: vec![ChrName::new(String::from("Alice"))],
+            cond: NewCondition::NonValued {
+                cond: NonValuedCondition::Dazzled,
+                term: Some(Term::Until(Event { start: false, character: Some(ChrName::new(String::from("Bob"))) })),
+            },
         };
 
         assert_eq!(expected, command);
@@ -282,12 +294,12 @@ mod tests {
         let input = ["add",v_conds::FRIGHTENED,"2","reduced","by","1","end","of","Alice","turn","on","Alice"];
         let command = parse(&input)?;
         let expected = Command::AddCond {
-            character: ChrName::new(String::from("Alice")),
-            cond: Condition::builder()
-                .condition(ValuedCondition::Frightened)
-                .value(2)
-                .term(ValuedTerm::Reduced(TurnEvent::EndOfNextTurn(ChrName::new(String::from("Alice"))), 1))
-                .build()
+            characters: vec![ChrName::new(String::from("Alice"))],
+            cond: NewCondition::Valued {
+                cond: ValuedCondition::Frightened,
+                level: 2,
+                term: Some(Term::Reduced(Event { start: false, character: Some(ChrName::new(String::from("Alice"))) }, 1)),
+            },
         };
 
         assert_eq!(expected, command);
@@ -300,12 +312,12 @@ mod tests {
         let input = ["add",v_conds::DRAINED,"2","for","12","hours","on","Alice"];
         let command = parse(&input)?;
         let expected = Command::AddCond {
-            character: ChrName::new(String::from("Alice")),
-            cond: Condition::builder()
-                .condition(ValuedCondition::Drained)
-                .value(2)
-                .term(ValuedTerm::For(Duration::from_hours(12)))
-                .build()
+            characters: vec![ChrName::new(String::from("Alice"))],
+            cond: NewCondition::Valued {
+                cond: ValuedCondition::Drained,
+                level: 2,
+                term: Some(Term::For(Duration::from_hours(12))),
+            },
         };
 
         assert_eq!(expected, command);
@@ -318,11 +330,43 @@ mod tests {
         let input = ["add",nv_conds::BLINDED,"for","8","hours","on","Bob"];
         let command = parse(&input)?;
         let expected = Command::AddCond {
-            character: ChrName::new(String::from("Bob")),
-            cond: Condition::builder()
-                .condition(NonValuedCondition::Blinded)
-                .term(NonValuedTerm::For(Duration::from_hours(8)))
-                .build()
+            characters: vec![ChrName::new(String::from("Bob"))],
+            cond: NewCondition::NonValued {
+                cond: NonValuedCondition::Blinded,
+                term: Some(Term::For(Duration::from_hours(8))),
+            },
+        };
+
+        assert_eq!(expected, command);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_frightened_1_on_two_goblins_and_orc_parses_correctly() -> super::Result<()> {
+        let input = ["add",v_conds::FRIGHTENED,"1","on","Goblin","1,","Goblin","2,","Orc"];
+        let command = parse(&input)?;
+        let expected = Command::AddCond {
+            characters: vec![ChrName::new("Goblin 1"), ChrName::new("Goblin 2"), ChrName::new("Orc")],
+            cond: NewCondition::Valued { cond: ValuedCondition::Frightened, level: 1, term: None },
+        };
+
+        assert_eq!(expected, command);
+
+        Ok(())
+    }
+
+    #[test]
+    fn add_slowed_until_end_of_turn_on_many_leaves_the_character_out() -> super::Result<()> {
+        let input = ["add",v_conds::SLOWED,"1","until","end","of","turn","on","Alice,","Bob"];
+        let command = parse(&input)?;
+        let expected = Command::AddCond {
+            characters: vec![ChrName::new("Alice"), ChrName::new("Bob")],
+            cond: NewCondition::Valued {
+                cond: ValuedCondition::Slowed,
+                level: 1,
+                term: Some(Term::Until(Event { start: false, character: None })),
+            },
         };
 
         assert_eq!(expected, command);
@@ -330,6 +374,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn turn_event_without_character_waits_for_each_affected() {
+        let cond = NewCondition::Valued {
+            cond: ValuedCondition::Slowed,
+            level: 1,
+            term: Some(Term::Until(Event { start: false, character: None })),
+        };
+
+        let bob = ChrName::new("Bob");
+        assert!(matches!(cond.build(&bob), Condition::Valued { term: ValuedTerm::Until(TurnEvent::EndOfNextTurn(name)), .. } if name == bob));
+    }
+
+    #[test]
+    fn turn_event_naming_first_target_waits_for_them_on_every_target() {
+        let alice = ChrName::new("Alice");
+        let cond = NewCondition::NonValued {
+            cond: NonValuedCondition::Dazzled,
+            term: Some(Term::Until(Event { start: true, character: Some(alice.clone()) })),
+        };
+
+        for affected in [ChrName::new("Alice"), ChrName::new("Bob")] {
+            assert!(matches!(cond.build(&affected), Condition::NonValued { term: NonValuedTerm::Until(TurnEvent::StartOfNextTurn(name)), .. } if name == alice));
+        }
+    }
+
+    #[test]
+    fn add_blinded_on_only_commas_is_missing_character() {
+        let input = ["add",nv_conds::BLINDED,"on",",",","];
+        let result = parse(&input);
+        assert_eq!(Err(Error::MissingChr), result);
+    }
+
     #[test]
     fn add_frightened_negative_2_on_bob() {
         let input = ["add",v_conds::FRIGHTENED,"-2","on","Bob"];
@@ -389,5 +465,16 @@ mod tests {
         fn five_h_parses() {
             assert_eq!(Ok(Duration::from_hours(5)), parse_duration(&["5","h"]));
         }
+
+        #[test]
+        fn compound_duration_parses() {
+            let expected = Duration::builder().with_hours(1).with_minutes(30).build();
+            assert_eq!(Ok(expected), parse_duration(&["1h30m"]));
+        }
+
+        #[test]
+        fn three_rounds_parses() {
+            assert_eq!(Ok(Duration::from_rounds(3)), parse_duration(&["3","rounds"]));
+        }
     }
 }
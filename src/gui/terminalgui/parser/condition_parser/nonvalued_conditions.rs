@@ -31,6 +31,39 @@ pub const UNDETECTED: &str      = "undetected";
 pub const UNFRIENDLY: &str      = "unfriendly";
 pub const UNNOTICED: &str       = "unnoticed";
 
+/// Every nonvalued condition name, for completion.
+pub const ALL: [&str; 29] = [
+    BLINDED,
+    BROKEN,
+    CONCEALED,
+    CONFUSED,
+    DAZZLED,
+    DEAFENED,
+    ENCUMBERED,
+    FASCINATED,
+    FATIGUED,
+    FLATFOOTED,
+    FLEEING,
+    FRIENDLY,
+    GRABBED,
+    HELPFUL,
+    HIDDEN,
+    HOSTILE,
+    IMMOBILIZED,
+    INDIFFERENT,
+    INVISIBLE,
+    OBSERVED,
+    PARALYZED,
+    PETRIFIED,
+    PRONE,
+    QUICKENED,
+    RESTRAINED,
+    UNCONSCIOUS,
+    UNDETECTED,
+    UNFRIENDLY,
+    UNNOTICED,
+];
+
 #[coverage(off)]
 pub fn parse(cond_name: &str) -> Result<NonValuedCondition> {
     match cond_name {
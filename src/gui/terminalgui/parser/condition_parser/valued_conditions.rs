@@ -53,6 +53,37 @@ pub const PERSISTENT_EVIL: &str          = concatcp!(PERSISTENT, SEP, EVIL);
 pub const PERSISTENT_GOOD: &str          = concatcp!(PERSISTENT, SEP, GOOD);
 pub const PERSISTENT_LAWFUL: &str        = concatcp!(PERSISTENT, SEP, LAWFUL);
 
+/// Every valued condition name, for completion.
+pub const ALL: [&str; 27] = [
+    CLUMSY,
+    DOOMED,
+    DRAINED,
+    DYING,
+    ENFEEBLED,
+    FRIGHTENED,
+    SICKENED,
+    SLOWED,
+    STUNNED,
+    STUPIFIED,
+    WOUNDED,
+    PERSISTENT_BLEED,
+    PERSISTENT_POISON,
+    PERSISTENT_PIERCING,
+    PERSISTENT_BLUDGEONING,
+    PERSISTENT_SLASHING,
+    PERSISTENT_ACID,
+    PERSISTENT_COLD,
+    PERSISTENT_ELECTRICITY,
+    PERSISTENT_SONIC,
+    PERSISTENT_POSITIVE,
+    PERSISTENT_NEGATIVE,
+    PERSISTENT_FORCE,
+    PERSISTENT_CHAOTIC,
+    PERSISTENT_EVIL,
+    PERSISTENT_GOOD,
+    PERSISTENT_LAWFUL,
+];
+
 #[coverage(off)]
 pub fn parse(cond_name: &str) -> Result<ValuedCondition> {
     match cond_name {
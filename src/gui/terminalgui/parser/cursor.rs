@@ -0,0 +1,212 @@
+use std::str::FromStr;
+
+use crate::character::ChrName;
+
+use super::{lexer::{self, Token, TokenKind}, resolver, Error, ErrorKind, Result};
+
+/// Walks through the [`Token`]s of a command, keeping the input around to
+/// point errors at where they are, and the characters to resolve names to.
+#[derive(Debug, Clone)]
+pub struct Cursor<'a> {
+    input: &'a str,
+    chrs: &'a [ChrName],
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Tokenizes [`input`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if [`lexer::tokenize`] fails.
+    pub fn new(input: &'a str, chrs: &'a [ChrName]) -> Result<Self> {
+        Ok(Self { input, chrs, tokens: lexer::tokenize(input)?, pos: 0 })
+    }
+
+    #[must_use]
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    pub fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += usize::from(token.is_some());
+        token
+    }
+
+    /// Whether the next token is the unquoted word [`word`].
+    #[must_use]
+    pub fn at_word(&self, word: &str) -> bool {
+        self.peek().is_some_and(|token| token.is_word(word))
+    }
+
+    /// Moves past the next token if it is the unquoted word [`word`], telling
+    /// whether it was.
+    pub fn eat_word(&mut self, word: &str) -> bool {
+        let at = self.at_word(word);
+        self.pos += usize::from(at);
+        at
+    }
+
+    /// Whether there are no tokens left.
+    #[must_use]
+    pub const fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    /// Moves past the unquoted word [`word`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the next token is something
+    /// else.
+    pub fn expect_word(&mut self, word: &'static str) -> Result<()> {
+        if self.eat_word(word) { Ok(()) } else { Err(self.unexpected(word)) }
+    }
+
+    /// Takes the next token if it is a word, quoted or not.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the next token isn't a word,
+    /// naming [`what`] was expected.
+    pub fn word(&mut self, what: &'static str) -> Result<Token> {
+        let token = self.peek()
+            .filter(|token| matches!(token.kind, TokenKind::Word | TokenKind::Quoted))
+            .cloned()
+            .ok_or_else(|| self.unexpected(what))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Takes the next word and parses it, turning a failure into an error with
+    /// [`err`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there is no next word or it
+    /// doesn't parse.
+    pub fn parse<T: FromStr>(&mut self, what: &'static str, err: impl FnOnce(String, T::Err) -> ErrorKind) -> Result<T> {
+        let token = self.word(what)?;
+        token.text.parse().map_err(|e| self.error_at(&token, err(token.text.clone(), e)))
+    }
+
+    /// Takes the words of a name, up to a comma, an option, the end or an
+    /// unquoted word in [`stop`]. The words are joined by single spaces.
+    pub fn name(&mut self, stop: &[&str]) -> Option<String> {
+        let mut words = Vec::new();
+        while let Some(token) = self.peek() {
+            let is_stop = token.kind == TokenKind::Word && stop.contains(&token.text.as_str());
+            if is_stop || !matches!(token.kind, TokenKind::Word | TokenKind::Quoted) {
+                break
+            }
+            words.push(token.text.clone());
+            self.pos += 1;
+        }
+        (!words.is_empty()).then(|| words.join(" "))
+    }
+
+    /// Takes the name of a character, as with [`Cursor::name`], and resolves it
+    /// with [`resolver::resolve`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the name can't be resolved.
+    pub fn chr_name(&mut self, stop: &[&str]) -> Result<Option<ChrName>> {
+        let at = self.peek().map_or(self.input.len(), |token| token.start);
+        self.name(stop)
+            .map(|name| resolver::resolve(&name, self.chrs).map_err(|kind| Error::at(self.input, at, kind)))
+            .transpose()
+    }
+
+    /// Takes the names of characters separated by commas, as with
+    /// [`Cursor::chr_name`], skipping empty names.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if a name can't be resolved.
+    pub fn chr_names(&mut self, stop: &[&str]) -> Result<Vec<ChrName>> {
+        let mut names = Vec::new();
+        loop {
+            names.extend(self.chr_name(stop)?);
+            if self.peek().is_some_and(|token| token.kind == TokenKind::Comma) {
+                self.pos += 1;
+            } else {
+                return Ok(names)
+            }
+        }
+    }
+
+    /// Takes the words up to the end or an unquoted word in [`stop`].
+    pub fn words_until(&mut self, stop: &[&str]) -> Vec<Token> {
+        let mut words = Vec::new();
+        while let Some(token) = self.peek() {
+            if token.kind == TokenKind::Word && stop.contains(&token.text.as_str()) {
+                break
+            }
+            words.push(token.clone());
+            self.pos += 1;
+        }
+        words
+    }
+
+    /// Takes the rest of the input as it was written, or the text of a single
+    /// quoted string, for arguments such as dice and file names.
+    pub fn rest(&mut self) -> String {
+        let rest = match self.tokens.get(self.pos..) {
+            Some([token]) if token.kind == TokenKind::Quoted => token.text.clone(),
+            Some([first, ..]) => self.input[first.start..].trim().to_string(),
+            _ => String::new(),
+        };
+        self.pos = self.tokens.len();
+        rest
+    }
+
+    /// Takes the options and the tokens after each of them, up to the next
+    /// option.
+    pub fn opts(&mut self) -> Vec<(Token, Vec<Token>)> {
+        let mut opts: Vec<(Token, Vec<Token>)> = Vec::new();
+        while let Some(token) = self.next() {
+            match opts.last_mut() {
+                _ if token.kind == TokenKind::Opt => opts.push((token, Vec::new())),
+                Some((_, values)) => values.push(token),
+                None => {
+                    self.pos -= 1;
+                    break
+                },
+            }
+        }
+        opts
+    }
+
+    /// Checks that every token has been taken.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if there are tokens left.
+    pub fn end(&self) -> Result<()> {
+        if self.at_end() { Ok(()) } else { Err(self.unexpected("end of command")) }
+    }
+
+    /// An error at the next token, or at the end if there are none left.
+    #[must_use]
+    pub fn error(&self, kind: impl Into<ErrorKind>) -> Error {
+        let at = self.peek().map_or(self.input.len(), |token| token.start);
+        Error::at(self.input, at, kind.into())
+    }
+
+    #[must_use]
+    pub fn error_at(&self, token: &Token, kind: impl Into<ErrorKind>) -> Error {
+        Error::at(self.input, token.start, kind.into())
+    }
+
+    /// An error for the next token not being [`expected`].
+    #[must_use]
+    pub fn unexpected(&self, expected: &'static str) -> Error {
+        self.peek().map_or_else(
+            || self.error(ErrorKind::Missing(expected)),
+            |token| self.error(ErrorKind::Unexpected { expected, found: token.text.clone() }),
+        )
+    }
+}
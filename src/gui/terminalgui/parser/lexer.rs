@@ -0,0 +1,150 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use super::{Error, ErrorKind};
+
+/// The kinds of [`Token`]s a command is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// A run of characters up to a space or a comma, like `Goblin` or
+    /// `Half-Orc`.
+    Word,
+    /// A string in double or single quotes, which is never taken for a
+    /// keyword, like `"Dances on Water"`.
+    Quoted,
+    Comma,
+    /// A word starting with `-` followed by a letter, like `-health`. The text
+    /// of the token leaves out the `-`.
+    Opt,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// The text of the token, without quotes and escapes for quoted strings.
+    pub text: String,
+    /// Where the token starts in the input, in bytes.
+    pub start: usize,
+}
+
+impl Token {
+    /// Whether the token is the unquoted word [`word`].
+    #[must_use]
+    pub fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text == word
+    }
+}
+
+/// Splits [`input`] into [`Token`]s.
+///
+/// Quotes only start a string at the start of a word, so names like
+/// `D'Artagnan` don't need quoting. Inside quotes, `\` escapes a quote or
+/// another `\`.
+///
+/// # Errors
+///
+/// This function will return an error if
+/// - A quoted string isn't closed
+/// - A quoted string has an escape other than `\"`, `\'` or `\\`.
+pub fn tokenize(input: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => { chars.next(); },
+            ',' => {
+                chars.next();
+                tokens.push(Token { kind: TokenKind::Comma, text: ",".into(), start });
+            },
+            '"' | '\'' => {
+                chars.next();
+                let text = quoted(input, &mut chars, start, c)?;
+                tokens.push(Token { kind: TokenKind::Quoted, text, start });
+            },
+            '-' if input[start + 1..].starts_with(char::is_alphabetic) => {
+                chars.next();
+                let text = word(&mut chars);
+                tokens.push(Token { kind: TokenKind::Opt, text, start });
+            },
+            _ => {
+                let text = word(&mut chars);
+                tokens.push(Token { kind: TokenKind::Word, text, start });
+            },
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn word(chars: &mut Peekable<CharIndices>) -> String {
+    let mut text = String::new();
+    while let Some(&(_, c)) = chars.peek() {
+        if c.is_whitespace() || c == ',' {
+            break
+        }
+        text.push(c);
+        chars.next();
+    }
+    text
+}
+
+fn quoted(input: &str, chars: &mut Peekable<CharIndices>, start: usize, quote: char) -> Result<String, Error> {
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some((_, c)) if c == quote => return Ok(text),
+            Some((i, '\\')) => match chars.next() {
+                Some((_, c @ ('"' | '\'' | '\\'))) => text.push(c),
+                Some((_, c)) => return Err(Error::at(input, i, ErrorKind::InvalidEscape(c))),
+                None => return Err(Error::at(input, start, ErrorKind::UnterminatedQuote)),
+            },
+            Some((_, c)) => text.push(c),
+            None => return Err(Error::at(input, start, ErrorKind::UnterminatedQuote)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::{tokenize, TokenKind};
+
+    fn kinds_and_texts(input: &str) -> Vec<(TokenKind, String)> {
+        tokenize(input).unwrap()
+            .into_iter()
+            .map(|token| (token.kind, token.text))
+            .collect()
+    }
+
+    #[test]
+    fn dashes_inside_words_are_kept() {
+        assert_eq!(
+            vec![(TokenKind::Word, "add".into()), (TokenKind::Word, "12".into()), (TokenKind::Word, "Half-Orc".into()), (TokenKind::Opt, "h".into()), (TokenKind::Word, "20".into())],
+            kinds_and_texts("add 12 Half-Orc -h 20")
+        );
+    }
+
+    #[test]
+    fn negative_numbers_are_words() {
+        assert_eq!(vec![(TokenKind::Opt, "m".into()), (TokenKind::Word, "-2".into())], kinds_and_texts("-m -2"));
+    }
+
+    #[test]
+    fn quotes_and_escapes() {
+        assert_eq!(
+            vec![(TokenKind::Quoted, "Dances on \"Water\"".into()), (TokenKind::Comma, ",".into()), (TokenKind::Quoted, "it's".into())],
+            kinds_and_texts(r#""Dances on \"Water\"", 'it\'s'"#)
+        );
+    }
+
+    #[test]
+    fn apostrophes_inside_words_are_kept() {
+        assert_eq!(vec![(TokenKind::Word, "D'Artagnan".into())], kinds_and_texts("D'Artagnan"));
+    }
+
+    #[test]
+    fn unterminated_quote_gives_its_column() {
+        let err = tokenize("rm \"Sarah").unwrap_err();
+        assert_eq!(4, err.column);
+    }
+}
@@ -0,0 +1,95 @@
+use crate::character::ChrName;
+
+use super::ErrorKind;
+
+/// Resolves a character name as typed to one of the characters in [`chrs`],
+/// which are in initiative order.
+///
+/// The name is matched, in order of precedence, by
+/// - Being exactly the name of a character
+/// - Being `#` followed by the position of a character in the order, from 1
+/// - Being the name of a character, ignoring case
+/// - Being the start of the name of exactly one character, ignoring case.
+///
+/// A name matching no character is kept as typed, so the tracker can tell
+/// that the character doesn't exist.
+///
+/// # Errors
+///
+/// This function will return an error if
+/// - There is no character at the given position
+/// - The name is the start of the names of several characters.
+pub fn resolve(name: &str, chrs: &[ChrName]) -> Result<ChrName, ErrorKind> {
+    if let Some(chr) = chrs.iter().find(|chr| *chr == name) {
+        return Ok(chr.clone())
+    }
+
+    if let Some(pos) = name.strip_prefix('#').and_then(|pos| pos.parse::<usize>().ok()) {
+        return pos.checked_sub(1)
+            .and_then(|i| chrs.get(i))
+            .cloned()
+            .ok_or(ErrorKind::NoChrAtPosition(pos))
+    }
+
+    let lower = name.to_lowercase();
+    if let Some(chr) = chrs.iter().find(|chr| chr.as_ref().to_lowercase() == lower) {
+        return Ok(chr.clone())
+    }
+
+    let candidates: Vec<ChrName> = chrs.iter()
+        .filter(|chr| chr.as_ref().to_lowercase().starts_with(&lower))
+        .cloned()
+        .collect();
+    match candidates.as_slice() {
+        [] => Ok(ChrName::new(name)),
+        [chr] => Ok(chr.clone()),
+        _ => Err(ErrorKind::AmbiguousName { name: name.to_string(), candidates }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use crate::character::ChrName;
+    use crate::gui::terminalgui::parser::ErrorKind;
+
+    use super::resolve;
+
+    fn chrs() -> Vec<ChrName> {
+        vec![ChrName::new("Goblin"), ChrName::new("Goblin 2"), ChrName::new("Sarah"), ChrName::new("sarah's wolf")]
+    }
+
+    #[test]
+    fn unique_prefix_ignoring_case_resolves() {
+        assert_eq!(ChrName::new("Goblin 2"), resolve("goblin 2", &chrs()).unwrap());
+        assert_eq!(ChrName::new("sarah's wolf"), resolve("SARAH'", &chrs()).unwrap());
+    }
+
+    #[test]
+    fn whole_name_wins_over_longer_names() {
+        assert_eq!(ChrName::new("Goblin"), resolve("goblin", &chrs()).unwrap());
+        assert_eq!(ChrName::new("Sarah"), resolve("sarah", &chrs()).unwrap());
+    }
+
+    #[test]
+    fn position_resolves() {
+        assert_eq!(ChrName::new("Sarah"), resolve("#3", &chrs()).unwrap());
+    }
+
+    #[test]
+    fn position_outside_order_is_invalid() {
+        assert!(matches!(resolve("#0", &chrs()), Err(ErrorKind::NoChrAtPosition(0))));
+        assert!(matches!(resolve("#5", &chrs()), Err(ErrorKind::NoChrAtPosition(5))));
+    }
+
+    #[test]
+    fn ambiguous_prefix_lists_candidates() {
+        let res = resolve("gob", &chrs());
+        assert!(matches!(res, Err(ErrorKind::AmbiguousName { candidates, .. }) if candidates == vec![ChrName::new("Goblin"), ChrName::new("Goblin 2")]));
+    }
+
+    #[test]
+    fn unknown_name_is_kept() {
+        assert_eq!(ChrName::new("Orc"), resolve("Orc", &chrs()).unwrap());
+    }
+}
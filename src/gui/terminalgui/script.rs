@@ -0,0 +1,63 @@
+use thiserror::Error;
+
+use crate::{saver::Saver, tracker::Tracker};
+
+use super::run_command;
+
+/// Starts a comment line in a script.
+const COMMENT: char = '#';
+
+/// An error on a line of a script.
+#[derive(Debug, Error)]
+#[error("line {line}: {source}")]
+pub struct LineError {
+    /// The line the error is on, counting from 1.
+    pub line: usize,
+    #[source]
+    pub source: super::Error,
+}
+
+/// What running a script gave back.
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The messages of the commands that had one, in order.
+    pub messages: Vec<String>,
+    pub errors: Vec<LineError>,
+}
+
+impl Outcome {
+    /// Whether every command of the script ran without errors.
+    #[must_use]
+    pub const fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Runs [`script`] on the tracker, one terminal command per line. Empty lines
+/// and lines starting with `#` are skipped.
+///
+/// Running stops at the first error, unless [`keep_going`] is set, in which
+/// case the lines with errors are skipped.
+pub fn run<S: Saver>(t: &mut Tracker<S>, script: &str, keep_going: bool) -> Outcome {
+    let mut outcome = Outcome::default();
+
+    for (i, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(COMMENT) {
+            continue
+        }
+
+        match run_command(t, line) {
+            Ok(msg) => outcome.messages.extend(msg),
+            Err(source) => {
+                outcome.errors.push(LineError { line: i + 1, source });
+                if !keep_going {
+                    break
+                }
+            },
+        }
+    }
+
+    outcome
+}
+
@@ -0,0 +1,441 @@
+use ratatui::{layout::{Constraint, Layout, Position, Rect}, style::{Color, Modifier, Style}, text::Text, widgets::{Block, Paragraph, Row, Table, TableState, Wrap}, Frame};
+
+use crate::{conditions::CondFormat, gui::{format_clock, order::health_text}, saver::Saver, tracker::Tracker};
+
+use super::{completion, history::History};
+
+const TITLE: &str = " <>~<>~ PATHTRACKER ~<>~<> ";
+const KEYS: &str = "Enter runs, Tab completes, Up/Down history, PgUp/PgDn scroll, Esc clears, Ctrl+C quits";
+
+/// What the last command gave back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Output {
+    Message(String),
+    Error(String),
+}
+
+/// The state of the terminal GUI between draws.
+#[derive(Debug, Clone, Default)]
+pub struct App {
+    input: String,
+    /// The position of the cursor in the input, counted in characters.
+    cursor: usize,
+    output: Option<Output>,
+    /// A problem beside the output of the last command, such as the command
+    /// history failing to save.
+    warning: Option<String>,
+    /// The index of the first character shown in the initiative table.
+    scroll: usize,
+    /// Whether to scroll the table to the characters in turn on the next
+    /// draw.
+    follow_turn: bool,
+    history: History,
+}
+
+impl App {
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+    }
+
+    /// Adds [`entry`] to the command history.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the history can't be saved.
+    pub fn push_history(&mut self, entry: &str) -> std::io::Result<()> {
+        self.history.push(entry)
+    }
+
+    /// Replaces the input with the previous command in the history.
+    pub fn history_prev(&mut self) {
+        if let Some(entry) = self.history.prev(&self.input) {
+            self.input = entry.to_string();
+            self.move_end();
+        }
+    }
+
+    /// Replaces the input with the next command in the history.
+    pub fn history_next(&mut self) {
+        if let Some(entry) = self.history.next() {
+            self.input = entry.to_string();
+            self.move_end();
+        }
+    }
+
+    /// Completes the input before the cursor as far as every candidate
+    /// agrees, listing the candidates if there are several.
+    pub fn complete(&mut self, chrs: &[String]) {
+        let i = self.byte_index();
+        let completion = completion::complete(&self.input[..i], chrs);
+        let Some(prefix) = completion.common_prefix() else { return };
+
+        let completed = match completion.candidates.as_slice() {
+            [_] => prefix + " ",
+            candidates => {
+                self.output = Some(Output::Message(candidates.join("  ")));
+                prefix
+            },
+        };
+        self.input.replace_range(completion.start..i, &completed);
+        self.cursor = self.input[..completion.start].chars().count() + completed.chars().count();
+    }
+
+    /// Takes the input line, leaving it empty.
+    pub fn take_input(&mut self) -> String {
+        self.cursor = 0;
+        std::mem::take(&mut self.input)
+    }
+
+    /// Shows the result of a command, scrolling to whoever is in turn.
+    pub fn set_result<E: std::fmt::Display>(&mut self, res: Result<Option<String>, E>) {
+        self.output = match res {
+            Ok(msg) => msg.map(Output::Message),
+            Err(err) => Some(Output::Error(err.to_string())),
+        };
+        self.warning = None;
+        self.follow_turn = true;
+    }
+
+    /// Shows a warning below the output of the last command, until the next
+    /// command is run.
+    pub fn set_warning(&mut self, warning: impl std::fmt::Display) {
+        self.warning = Some(warning.to_string());
+    }
+
+    /// Clears the input line, or the output of the last command if the
+    /// input is already empty.
+    pub fn clear(&mut self) {
+        if self.input.is_empty() {
+            self.output = None;
+            self.warning = None;
+        } else {
+            self.take_input();
+        }
+    }
+
+    pub fn insert(&mut self, c: char) {
+        let i = self.byte_index();
+        self.input.insert(i, c);
+        self.cursor += 1;
+    }
+
+    /// Removes the character before the cursor.
+    pub fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let i = self.byte_index();
+            self.input.remove(i);
+        }
+    }
+
+    /// Removes the character under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.input.chars().count() {
+            let i = self.byte_index();
+            self.input.remove(i);
+        }
+    }
+
+    pub const fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.input.chars().count());
+    }
+
+    pub const fn move_home(&mut self) {
+        self.cursor = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.cursor = self.input.chars().count();
+    }
+
+    /// Removes the word before the cursor.
+    pub fn delete_word(&mut self) {
+        let end = self.byte_index();
+        self.move_word_left();
+        let start = self.byte_index();
+        self.input.replace_range(start..end, "");
+    }
+
+    /// Removes everything before the cursor.
+    pub fn delete_to_start(&mut self) {
+        let end = self.byte_index();
+        self.input.replace_range(..end, "");
+        self.cursor = 0;
+    }
+
+    /// Removes everything after the cursor.
+    pub fn delete_to_end(&mut self) {
+        let start = self.byte_index();
+        self.input.truncate(start);
+    }
+
+    /// Moves the cursor to the start of the word before it.
+    pub fn move_word_left(&mut self) {
+        let chars: Vec<char> = self.input.chars().take(self.cursor).collect();
+        let spaces = chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+        let word = chars.iter().rev().skip(spaces).take_while(|c| !c.is_whitespace()).count();
+        self.cursor -= spaces + word;
+    }
+
+    /// Moves the cursor past the end of the word after it.
+    pub fn move_word_right(&mut self) {
+        let chars: Vec<char> = self.input.chars().skip(self.cursor).collect();
+        let spaces = chars.iter().take_while(|c| c.is_whitespace()).count();
+        let word = chars.iter().skip(spaces).take_while(|c| !c.is_whitespace()).count();
+        self.cursor += spaces + word;
+    }
+
+    pub const fn scroll_up(&mut self, rows: usize) {
+        self.scroll = self.scroll.saturating_sub(rows);
+    }
+
+    pub const fn scroll_down(&mut self, rows: usize) {
+        self.scroll = self.scroll.saturating_add(rows);
+    }
+
+    fn byte_index(&self) -> usize {
+        self.input.char_indices().nth(self.cursor).map_or(self.input.len(), |(i, _)| i)
+    }
+}
+
+/// Draws the tracker and the state of the terminal GUI to [`frame`].
+pub fn draw<S: Saver>(frame: &mut Frame, t: &Tracker<S>, app: &mut App) {
+    let area = frame.area();
+    let output_height = app.output.as_ref().map_or(0, |output| {
+        let lines = match output {
+            Output::Message(msg) => msg.lines().count(),
+            Output::Error(_) => 1,
+        };
+        // Long output like help can take the room of the table, but leaves
+        // its header in view.
+        u16::try_from(lines + 2).unwrap_or(u16::MAX).min(area.height.saturating_sub(7))
+    });
+    let warning_height = u16::from(app.warning.is_some());
+    let [table_area, output_area, warning_area, status_area, input_area] = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(output_height),
+        Constraint::Length(warning_height),
+        Constraint::Length(1),
+        Constraint::Length(3),
+    ]).areas(area);
+
+    draw_table(frame, t, app, table_area);
+    draw_output(frame, app, output_area);
+    draw_warning(frame, app, warning_area);
+    draw_status(frame, t, status_area);
+    draw_input(frame, app, input_area);
+}
+
+fn draw_table<S: Saver>(frame: &mut Frame, t: &Tracker<S>, app: &mut App, area: Rect) {
+    let format = CondFormat::default().set_version(t.get_pf2e_version_setting());
+    let in_turn = t.get_in_turn_slot();
+    let in_turn_style = Style::new().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD);
+
+    let rows: Vec<Row> = t.get_chrs().iter().map(|chr| {
+        let mut conds: Vec<String> = t.get_conditions(&chr.name).into_iter()
+            .map(|c| c.to_string(format))
+            .collect();
+        conds.sort();
+        let modifiers = t.get_modifiers(&chr.name);
+        if !modifiers.is_empty() {
+            conds.push(format!("({modifiers})"));
+        }
+        let height = u16::try_from(conds.len().max(1)).unwrap_or(u16::MAX);
+        let turn = in_turn.contains(chr);
+
+        let row = Row::new(vec![
+            Text::from(if turn { ">" } else { "" }),
+            Text::from(chr.init.to_string()).right_aligned(),
+            Text::from(if chr.player { "*" } else { "" }),
+            Text::from(chr.name.to_string()),
+            Text::from(chr.health.as_ref().map_or_else(|| "---".into(), health_text)).right_aligned(),
+            Text::from(conds.join("\n")),
+        ]).height(height);
+
+        if turn { row.style(in_turn_style) } else { row }
+    }).collect();
+
+    let name_width = t.get_chrs().iter()
+        .map(|chr| chr.name.as_ref().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let name_width = u16::try_from(name_width).unwrap_or(u16::MAX);
+    let widths = [
+        Constraint::Length(1),
+        Constraint::Length(4),
+        Constraint::Length(1),
+        Constraint::Length(name_width),
+        Constraint::Length(11),
+        Constraint::Fill(1),
+    ];
+    let header = Row::new(["", "Init", "P", "Name", "HP", "Condition(s)"])
+        .style(Style::new().add_modifier(Modifier::BOLD));
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::bordered().title(TITLE));
+
+    let len = t.get_chrs().len();
+    app.scroll = app.scroll.min(len.saturating_sub(1));
+    let mut state = TableState::new().with_offset(app.scroll);
+    if app.follow_turn {
+        // Selecting the characters in turn makes the table scroll to them.
+        let first = in_turn.first().and_then(|first| t.get_chrs().iter().position(|chr| chr == first));
+        state.select(first);
+    }
+    frame.render_stateful_widget(table, area, &mut state);
+
+    if app.follow_turn {
+        app.scroll = state.offset();
+        app.follow_turn = false;
+    }
+}
+
+fn draw_output(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(output) = &app.output else { return };
+
+    let paragraph = match output {
+        Output::Message(msg) => Paragraph::new(msg.as_str())
+            .block(Block::bordered()),
+        Output::Error(err) => Paragraph::new(err.as_str())
+            .style(Style::new().fg(Color::Red))
+            .block(Block::bordered().title(" Error ")),
+    };
+    frame.render_widget(paragraph.wrap(Wrap { trim: false }), area);
+}
+
+fn draw_warning(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(warning) = &app.warning else { return };
+
+    let paragraph = Paragraph::new(format!(" Warning: {warning}"))
+        .style(Style::new().fg(Color::Yellow));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_status<S: Saver>(frame: &mut Frame, t: &Tracker<S>, area: Rect) {
+    let threat = t.get_threat().map_or_else(String::new, |rating| format!(" | {} threat", rating.threat));
+    let status = format!(" {}{threat} | {KEYS}", format_clock(t.get_clock()));
+    frame.render_widget(Paragraph::new(status).style(Style::new().add_modifier(Modifier::REVERSED)), area);
+}
+
+fn draw_input(frame: &mut Frame, app: &App, area: Rect) {
+    let width = usize::from(area.width.saturating_sub(2)).max(1);
+    // Scrolls the input sideways to keep the cursor in view.
+    let hscroll = (app.cursor + 1).saturating_sub(width);
+    let visible: String = app.input.chars().skip(hscroll).take(width).collect();
+
+    frame.render_widget(Paragraph::new(visible).block(Block::bordered().title(" Command ")), area);
+
+    let x = u16::try_from(app.cursor - hscroll).unwrap_or(0);
+    frame.set_cursor_position(Position::new(area.x + 1 + x, area.y + 1));
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use crate::{character::Chr, saver::NoSaver, tracker::Tracker};
+
+    use super::{draw, App};
+
+    fn render(t: &Tracker<NoSaver>, app: &mut App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|frame| draw(frame, t, app)).unwrap();
+        terminal.backend().buffer().content().iter().map(ratatui::buffer::Cell::symbol).collect()
+    }
+
+    #[test]
+    fn long_names_are_not_cut() {
+        let t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+            Chr::builder("Bartholomew the Magnificent", 12, true).build(),
+        ]).build();
+
+        let screen = render(&t, &mut App::default(), 80, 12);
+
+        assert!(screen.contains("Bartholomew the Magnificent"));
+    }
+
+    #[test]
+    fn errors_are_shown() {
+        let t: Tracker<NoSaver> = Tracker::default();
+        let mut app = App::default();
+        app.set_result::<&str>(Err("invalid key word"));
+
+        let screen = render(&t, &mut app, 80, 12);
+
+        assert!(screen.contains("invalid key word"));
+    }
+
+    #[test]
+    fn warnings_are_shown_with_the_output() {
+        let t: Tracker<NoSaver> = Tracker::default();
+        let mut app = App::default();
+        app.set_result::<&str>(Ok(Some(String::from("Goblin rolled 12"))));
+        app.set_warning("history could not be saved");
+
+        let screen = render(&t, &mut app, 80, 12);
+
+        assert!(screen.contains("Goblin rolled 12"));
+        assert!(screen.contains("history could not be saved"));
+    }
+
+    #[test]
+    fn follows_turn_off_screen() {
+        let chrs: Vec<Chr> = (0..20).map(|i| Chr::builder(format!("Goblin {i}"), 40 - i, false).build()).collect();
+        let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(chrs).build();
+        for _ in 0..15 {
+            t.end_turn().unwrap();
+        }
+        let mut app = App::default();
+        app.set_result::<&str>(Ok(None));
+
+        let screen = render(&t, &mut app, 80, 12);
+
+        assert!(screen.contains("Goblin 14"));
+        assert!(app.scroll > 0);
+    }
+
+    #[test]
+    fn completion_fills_in_name() {
+        let mut app = App::default();
+        for c in "cond add prone on sa".chars() {
+            app.insert(c);
+        }
+
+        app.complete(&["Sarah".to_string(), "Goblin".to_string()]);
+
+        assert_eq!("cond add prone on Sarah ", app.take_input());
+    }
+
+    #[test]
+    fn deleting_word_keeps_rest() {
+        let mut app = App::default();
+        for c in "cond add prone".chars() {
+            app.insert(c);
+        }
+        app.move_word_left();
+        app.delete_word();
+
+        assert_eq!("cond prone", app.take_input());
+    }
+
+    #[test]
+    fn editing_input_moves_cursor() {
+        let mut app = App::default();
+        for c in "ad".chars() {
+            app.insert(c);
+        }
+        app.move_left();
+        app.insert('d');
+        app.move_end();
+        app.backspace();
+        app.insert('d');
+
+        assert_eq!("add", app.take_input());
+    }
+}
@@ -2,20 +2,33 @@ use addwindow::AddWindow;
 use condwindow::CondWindow;
 use dragvaluewindow::DragValueWindow;
 use egui::{Context, IntoAtoms, Ui};
+use encounterwindow::EncounterWindow;
 use errorwindow::ErrorWindow;
+use filedialog::{FileAction, FileDialog};
 use healthwindow::HealthWindow;
+use newencounterwindow::NewEncounterWindow;
+use palettewindow::PaletteWindow;
+use selection::Selection;
 use renamewindow::RenameWindow;
+use timewindow::TimeWindow;
 
-use crate::{character::ChrName, gui::windowgui::settingswindow::SettingsWindow, saver::Saver, tracker::{self, Tracker}};
+use crate::{character::ChrName, gui::{format_clock, windowgui::settingswindow::SettingsWindow}, saver::Saver, settings::Action, tracker::{self, Tracker}};
 
 mod condwindow;
 mod errorwindow;
+mod filedialog;
 mod addwindow;
 mod characters;
 mod renamewindow;
 mod healthwindow;
 mod dragvaluewindow;
 mod settingswindow;
+mod timewindow;
+mod encounterwindow;
+mod newencounterwindow;
+mod palettewindow;
+mod selection;
+mod shortcuts;
 
 #[derive(Debug)]
 #[derive(thiserror::Error)]
@@ -52,25 +65,45 @@ struct WindowApp<S: Saver> {
     heal_window: DragValueWindow<u32, ChrName>,
     add_temp_hp_window: DragValueWindow<u32, ChrName>,
     settings_window: SettingsWindow,
+    time_window: TimeWindow,
+    encounter_window: EncounterWindow,
+    new_encounter_window: NewEncounterWindow,
+    palette_window: PaletteWindow,
+    bulk_damage_window: DragValueWindow<u32, Vec<ChrName>>,
+    bulk_heal_window: DragValueWindow<u32, Vec<ChrName>>,
+    /// The characters whose rows are selected.
+    selection: Selection,
+    file_dialog: FileDialog,
 }
 
 impl<S: Saver> eframe::App for WindowApp<S> {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.error_window.show(ctx);
-        let res = self.show_main_window(ctx)
+        let names: Vec<ChrName> = self.tracker.get_chrs().iter().map(|chr| chr.name.clone()).collect();
+        self.selection.retain(&names);
+
+        let res = self.handle_shortcuts(ctx)
+            .and_then(|()| self.show_main_window(ctx))
             .and_then(|()| self.add_window.show(&mut self.tracker, ctx))
             .and_then(|()| self.add_cond_window.show(&mut self.tracker, ctx))
             .and_then(|()| self.rename_window.show(&mut self.tracker, ctx))
             .and_then(|()| self.health_window.show(&mut self.tracker, ctx))
             .and_then(|()| self.show_damage_window(ctx))
             .and_then(|()| self.show_heal_window(ctx))
-            .and_then(|()| self.show_add_temp_hp_window(ctx));
+            .and_then(|()| self.show_add_temp_hp_window(ctx))
+            .and_then(|()| self.show_bulk_damage_window(ctx))
+            .and_then(|()| self.show_bulk_heal_window(ctx))
+            .and_then(|()| self.time_window.show(&mut self.tracker, ctx))
+            .and_then(|()| self.encounter_window.show(&mut self.tracker, ctx))
+            .and_then(|()| self.new_encounter_window.show(&mut self.tracker, ctx))
+            .and_then(|()| self.handle_file_dialog(ctx));
 
         if let Err(err) = res {
             self.error_window.open(err);
         }
 
         self.settings_window.show(&mut self.tracker, ctx);
+        self.palette_window.show(&mut self.tracker, ctx);
     }
 }
 
@@ -83,18 +116,130 @@ impl<S: Saver> WindowApp<S> {
             rename_window: RenameWindow::default(),
             health_window: HealthWindow::default(),
             error_window: ErrorWindow::default(),
-            damage_window: DragValueWindow::default(),
-            heal_window: DragValueWindow::default(),
+            damage_window: DragValueWindow::default().with_dice(),
+            heal_window: DragValueWindow::default().with_dice(),
             add_temp_hp_window: DragValueWindow::default(),
             settings_window: SettingsWindow::default(),
+            time_window: TimeWindow::default(),
+            encounter_window: EncounterWindow::default(),
+            new_encounter_window: NewEncounterWindow::default(),
+            palette_window: PaletteWindow::default(),
+            bulk_damage_window: DragValueWindow::default().with_dice(),
+            bulk_heal_window: DragValueWindow::default().with_dice(),
+            selection: Selection::default(),
+            file_dialog: FileDialog::default(),
+        }
+    }
+
+    /// Imports or exports the file picked in the file dialog, once it is.
+    fn handle_file_dialog(&mut self, ctx: &Context) -> Result<()> {
+        let Some((action, file)) = self.file_dialog.poll(ctx) else { return Ok(()) };
+        match action {
+            FileAction::ImportPathbuilder => {self.tracker.import_pathbuilder(file)?;}
+            FileAction::ImportFoundry => self.tracker.import_foundry(file)?,
+            FileAction::ExportFoundry => self.tracker.export_foundry(file)?,
+        }
+        Ok(())
+    }
+
+    /// Runs the actions of the keys pressed this frame.
+    fn handle_shortcuts(&mut self, ctx: &Context) -> Result<()> {
+        for action in shortcuts::pressed(ctx, self.tracker.get_key_bindings_setting()) {
+            match action {
+                Action::EndTurn => {self.tracker.end_turn()?;}
+                Action::Undo => self.tracker.undo()?,
+                Action::Redo => self.tracker.redo()?,
+                Action::Add => self.add_window.open(),
+                Action::Damage if self.selection.names().len() > 1 => self.bulk_damage_window.open(self.selection.names().to_vec()),
+                Action::Damage => if let Some(name) = self.health_target() { self.damage_window.open(name) },
+                Action::Heal if self.selection.names().len() > 1 => self.bulk_heal_window.open(self.selection.names().to_vec()),
+                Action::Heal => if let Some(name) = self.health_target() { self.heal_window.open(name) },
+                Action::Conditions if self.selection.names().len() > 1 => self.add_cond_window.open_many(self.selection.names().to_vec()),
+                Action::Conditions => if let Some(name) = self.target() { self.add_cond_window.open(name) },
+                Action::CommandPalette => self.palette_window.open(),
+            }
         }
+
+        Ok(())
+    }
+
+    /// The character acted on by shortcuts when at most one is selected,
+    /// which is the selected character, or the one in turn if none is.
+    fn target(&self) -> Option<ChrName> {
+        self.selection.primary().cloned()
+            .or_else(|| self.selection.names().first().cloned())
+            .or_else(|| self.tracker.get_in_turn_slot().first().map(|chr| chr.name.clone()))
+    }
+
+    /// The [`WindowApp::target`], if health is tracked for it.
+    fn health_target(&self) -> Option<ChrName> {
+        self.target().filter(|name| self.tracker.get_chr(name).is_some_and(|chr| chr.health.is_some()))
     }
     
     fn show_main_window(&mut self, ctx: &Context) -> Result<()> {
         self.show_button_panel(ctx)?;
+        self.show_bulk_action_panel(ctx)?;
         self.show_character_panel(ctx)
     }
 
+    fn show_bulk_damage_window(&mut self, ctx: &Context) -> Result<()> {
+        self.bulk_damage_window.show("bulk_damage_window".into(), ctx,
+            |c,_| format!("Damage {} characters", c.len()),
+            |_,_| "Amount each: ".into(),
+            |c,d| {
+                self.tracker.damage_many(&c, d)?;
+                Ok(())
+            }
+        )
+    }
+
+    fn show_bulk_heal_window(&mut self, ctx: &Context) -> Result<()> {
+        self.bulk_heal_window.show("bulk_heal_window".into(), ctx,
+            |c,_| format!("Heal {} characters", c.len()),
+            |_,_| "Amount each: ".into(),
+            |c,d| {
+                self.tracker.heal_many(&c, d)?;
+                Ok(())
+            }
+        )
+    }
+
+    /// Shows the actions for all the selected characters, when any are
+    /// selected. Each action is a single change to undo.
+    fn show_bulk_action_panel(&mut self, ctx: &Context) -> Result<()> {
+        if self.selection.is_empty() { return Ok(()) }
+
+        egui::TopBottomPanel::top("bulk actions").show(ctx, |ui| {
+            let res = ui.horizontal(|ui| {
+                ui.label(format!("{} selected", self.selection.names().len()));
+                if ui.button("Damage").clicked() { return Some(BulkAction::Damage) }
+                if ui.button("Heal").clicked() { return Some(BulkAction::Heal) }
+                if ui.button("Add condition").clicked() { return Some(BulkAction::AddCondition) }
+                if ui.button("Set as player").clicked() { return Some(BulkAction::SetPlayer(true)) }
+                if ui.button("Set as enemy").clicked() { return Some(BulkAction::SetPlayer(false)) }
+                if ui.button("Remove").clicked() { return Some(BulkAction::Remove) }
+                if ui.button("Deselect").clicked() { return Some(BulkAction::Deselect) }
+                None
+            }).inner;
+
+            let names = self.selection.names().to_vec();
+            match res {
+                Some(BulkAction::Damage) => self.bulk_damage_window.open(names),
+                Some(BulkAction::Heal) => self.bulk_heal_window.open(names),
+                Some(BulkAction::AddCondition) => self.add_cond_window.open_many(names),
+                Some(BulkAction::SetPlayer(player)) => self.tracker.set_player_many(&names, player)?,
+                Some(BulkAction::Remove) => {
+                    self.tracker.rm_chrs(&names)?;
+                    self.selection.clear();
+                },
+                Some(BulkAction::Deselect) => self.selection.clear(),
+                None => (),
+            }
+
+            Ok::<(), Error>(())
+        }).inner
+    }
+
     fn show_damage_window(&mut self, ctx: &Context) -> Result<()> {
         self.damage_window.show("damage_window".into(), ctx, 
             |c,_| format!("Damage {}", c), 
@@ -133,13 +278,16 @@ impl<S: Saver> WindowApp<S> {
         egui::CentralPanel::default()
             .frame(frame)
             .show(ctx, |ui| {
-                let responses = characters::show(&self.tracker, ui);
+                let responses = characters::show(&self.tracker, &self.selection, ui);
 
                 for resp in responses {
                     match resp {
                         characters::Response::RemoveCharacter(name) => { 
                             self.tracker.rm_chr(&name)?;
                         },
+                        characters::Response::RemoveGroup(group) => {
+                            self.tracker.rm_group(&group)?;
+                        },
                         characters::Response::OpenCondWindow(name) => {
                             self.add_cond_window.open(name);
                         },
@@ -158,6 +306,10 @@ impl<S: Saver> WindowApp<S> {
                         characters::Response::OpenAddTempHpWindow(name) => {
                             self.add_temp_hp_window.open(name);
                         },
+                        characters::Response::Select(name, mode) => {
+                            let order: Vec<ChrName> = self.tracker.get_chrs().iter().map(|chr| chr.name.clone()).collect();
+                            self.selection.click(name, mode, &order);
+                        },
                     }
                 }
 
@@ -173,9 +325,21 @@ impl<S: Saver> WindowApp<S> {
                     if button_panel_button(ui, egui::RichText::new("+")).on_hover_text("Adds a character.").clicked() { return Some(ButtonPanelResponse::Add) }
                     if button_panel_button(ui, "\u{27F2}").on_hover_text("Undoes the last change.").clicked() { return Some(ButtonPanelResponse::Undo) }
                     if button_panel_button(ui, "\u{27F3}").on_hover_text("Redoes the last undone change.").clicked(){ return Some(ButtonPanelResponse::Redo) }
+                    if button_panel_button(ui, "\u{1F3B2}").on_hover_text("Rolls initiative for every enemy.").clicked() { return Some(ButtonPanelResponse::RollInitiative) }
                     None
                 },
                 |ui|{
+                    let threat = self.tracker.get_threat();
+                    let hover = threat.map_or_else(|| "Plans encounters. Give the player characters a level to rate the encounter.".into(), |rating| format!("Plans encounters. This one is {rating}."));
+                    if button_panel_button(ui, "\u{2694}").on_hover_text(hover).clicked() { return Some(ButtonPanelResponse::Encounter) }
+                    if let Some(rating) = threat {
+                        ui.label(rating.threat.to_string());
+                    }
+                    if button_panel_button(ui, "\u{1F4E5}").on_hover_text("Imports a player character from a Pathbuilder export.").clicked() { return Some(ButtonPanelResponse::ImportPathbuilder) }
+                    if button_panel_button(ui, "\u{1F4C2}").on_hover_text("Replaces the encounter with a Foundry VTT combat export.").clicked() { return Some(ButtonPanelResponse::ImportFoundry) }
+                    if button_panel_button(ui, "\u{1F4E4}").on_hover_text("Exports the encounter for Foundry VTT.").clicked() { return Some(ButtonPanelResponse::ExportFoundry) }
+                    if button_panel_button(ui, "\u{23F1}").on_hover_text(format!("Lets time pass. It is {}.", format_clock(self.tracker.get_clock()))).clicked() { return Some(ButtonPanelResponse::Time) }
+                    if button_panel_button(ui, "\u{1F195}").on_hover_text("Starts a new encounter with the party, removing the enemies.").clicked() { return Some(ButtonPanelResponse::NewEncounter) }
                     if button_panel_button(ui, "\u{1F5D1}").on_hover_text("Removes every character.").clicked() { return Some(ButtonPanelResponse::Clear) }
                     if button_panel_button(ui, "\u{2699}").on_hover_text("General settings.").clicked() { return Some(ButtonPanelResponse::Settings) }
                     None
@@ -188,8 +352,15 @@ impl<S: Saver> WindowApp<S> {
                     ButtonPanelResponse::Add => {self.add_window.open();}
                     ButtonPanelResponse::Undo => {self.tracker.undo()?;}
                     ButtonPanelResponse::Redo => {self.tracker.redo()?;}
+                    ButtonPanelResponse::RollInitiative => {self.tracker.roll_enemy_initiative()?;}
                     ButtonPanelResponse::Clear => {self.tracker.clear();}
+                    ButtonPanelResponse::NewEncounter => {self.new_encounter_window.open(&self.tracker);}
                     ButtonPanelResponse::Settings => {self.settings_window.open(&self.tracker);}
+                    ButtonPanelResponse::Time => {self.time_window.open();}
+                    ButtonPanelResponse::Encounter => {self.encounter_window.open(&self.tracker);}
+                    ButtonPanelResponse::ImportPathbuilder => self.file_dialog.open(FileAction::ImportPathbuilder),
+                    ButtonPanelResponse::ImportFoundry => self.file_dialog.open(FileAction::ImportFoundry),
+                    ButtonPanelResponse::ExportFoundry => self.file_dialog.open(FileAction::ExportFoundry),
                 }
             }
 
@@ -210,8 +381,25 @@ enum ButtonPanelResponse {
     Add,
     Undo,
     Redo,
+    RollInitiative,
     Clear,
+    NewEncounter,
     Settings,
+    Time,
+    Encounter,
+    ImportPathbuilder,
+    ImportFoundry,
+    ExportFoundry,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BulkAction {
+    Damage,
+    Heal,
+    AddCondition,
+    SetPlayer(bool),
+    Remove,
+    Deselect,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
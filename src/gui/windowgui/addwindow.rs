@@ -1,6 +1,6 @@
 use egui::{Context, Id, Modal, Ui};
 
-use crate::{character::{Chr, Health}, saver::Saver, tracker::Tracker};
+use crate::{bestiary::{Template, Variant}, character::{Chr, Health}, dice::{DiceExpr, Roller}, saver::Saver, tracker::Tracker};
 
 use super::Confirmation;
 
@@ -11,17 +11,40 @@ pub struct AddWindow {
     focus: bool,
     name: String,
     init: i32,
+    init_mod: i32,
+    roller: Roller,
     player: bool,
     enable_health: bool,
-    health: u32
+    health: u32,
+    enable_level: bool,
+    level: i32,
+    count: u32,
+    enable_group: bool,
+    vary_each: bool,
+    /// The initiative and max HP of each character when adding several that
+    /// vary.
+    each: Vec<(i32, u32)>,
+    /// The id of the bestiary template picked, if any.
+    template: Option<String>,
+    template_query: String,
+    variant: Option<Variant>,
 }
 
 impl AddWindow {
     fn reset(&mut self) {
         self.name = String::new();
         self.init = 0;
+        self.init_mod = 0;
         self.player = false;
         self.enable_health = false;
+        self.enable_level = false;
+        self.count = 1;
+        self.enable_group = false;
+        self.vary_each = false;
+        self.each = Vec::new();
+        self.template = None;
+        self.template_query = String::new();
+        self.variant = None;
     }
 
     pub const fn open(&mut self) {
@@ -41,12 +64,20 @@ impl AddWindow {
             .show(ctx, |ui| {
                 let name_edit = self.show_name_label(ui);
 
+                self.show_template_picker(tracker, ui);
+
                 self.show_initiative(ui);
 
                 self.show_is_player(ui);
 
                 self.show_health_tracking_option(ui);
 
+                self.show_level_option(ui);
+
+                self.show_variant_option(ui);
+
+                self.show_count_option(ui);
+
                 ui.separator();
 
                 self.show_confirmation_bar(tracker, ui)?;
@@ -67,8 +98,36 @@ impl AddWindow {
             Some(Confirmation::Confirm) => {
                 let c1 = Chr::builder(self.name.clone(), self.init, self.player);
                 let c2 = if self.enable_health { c1.with_health(Health::new(self.health)) } else { c1 };
-                let character = c2.build();
-                tracker.add_chr(character)?;
+                let c3 = if self.enable_level { c2.with_level(self.level) } else { c2 };
+                let c4 = if self.init_mod == 0 { c3 } else { c3.with_init_mod(self.init_mod) };
+                let c5 = match &self.template { Some(id) => c4.with_template(id.clone()), None => c4 };
+                // The stats of the template aren't shown to be edited, so they
+                // are taken as they are, to be adjusted with the rest by the
+                // variant.
+                let stats = self.template.as_ref().and_then(|id| tracker.get_bestiary().get(id)).map(Template::stats);
+                let c6 = match stats { Some(stats) => c5.with_stats(stats), None => c5 };
+                let mut character = c6.build();
+                if let Some(variant) = self.variant {
+                    character.apply_variant(variant);
+                }
+                let count = self.count.max(1);
+                if count == 1 {
+                    tracker.add_chr(character)?;
+                } else if self.enable_group {
+                    tracker.add_group(character, count)?;
+                } else if self.vary_each {
+                    let (each, enable_health) = (&self.each, self.enable_health);
+                    tracker.add_many_with(character, count, |i, chr| {
+                        if let Some((init, health)) = usize::try_from(i).ok().and_then(|i| each.get(i)) {
+                            chr.init = *init;
+                            if enable_health {
+                                chr.set_health(Health::new(*health));
+                            }
+                        }
+                    })?;
+                } else {
+                    tracker.add_many(character, count)?;
+                }
                 self.close();
             },
             Some(Confirmation::Cancel) => self.close(),
@@ -92,6 +151,107 @@ impl AddWindow {
         });
     }
 
+    fn show_level_option(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.enable_level, "Level");
+
+            if self.enable_level {
+                let drag = egui::DragValue::new(&mut self.level).range(-1..=25);
+                ui.add(drag);
+            }
+        });
+    }
+
+    fn show_variant_option(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.variant, None, "Normal");
+            ui.radio_value(&mut self.variant, Some(Variant::Elite), "Elite")
+                .on_hover_text("Raises level, initiative modifier and, with a level, max HP.");
+            ui.radio_value(&mut self.variant, Some(Variant::Weak), "Weak")
+                .on_hover_text("Lowers level, initiative modifier and, with a level, max HP.");
+        });
+    }
+
+    fn show_count_option(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Count:");
+            let drag = egui::DragValue::new(&mut self.count).range(1..=50);
+            ui.add(drag).on_hover_text("Adds several characters, numbering their names.");
+
+            if self.count > 1 {
+                ui.checkbox(&mut self.enable_group, "Group")
+                    .on_hover_text("Makes the characters members of a group sharing one turn.");
+            }
+        });
+
+        if self.count > 1 && !self.enable_group {
+            self.show_each_option(ui);
+        }
+    }
+
+    fn show_each_option(&mut self, ui: &mut Ui) {
+        ui.checkbox(&mut self.vary_each, "Vary each")
+            .on_hover_text("Gives each character their own initiative and max HP.");
+
+        if !self.vary_each {
+            return
+        }
+
+        let count = usize::try_from(self.count).unwrap_or(usize::MAX);
+        self.each.resize(count, (self.init, self.health));
+
+        egui::Grid::new("add_each").show(ui, |ui| {
+            for (i, (init, health)) in self.each.iter_mut().enumerate() {
+                ui.label(format!("#{}", i + 1));
+                ui.add(egui::DragValue::new(init).range(0..=50).prefix("init "));
+                if self.enable_health {
+                    ui.add(egui::DragValue::new(health).range(0..=999).prefix("HP "));
+                }
+                ui.end_row();
+            }
+        });
+    }
+
+    fn show_template_picker(&mut self, tracker: &Tracker<impl Saver>, ui: &mut Ui) {
+        let bestiary = tracker.get_bestiary();
+        if bestiary.get_templates().is_empty() {
+            return
+        }
+
+        egui::CollapsingHeader::new("From bestiary").show(ui, |ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.template_query).hint_text("Search templates"));
+
+            let mut picked = None;
+            egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                for template in bestiary.search(&self.template_query) {
+                    let selected = self.template.as_ref() == Some(&template.id);
+                    let label = format!("{} (level {})", template.name, template.level);
+                    let hover = format!("{}\nAC {}, HP {}, Perception {:+}", template.id, template.ac, template.hp, template.init_mod);
+                    if ui.selectable_label(selected, label).on_hover_text(hover).clicked() {
+                        picked = Some(template.clone());
+                    }
+                }
+            });
+
+            if let Some(template) = picked {
+                self.pick_template(&template);
+            }
+        });
+    }
+
+    /// Fills in the fields from [`template`], rolling its initiative.
+    fn pick_template(&mut self, template: &Template) {
+        self.name.clone_from(&template.name);
+        self.player = false;
+        self.enable_health = true;
+        self.health = template.hp;
+        self.enable_level = true;
+        self.level = template.level;
+        self.init_mod = template.init_mod;
+        self.init = self.roller.roll(&DiceExpr::d20(template.init_mod)).total;
+        self.template = Some(template.id.clone());
+    }
+
     fn show_is_player(&mut self, ui: &mut Ui) {
         ui.checkbox(&mut self.player, "Player");
     }
@@ -101,6 +261,15 @@ impl AddWindow {
             ui.label("Initiative: ");
             let drag = egui::DragValue::new(&mut self.init).range(0..=50);
             ui.add(drag);
+
+            ui.add_space(12.0);
+            ui.label("Modifier: ");
+            let drag = egui::DragValue::new(&mut self.init_mod).range(-10..=50);
+            ui.add(drag).on_hover_text("The initiative modifier, usually Perception.");
+
+            if ui.button("\u{1F3B2}").on_hover_text("Rolls initiative with the modifier.").clicked() {
+                self.init = self.roller.roll(&DiceExpr::d20(self.init_mod)).total;
+            }
         });
     }
 
@@ -1,31 +1,75 @@
 use egui::{Align, ProgressBar, Ui}; use egui_extras::{Column, TableBuilder, TableRow};
-use crate::{character::{Chr, ChrName, Health}, conditions::CondFormat, saver::Saver, tracker::Tracker};
+use crate::{character::{Chr, ChrName, Health, Stats}, conditions::CondFormat, saver::Saver, tracker::Tracker};
+
+use super::selection::{SelectMode, Selection};
 
 #[derive(Debug, Clone)]
 pub enum Response {
     RemoveCharacter(ChrName),
+    RemoveGroup(String),
     OpenCondWindow(ChrName),
     RenameCharacter(ChrName),
     OpenHealthWindow(ChrName),
     OpenDamageWindow(ChrName),
     OpenHealWindow(ChrName),
-    OpenAddTempHpWindow(ChrName)
+    OpenAddTempHpWindow(ChrName),
+    /// The row of the character was clicked.
+    Select(ChrName, SelectMode),
+}
+
+/// A row of the character table.
+enum Row<'a> {
+    Single(&'a Chr),
+    /// A group, shown as one collapsible row in place of its members.
+    Group { group: &'a str, members: &'a [Chr] },
+    Member(&'a Chr),
 }
 
-pub fn show<S: Saver>(tracker: &Tracker<S>, ui: &mut Ui) -> Vec<Response> {
+fn group_id(group: &str) -> egui::Id {
+    egui::Id::new(("group expanded", group))
+}
+
+/// Lays out the slots of the tracker as rows, listing the members of a group
+/// only when it has been expanded.
+fn rows<'a>(tracker: &'a Tracker<impl Saver>, ui: &Ui) -> Vec<Row<'a>> {
+    let mut rows = Vec::new();
+    for slot in tracker.get_slots() {
+        match slot[0].group.as_deref() {
+            Some(group) => {
+                rows.push(Row::Group { group, members: slot });
+                if ui.data(|data| data.get_temp(group_id(group)).unwrap_or(false)) {
+                    rows.extend(slot.iter().map(Row::Member));
+                }
+            },
+            None => rows.push(Row::Single(&slot[0])),
+        }
+    }
+    rows
+}
+
+/// Shows the character table, highlighting the rows of the [`selection`].
+pub fn show<S: Saver>(tracker: &Tracker<S>, selection: &Selection, ui: &mut Ui) -> Vec<Response> {
+    let rows = rows(tracker, ui);
+    let in_turn = tracker.get_in_turn_slot();
+
     let mut table = TableBuilder::new(ui)
         .cell_layout(egui::Layout::left_to_right(Align::Center))
         .auto_shrink(false)
         .striped(true)
+        .sense(egui::Sense::click())
         .column(Column::exact(20.0))
         .column(Column::auto()) // Initiative and name
         .column(Column::auto()) // Optional health
-        .column(Column::remainder())
+        .column(Column::remainder()) // Condition modifiers
         .column(Column::auto()) // Conditions
         .column(Column::auto()) // Options
         .column(Column::auto()); // Remove
 
-    let in_turn_index = tracker.get_chrs().iter().enumerate().find(|(_, c)| Some(*c) == tracker.get_in_turn()).map(|(i, _)| i);
+    let in_turn_index = rows.iter().position(|row| match row {
+        Row::Single(character) => in_turn.contains(character),
+        Row::Group { members, .. } => *members == in_turn,
+        Row::Member(_) => false,
+    });
     if let Some(index) = in_turn_index {
         table = table.scroll_to_row(index, Some(Align::Center));
     }
@@ -34,31 +78,148 @@ pub fn show<S: Saver>(tracker: &Tracker<S>, ui: &mut Ui) -> Vec<Response> {
 
     table.body(|body| {
         let row_height = 30.0;
-        let number_of_rows = tracker.get_chrs().len();
+        let number_of_rows = rows.len();
         body.rows(row_height, number_of_rows, |mut row| {
-            let index = row.index();
-            let character = &tracker.get_chrs()[index];
-            let is_in_turn = Some(character) == tracker.get_in_turn();
+            match rows[row.index()] {
+                Row::Single(character) => {
+                    let is_in_turn = in_turn.contains(character);
+                    row.set_selected(selection.contains(&character.name));
+
+                    show_in_turn_marker_col(&mut row, is_in_turn);
+
+                    show_name_col(&mut responses, &mut row, character, is_in_turn);
+
+                    show_character_cols(tracker, &mut responses, &mut row, character);
+
+                    responses.extend(select_response(&row, character));
+                },
+                Row::Group { group, members } => {
+                    let is_in_turn = members == in_turn;
+
+                    show_in_turn_marker_col(&mut row, is_in_turn);
 
-            show_in_turn_marker_col(&mut row, is_in_turn);
+                    show_group_name_col(&mut row, group, members, is_in_turn);
 
-            show_name_col(&mut responses, &mut row, character, is_in_turn);
+                    show_group_health_col(&mut row, members);
 
-            show_health_col(&mut responses, &mut row, character);
+                    row.col(|_| ());
+                    row.col(|_| ());
+                    row.col(|_| ());
 
-            row.col(|_| {});
+                    show_group_remove_col(&mut responses, &mut row, group);
+                },
+                Row::Member(character) => {
+                    row.set_selected(selection.contains(&character.name));
 
-            show_conds_col(tracker, &mut responses, &mut row, character);
+                    show_in_turn_marker_col(&mut row, false);
 
-            show_options_col(&mut responses, &mut row, character);
+                    show_member_name_col(&mut responses, &mut row, character);
 
-            show_remove_col(&mut responses, &mut row, character);
+                    show_character_cols(tracker, &mut responses, &mut row, character);
+
+                    responses.extend(select_response(&row, character));
+                },
+            }
         });
     });
 
     responses
 }
 
+/// Selects the character of the row if it was clicked, with shift-click
+/// selecting a range and ctrl-click adding or removing it.
+fn select_response(row: &TableRow<'_, '_>, character: &Chr) -> Option<Response> {
+    let response = row.response();
+    if !response.clicked() {
+        return None
+    }
+
+    let modifiers = response.ctx.input(|input| input.modifiers);
+    let mode = if modifiers.shift {
+        SelectMode::Range
+    } else if modifiers.command {
+        SelectMode::Toggle
+    } else {
+        SelectMode::Only
+    };
+
+    Some(Response::Select(character.name.clone(), mode))
+}
+
+fn show_character_cols(tracker: &Tracker<impl Saver>, responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, character: &Chr) {
+    show_health_col(responses, row, character);
+
+    show_modifiers_col(tracker, row, character);
+
+    show_conds_col(tracker, responses, row, character);
+
+    show_options_col(responses, row, character);
+
+    show_remove_col(responses, row, character);
+}
+
+fn show_group_name_col(row: &mut TableRow<'_, '_>, group: &str, members: &[Chr], is_in_turn: bool) {
+    row.col(|ui| {
+        let id = group_id(group);
+        let expanded = ui.data(|data| data.get_temp(id).unwrap_or(false));
+        let init = members.first().map_or(0, |chr| chr.init);
+
+        let init_text = egui::RichText::new(format!("{init:>2}")).size(18.0).monospace();
+        let name_text = egui::RichText::new(format!("{} {group} ({})", if expanded { "\u{23F7}" } else { "\u{23F5}" }, members.len())).size(16.0);
+
+        let name = if is_in_turn {
+            ui.add(egui::Label::new(init_text.strong()));
+            ui.add(egui::Label::new(name_text.strong()).sense(egui::Sense::click()))
+        } else {
+            ui.add(egui::Label::new(init_text));
+            ui.add(egui::Label::new(name_text).sense(egui::Sense::click()))
+        };
+
+        if name.clicked() {
+            ui.data_mut(|data| data.insert_temp(id, !expanded));
+        }
+    });
+}
+
+fn show_member_name_col(responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, character: &Chr) {
+    row.col(|ui| {
+        ui.add_space(30.0);
+        let name = ui.add(egui::Label::new(egui::RichText::new(character.name.clone()).size(14.0)));
+
+        show_variant_tag(ui, character);
+
+        if name.clicked() {
+            responses.push(Response::RenameCharacter(character.name.clone()));
+        }
+    });
+}
+
+fn show_group_health_col(row: &mut TableRow<'_, '_>, members: &[Chr]) {
+    row.col(|ui| {
+        let healths: Vec<&Health> = members.iter().filter_map(|chr| chr.health.as_ref()).collect();
+        if healths.is_empty() {
+            return
+        }
+
+        let current: u32 = healths.iter().map(|health| health.current).sum();
+        let max: u32 = healths.iter().map(|health| health.max).sum();
+        let standing = members.iter().filter(|chr| !chr.is_down()).count();
+
+        let bar = health_bar(&Health { current, ..Health::new(max.max(1)) })
+            .text(format!("{standing}/{} standing", members.len()));
+        ui.add(bar).on_hover_text(format!("{current}/{max} HP in total"));
+    });
+}
+
+fn show_group_remove_col(responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, group: &str) {
+    row.col(|ui| {
+        let button = egui::Button::new("\u{1F5D9}").frame(false).small();
+        if ui.add(button).on_hover_text("Removes the whole group.").clicked() {
+            responses.push(Response::RemoveGroup(group.to_string()));
+        }
+    });
+}
+
 fn show_remove_col(responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, character: &Chr) {
     row.col(|ui| {
         let button = egui::Button::new("\u{1F5D9}").frame(false).small();
@@ -106,6 +267,18 @@ fn show_options_col(responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, c
     });
 }
 
+fn show_modifiers_col(tracker: &Tracker<impl Saver>, row: &mut TableRow<'_, '_>, character: &Chr) {
+    row.col(|ui| {
+        let modifiers = tracker.get_modifiers(&character.name);
+        if !modifiers.is_empty() {
+            ui.with_layout(egui::Layout::right_to_left(Align::Center), |ui| {
+                ui.add(egui::Label::new(egui::RichText::new(modifiers.to_string()).small().weak()).truncate())
+                    .on_hover_text(modifiers.to_string());
+            });
+        }
+    });
+}
+
 fn show_conds_col(tracker: &Tracker<impl Saver>, responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, character: &Chr) {
     row.col(|ui| {
         let mut conditions: Vec<_> = tracker.get_conditions(&character.name).into_iter().map(ToOwned::to_owned).collect();
@@ -139,6 +312,11 @@ fn show_health_col(responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, ch
     row.col(|ui| {
         if let Some(health) = &character.health {
             let bar_resp = ui.add(health_bar(health)).interact(egui::Sense::click());
+            let bar_resp = if health.drained > 0 {
+                bar_resp.on_hover_text(format!("Max HP lowered by {} from being drained.", health.drained))
+            } else {
+                bar_resp
+            };
 
             egui::Popup::menu(&bar_resp).close_behavior(egui::PopupCloseBehavior::CloseOnClick).show(|ui|{
                 if ui.button("Damage").clicked() {
@@ -171,12 +349,41 @@ fn show_name_col(responses: &mut Vec<Response>, row: &mut TableRow<'_, '_>, char
             ui.add(egui::Label::new(egui::RichText::new(character.name.clone()).size(16.0)))
         };
 
+        show_variant_tag(ui, character);
+
+        let name = match &character.stats {
+            Some(stats) => name.on_hover_text(stats_text(stats)),
+            None => name,
+        };
+
         if name.clicked() {
             responses.push(Response::RenameCharacter(character.name.clone()));
         }
     });
 }
 
+fn stats_text(stats: &Stats) -> String {
+    let mut lines = vec![format!(
+        "AC {}, Fort {:+}, Ref {:+}, Will {:+}, Perception {:+}",
+        stats.ac, stats.saves.fortitude, stats.saves.reflex, stats.saves.will, stats.perception
+    )];
+    if !stats.resistances.is_empty() {
+        let resistances: Vec<String> = stats.resistances.iter().map(ToString::to_string).collect();
+        lines.push(format!("Resistances: {}", resistances.join(", ")));
+    }
+    if !stats.weaknesses.is_empty() {
+        let weaknesses: Vec<String> = stats.weaknesses.iter().map(ToString::to_string).collect();
+        lines.push(format!("Weaknesses: {}", weaknesses.join(", ")));
+    }
+    lines.join("\n")
+}
+
+fn show_variant_tag(ui: &mut Ui, character: &Chr) {
+    if let Some(variant) = character.variant {
+        ui.add(egui::Label::new(egui::RichText::new(variant.to_string()).size(12.0).italics().weak()));
+    }
+}
+
 const HP_WIDTH: f32 = 100.0;
 
 fn health_bar(hp: &Health) -> ProgressBar {
@@ -188,6 +395,12 @@ fn health_bar(hp: &Health) -> ProgressBar {
         format!("{}/{}", hp.current, hp.max)
     };
 
+    let hp_str = if hp.drained > 0 {
+        format!("{hp_str} (-{})", hp.drained)
+    } else {
+        hp_str
+    };
+
     egui::ProgressBar::new(rel_hp)
         .text(hp_str)
         .corner_radius(2.0)
@@ -10,7 +10,8 @@ use crate::{
 
 #[derive(Debug, Clone)]
 pub enum Response {
-    AddCondition{character: ChrName, cond: Condition},
+    /// Adds each condition to the character it is paired with.
+    AddCondition{conds: Vec<(ChrName, Condition)>},
     RemoveCondition{character: ChrName, cond: Condition}
 }
 
@@ -45,14 +46,17 @@ pub struct CondWindow {
 #[derive(Debug, Clone, Default)]
 struct Data {
     character: Option<ChrName>,
+    targets: Vec<ChrName>,
     selected: ConditionEntry,
     cond_value: u8,
     auto_tracking: bool,
     selected_nonvalued_term: NonValuedTermEntry,
     selected_valued_term: ValuedTermEntry,
-    term_rounds: u32,
+    term_duration: String,
     selected_turn_event: TurnEventEntry,
-    selected_turn_event_character: ChrName,
+    /// The character whose turn the turn event waits for, or `None` for each
+    /// target waiting for their own turn.
+    selected_turn_event_character: Option<ChrName>,
     reduction: u8
 }
 
@@ -88,20 +92,29 @@ impl Display for TurnEventEntry {
 impl CondWindow {
     pub fn open(&mut self, character: ChrName) {
         self.reset();
-        self.data.selected_turn_event_character = character.clone();
+        self.data.targets = vec![character.clone()];
         self.data.character = Some(character);
         self.open = true;
     }
 
+    /// Opens the window for the first of [`characters`], with all of them as
+    /// targets of the condition to add.
+    pub fn open_many(&mut self, characters: Vec<ChrName>) {
+        let Some(first) = characters.first().cloned() else { return };
+        self.open(first);
+        self.data.targets = characters;
+    }
+
     fn reset(&mut self) {
         self.data.character = None;
+        self.data.targets = Vec::new();
         self.data.selected = ConditionEntry::default();
         self.data.cond_value = 0;
         self.data.auto_tracking = false;
         self.data.selected_nonvalued_term = NonValuedTermEntry::default();
         self.data.selected_valued_term = ValuedTermEntry::default();
-        self.data.term_rounds = 0;
-        self.data.selected_turn_event_character = ChrName::default();
+        self.data.term_duration = String::new();
+        self.data.selected_turn_event_character = None;
     }
 
     pub fn close(&mut self) {
@@ -135,8 +148,8 @@ impl CondWindow {
 
                     for resp in responses {
                         match resp {
-                            Response::AddCondition { character, cond } => {
-                                tracker.add_condition(character, cond)?;
+                            Response::AddCondition { conds } => {
+                                tracker.add_condition_many(conds)?;
                             }
                             Response::RemoveCondition { character, cond } => {
                                 tracker.rm_condition(&character, &cond);
@@ -161,64 +174,119 @@ fn show_cond_section(ui: &mut Ui, data: &mut Data, character: &ChrName, characte
         ui.set_max_width(200.);
         ui.label("Add Condition:");
 
+        show_targets(ui, data, &characters);
+
         show_cond_selector(ui, data, character, characters, format);
 
         ui.separator();
 
-        show_add_button(ui, data, character)
+        show_add_button(ui, data)
     })
     .inner
 }
 
-fn show_add_button(ui: &mut Ui, data: &Data, character: &ChrName) -> Option<Response> {
-    if ui.button("Add").clicked() {
-        let condition = match data.selected {
-            ConditionEntry::Valued(valued_condition) => {
-                let builder = Condition::builder()
-                    .condition(valued_condition)
-                    .value(data.cond_value);
+fn show_add_button(ui: &mut Ui, data: &Data) -> Option<Response> {
+    let conds: Option<Vec<(ChrName, Condition)>> = data.targets.iter()
+        .map(|target| create_condition(data, target).map(|cond| (target.clone(), cond)))
+        .collect();
+    let enabled = !data.targets.is_empty() && conds.is_some();
 
-                if data.auto_tracking {
-                    match data.selected_valued_term {
-                        ValuedTermEntry::For => builder.term(ValuedTerm::For(Duration::from_turns(data.term_rounds))).build(),
-                        ValuedTermEntry::Until => builder.term(ValuedTerm::Until(create_turn_event(data))).build(),
-                        ValuedTermEntry::Reduced => builder.term(ValuedTerm::Reduced(create_turn_event(data), data.reduction)).build()
-                    }
-                } else {
-                    builder.build()
+    if ui.add_enabled(enabled, egui::Button::new("Add")).clicked() {
+        conds.map(|conds| Response::AddCondition { conds })
+    } else {
+        None
+    }
+}
+
+/// Builds the selected condition for [`affected`], or `None` if it lasts for a
+/// duration that can't be parsed.
+fn create_condition(data: &Data, affected: &ChrName) -> Option<Condition> {
+    let condition = match data.selected {
+        ConditionEntry::Valued(valued_condition) => {
+            let builder = Condition::builder()
+                .condition(valued_condition)
+                .value(data.cond_value);
+
+            if data.auto_tracking {
+                match data.selected_valued_term {
+                    ValuedTermEntry::For => builder.term(ValuedTerm::For(data.term_duration.parse().ok()?)).build(),
+                    ValuedTermEntry::Until => builder.term(ValuedTerm::Until(create_turn_event(data, affected))).build(),
+                    ValuedTermEntry::Reduced => builder.term(ValuedTerm::Reduced(create_turn_event(data, affected), data.reduction)).build()
                 }
+            } else {
+                builder.build()
             }
-            ConditionEntry::NonValued(non_valued_condition) => {
-                let builder = Condition::builder().condition(non_valued_condition);
+        }
+        ConditionEntry::NonValued(non_valued_condition) => {
+            let builder = Condition::builder().condition(non_valued_condition);
 
-                if data.auto_tracking {
-                    match data.selected_nonvalued_term {
-                        NonValuedTermEntry::For => builder.term(NonValuedTerm::For(Duration::from_turns(data.term_rounds))).build(),
-                        NonValuedTermEntry::Until => builder.term(NonValuedTerm::Until(create_turn_event(data))).build()
+            if data.auto_tracking {
+                match data.selected_nonvalued_term {
+                    NonValuedTermEntry::For => builder.term(NonValuedTerm::For(data.term_duration.parse().ok()?)).build(),
+                    NonValuedTermEntry::Until => builder.term(NonValuedTerm::Until(create_turn_event(data, affected))).build()
+                }
+            } else {
+                builder.build()
+            }
+        }
+    };
+
+    Some(condition)
+}
+
+/// Shows a multi-select list of the characters to add the condition to.
+fn show_targets(ui: &mut Ui, data: &mut Data, characters: &[Chr]) {
+    ui.collapsing(format!("Targets ({})", data.targets.len()), |ui| {
+        egui::ScrollArea::vertical().max_height(120.).show(ui, |ui| {
+            for c in characters {
+                let mut targeted = data.targets.contains(&c.name);
+                if ui.checkbox(&mut targeted, c.name.to_string()).changed() {
+                    if targeted {
+                        data.targets.push(c.name.clone());
+                    } else {
+                        data.targets.retain(|name| *name != c.name);
                     }
-                } else {
-                    builder.build()
                 }
             }
-        };
+        });
+    });
 
-        Some(Response::AddCondition {
-            character: character.clone(),
-            cond: condition,
-        })
-    } else {
-        None
-    }
+    data.targets.sort_by_key(|name| characters.iter().position(|c| c.name == name));
 }
 
-fn create_turn_event(data: &Data) -> TurnEvent {
+fn create_turn_event(data: &Data, affected: &ChrName) -> TurnEvent {
+    let character = data.selected_turn_event_character.clone().unwrap_or_else(|| affected.clone());
     match data.selected_turn_event {
-        TurnEventEntry::StartOfNextTurn => TurnEvent::StartOfNextTurn(data.selected_turn_event_character.clone()),
-        TurnEventEntry::EndOfNextTurn => TurnEvent::EndOfNextTurn(data.selected_turn_event_character.clone()),
-        TurnEventEntry::EndOfCurrentTurn => TurnEvent::EndOfCurrentTurn(data.selected_turn_event_character.clone())
+        TurnEventEntry::StartOfNextTurn => TurnEvent::StartOfNextTurn(character),
+        TurnEventEntry::EndOfNextTurn => TurnEvent::EndOfNextTurn(character),
+        TurnEventEntry::EndOfCurrentTurn => TurnEvent::EndOfCurrentTurn(character)
     }
 }
 
+/// Shows a choice of whose turn the turn event waits for, where the first
+/// choice is each target waiting for their own turn.
+fn show_turn_event_character(ui: &mut Ui, data: &mut Data, characters: Vec<Chr>) {
+    let own_turn = match data.targets.as_slice() {
+        [target] => target.to_string(),
+        _ => String::from("each target"),
+    };
+    let selected = data.selected_turn_event_character.as_ref().map_or_else(|| own_turn.clone(), ToString::to_string);
+
+    egui::ComboBox::from_id_salt("turn event character")
+        .width(30.)
+        .selected_text(selected)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut data.selected_turn_event_character, None, own_turn);
+            for c in characters {
+                ui.selectable_value(
+                    &mut data.selected_turn_event_character,
+                    Some(c.name.clone()),
+                    String::from(c.name),
+                );
+            }
+        });
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 enum NonValuedTermEntry {
     #[default]
@@ -360,24 +428,24 @@ fn show_reduced_options(ui: &mut Ui, data: &mut Data, characters: Vec<Chr>) {
             show_turn_event_options(ui, data);
         });
 
-        egui::ComboBox::from_id_salt("turn event character")
-            .width(30.)
-            .selected_text(String::from(data.selected_turn_event_character.clone()))
-            .show_ui(ui, |ui| {
-                for c in characters {
-                    ui.selectable_value(
-                        &mut data.selected_turn_event_character,
-                        c.name.clone(),
-                        String::from(c.name),
-                    );
-                }
-            });
+        show_turn_event_character(ui, data, characters);
     });
 }
 
 fn show_for_options(ui: &mut Ui, data: &mut Data) {
-    let drag = egui::DragValue::new(&mut data.term_rounds).suffix(" rounds");
-    ui.add(drag);
+    ui.vertical(|ui| {
+        let edit = egui::TextEdit::singleline(&mut data.term_duration)
+            .hint_text("1 round, 1h30m")
+            .desired_width(100.);
+        ui.add(edit);
+
+        if !data.term_duration.trim().is_empty() {
+            match data.term_duration.parse::<Duration>() {
+                Ok(dur) => ui.weak(dur.to_string()),
+                Err(err) => ui.colored_label(ui.visuals().error_fg_color, err.to_string()),
+            };
+        }
+    });
 }
 
 fn show_until_options(ui: &mut Ui, data: &mut Data, characters: Vec<Chr>) {
@@ -387,18 +455,7 @@ fn show_until_options(ui: &mut Ui, data: &mut Data, characters: Vec<Chr>) {
             ui.label("of");
         });
 
-        egui::ComboBox::from_id_salt("turn event character")
-            .width(30.)
-            .selected_text(data.selected_turn_event_character.clone())
-            .show_ui(ui, |ui| {
-                for c in characters {
-                    ui.selectable_value(
-                        &mut data.selected_turn_event_character,
-                        c.name.clone(),
-                        c.name,
-                    );
-                }
-            });
+        show_turn_event_character(ui, data, characters);
     });
 }
 
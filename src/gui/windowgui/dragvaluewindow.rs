@@ -1,5 +1,6 @@
 use egui::{Context, Id};
 use super::Confirmation;
+use crate::dice::{DiceExpr, Roller};
 
 #[derive(Debug)]
 pub struct DragValueWindow<T, E> where
@@ -9,6 +10,10 @@ pub struct DragValueWindow<T, E> where
     focus: bool,
     elem: Option<E>,
     val: T,
+    /// The dice roller and the dice to roll for the value, if rolling is
+    /// enabled.
+    dice: Option<(Roller, String)>,
+    last_roll: Option<String>,
 }
 
 impl<T,E> Default for DragValueWindow<T, E> where
@@ -19,7 +24,9 @@ impl<T,E> Default for DragValueWindow<T, E> where
             open: false,
             focus: false,
             elem: None,
-            val: T::default()
+            val: T::default(),
+            dice: None,
+            last_roll: None,
         }
     }
 }
@@ -28,9 +35,20 @@ impl<T, E> DragValueWindow<T, E> where
     T: egui::emath::Numeric + Default,
     E: Clone,
 {
+    /// Lets the value be rolled with dice, such as for damage.
+    #[must_use]
+    pub fn with_dice(mut self) -> Self {
+        self.dice = Some((Roller::default(), String::new()));
+        self
+    }
+
     fn reset(&mut self) {
         self.elem = None;
         self.val = T::default();
+        self.last_roll = None;
+        if let Some((_, expr)) = self.dice.as_mut() {
+            expr.clear();
+        }
     }
 
     pub fn open(&mut self, elem: E) {
@@ -56,6 +74,7 @@ impl<T, E> DragValueWindow<T, E> where
                 ui.label(label(elem.clone(), self.val));
                 ui.add(egui::DragValue::new(&mut self.val).range(0..=999));
             });
+            self.show_dice_row(ui);
             ui.separator();
 
             let response = super::show_confirmation_bar(ui);
@@ -74,4 +93,28 @@ impl<T, E> DragValueWindow<T, E> where
             Ok(())
         }).inner)
     }
+
+    fn show_dice_row(&mut self, ui: &mut egui::Ui) {
+        let Some((roller, expr)) = self.dice.as_mut() else { return };
+
+        let parsed = expr.parse::<DiceExpr>();
+        ui.horizontal(|ui| {
+            ui.label("Dice: ");
+            ui.add(egui::TextEdit::singleline(expr).hint_text("2d6+3").desired_width(80.0));
+            let button = ui.add_enabled(parsed.is_ok(), egui::Button::new("\u{1F3B2}"))
+                .on_hover_text("Rolls the dice and uses the total.");
+
+            if let (true, Ok(parsed)) = (button.clicked(), &parsed) {
+                let roll = roller.roll(parsed);
+                self.val = T::from_f64(f64::from(roll.total.max(0)));
+                self.last_roll = Some(roll.to_string());
+            }
+        });
+
+        match (&parsed, &self.last_roll) {
+            (Err(err), _) if !expr.is_empty() => { ui.label(err.to_string()); },
+            (_, Some(roll)) => { ui.label(roll); },
+            _ => (),
+        }
+    }
 }
@@ -0,0 +1,173 @@
+use egui::{Context, Ui};
+
+use crate::{character::{Chr, Health}, encounter::{Encounter, Party, Rating, Threat}, saver::Saver, tracker::Tracker};
+
+#[derive(Debug, Clone)]
+pub struct EncounterWindow {
+    open: bool,
+    /// The party the planned encounter is rated for.
+    party: Party,
+    plan: Encounter,
+    name: String,
+    level: i32,
+    health: u32,
+    init_mod: i32,
+    count: u32,
+    /// The id of the bestiary template picked for the next creature, if any.
+    template: Option<String>,
+}
+
+impl Default for EncounterWindow {
+    fn default() -> Self {
+        Self {
+            open: false,
+            party: Party { level: 1, size: 4 },
+            plan: Encounter::new(),
+            name: String::new(),
+            level: 1,
+            health: 0,
+            init_mod: 0,
+            count: 1,
+            template: None,
+        }
+    }
+}
+
+impl EncounterWindow {
+    pub fn open(&mut self, tracker: &Tracker<impl Saver>) {
+        self.open = true;
+        if let Some(party) = tracker.get_party() {
+            self.party = party;
+        }
+    }
+
+    pub fn show<S: Saver>(&mut self, tracker: &mut Tracker<S>, ctx: &Context) -> super::Result<()> {
+        if !self.open { return Ok(()) }
+
+        let mut open = self.open;
+        let mut res = Ok(());
+        egui::Window::new("Encounter")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.heading("Current");
+                match tracker.get_threat() {
+                    Some(rating) => show_rating(ui, &rating),
+                    None => { ui.label("Give the player characters a level to rate the encounter."); },
+                }
+
+                ui.separator();
+
+                ui.heading("Plan");
+                self.show_party(ui);
+                self.show_creature_input(tracker, ui);
+                self.show_plan(ui);
+                show_rating(ui, &self.plan.rate(self.party));
+
+                ui.horizontal(|ui| {
+                    let add = ui.add_enabled(!self.plan.is_empty(), egui::Button::new("Add to tracker"))
+                        .on_hover_text("Adds the planned creatures, rolling their initiative.");
+                    if add.clicked() {
+                        res = tracker.add_encounter(&self.plan).map(|_| ());
+                        self.plan = Encounter::new();
+                    }
+
+                    if ui.button("Clear").clicked() {
+                        self.plan = Encounter::new();
+                    }
+                });
+            });
+        self.open = open;
+
+        Ok(res?)
+    }
+
+    fn show_party(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Party of");
+            ui.add(egui::DragValue::new(&mut self.party.size).range(1..=12));
+            ui.label("at level");
+            ui.add(egui::DragValue::new(&mut self.party.level).range(-1..=25));
+        });
+    }
+
+    fn show_creature_input(&mut self, tracker: &Tracker<impl Saver>, ui: &mut Ui) {
+        let bestiary = tracker.get_bestiary();
+        if !bestiary.get_templates().is_empty() {
+            let selected = self.template.as_deref().unwrap_or("No template");
+            egui::ComboBox::from_label("Template")
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    for template in bestiary.get_templates() {
+                        let picked = self.template.as_ref() == Some(&template.id);
+                        if ui.selectable_label(picked, &template.name).clicked() {
+                            self.name.clone_from(&template.name);
+                            self.level = template.level;
+                            self.health = template.hp;
+                            self.init_mod = template.init_mod;
+                            self.template = Some(template.id.clone());
+                        }
+                    }
+                });
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.name).hint_text("Name").desired_width(100.0));
+            ui.add(egui::DragValue::new(&mut self.level).range(-1..=25).prefix("level "));
+            ui.add(egui::DragValue::new(&mut self.health).range(0..=999).prefix("HP "));
+            ui.add(egui::DragValue::new(&mut self.count).range(1..=50).prefix("× "));
+
+            if ui.add_enabled(!self.name.is_empty(), egui::Button::new("Add")).clicked() {
+                let builder = Chr::builder(self.name.clone(), 0, false)
+                    .with_level(self.level)
+                    .with_init_mod(self.init_mod);
+                let builder = if self.health == 0 { builder } else { builder.with_health(Health::new(self.health)) };
+                let builder = match &self.template { Some(id) => builder.with_template(id.clone()), None => builder };
+                self.plan.add(builder.build(), self.count);
+
+                self.name = String::new();
+                self.template = None;
+                self.init_mod = 0;
+                self.count = 1;
+            }
+        });
+    }
+
+    fn show_plan(&mut self, ui: &mut Ui) {
+        let mut removed = None;
+        egui::Grid::new("encounter_plan").show(ui, |ui| {
+            for (i, (chr, count)) in self.plan.get_entries().iter().enumerate() {
+                ui.label(format!("{count} × {}", chr.name));
+                ui.label(chr.level.map_or_else(String::new, |level| format!("level {level}")));
+                if ui.small_button("\u{1F5D9}").on_hover_text("Removes the creature from the plan.").clicked() {
+                    removed = Some(i);
+                }
+                ui.end_row();
+            }
+        });
+
+        if let Some(i) = removed {
+            self.plan.remove(i);
+        }
+    }
+}
+
+fn show_rating(ui: &mut Ui, rating: &Rating) {
+    ui.label(egui::RichText::new(format!("{}: {} XP", rating.threat, rating.xp)).strong());
+
+    ui.horizontal(|ui| {
+        for threat in Threat::ALL {
+            let text = format!("{threat} {}", threat.budget(rating.party.size));
+            if threat == rating.threat {
+                ui.label(egui::RichText::new(text).underline());
+            } else {
+                ui.label(egui::RichText::new(text).weak());
+            }
+        }
+    });
+
+    match rating.unknown {
+        0 => (),
+        1 => { ui.label("1 creature without a level isn't counted."); },
+        n => { ui.label(format!("{n} creatures without a level aren't counted.")); },
+    }
+}
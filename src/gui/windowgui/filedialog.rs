@@ -0,0 +1,65 @@
+use std::{future::Future, path::PathBuf, pin::Pin, task::{Poll, Waker}, time::Duration};
+
+use egui::Context;
+use rfd::{AsyncFileDialog, FileHandle};
+
+/// What to do with the file picked in a [`FileDialog`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileAction {
+    ImportPathbuilder,
+    ImportFoundry,
+    ExportFoundry,
+}
+
+type Picked = Pin<Box<dyn Future<Output = Option<FileHandle>>>>;
+
+/// A dialog for picking a file, which is left open while the window keeps
+/// being drawn, rather than blocking it until a file is picked.
+#[derive(Default)]
+pub struct FileDialog {
+    pending: Option<(FileAction, Picked)>,
+}
+
+impl FileDialog {
+    /// Opens the dialog for picking the file of [`action`], unless a dialog is
+    /// open already.
+    pub fn open(&mut self, action: FileAction) {
+        if self.pending.is_some() { return }
+
+        let picked: Picked = match action {
+            FileAction::ImportPathbuilder => Box::pin(AsyncFileDialog::new()
+                .set_title("Import from Pathbuilder")
+                .add_filter("Pathbuilder export", &["json", "txt"])
+                .pick_file()),
+            FileAction::ImportFoundry => Box::pin(AsyncFileDialog::new()
+                .set_title("Import from Foundry VTT")
+                .add_filter("Foundry combat", &["json"])
+                .pick_file()),
+            FileAction::ExportFoundry => Box::pin(AsyncFileDialog::new()
+                .set_title("Export to Foundry VTT")
+                .add_filter("Foundry combat", &["json"])
+                .set_file_name("combat.json")
+                .save_file()),
+        };
+        self.pending = Some((action, picked));
+    }
+
+    /// The action and the picked file once the dialog is closed with a file
+    /// picked. While the dialog is open, the window is redrawn now and then
+    /// to check on it.
+    pub fn poll(&mut self, ctx: &Context) -> Option<(FileAction, PathBuf)> {
+        let (action, picked) = self.pending.as_mut()?;
+        let action = *action;
+
+        match picked.as_mut().poll(&mut std::task::Context::from_waker(Waker::noop())) {
+            Poll::Ready(file) => {
+                self.pending = None;
+                file.map(|file| (action, file.path().to_path_buf()))
+            },
+            Poll::Pending => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+                None
+            },
+        }
+    }
+}
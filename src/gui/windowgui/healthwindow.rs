@@ -17,7 +17,8 @@ pub struct HealthWindow {
 struct HealthData {
     current: u32,
     max: u32,
-    temp: u32
+    temp: u32,
+    drained: u32,
 }
 
 impl HealthWindow {
@@ -26,7 +27,8 @@ impl HealthWindow {
             self.health = HealthData {
                 current: health.current,
                 max: health.max,
-                temp: health.temp
+                temp: health.temp,
+                drained: health.drained,
             }
         }
 
@@ -65,7 +67,7 @@ impl HealthWindow {
 
         match confirmation {
             Some(Confirmation::Confirm) => {
-                let health = Health { max: self.health.max, current: self.health.current, temp: self.health.temp };
+                let health = Health { max: self.health.max, current: self.health.current, temp: self.health.temp, drained: self.health.drained };
                 tracker.set_health(name, health)?;
                 self.close();
             },
@@ -84,5 +86,9 @@ impl HealthWindow {
             ui.label(" + ");
             ui.add(egui::DragValue::new(&mut self.health.temp).range(0..=999));
         });
+
+        if self.health.drained > 0 {
+            ui.weak(format!("Max HP lowered by {} from being drained.", self.health.drained));
+        }
     }
 }
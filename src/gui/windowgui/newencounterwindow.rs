@@ -0,0 +1,73 @@
+use egui::Context;
+
+use crate::{character::ChrName, dice::DiceExpr, saver::Saver, tracker::Tracker};
+
+/// A party member and whether they join the new encounter.
+#[derive(Debug, Clone)]
+struct Member {
+    name: ChrName,
+    init_mod: Option<i32>,
+    init: i32,
+    joins: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NewEncounterWindow {
+    open: bool,
+    members: Vec<Member>,
+}
+
+impl NewEncounterWindow {
+    /// Opens the window with the members of the party, who all join the new
+    /// encounter unless told otherwise.
+    pub fn open(&mut self, tracker: &Tracker<impl Saver>) {
+        self.open = true;
+        self.members = tracker.get_party_members().into_iter()
+            .map(|chr| Member { name: chr.name, init_mod: chr.init_mod, init: 0, joins: true })
+            .collect();
+    }
+
+    pub fn show<S: Saver>(&mut self, tracker: &mut Tracker<S>, ctx: &Context) -> super::Result<()> {
+        if !self.open { return Ok(()) }
+
+        let mut open = self.open;
+        let mut res = Ok(());
+        egui::Window::new("New encounter")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                if self.members.is_empty() {
+                    ui.label("There are no player characters in the party.");
+                }
+
+                egui::Grid::new("new_encounter_party").show(ui, |ui| {
+                    for member in &mut self.members {
+                        ui.checkbox(&mut member.joins, member.name.as_ref())
+                            .on_hover_text("Whether the character is still in the party.");
+                        ui.add_enabled(member.joins, egui::DragValue::new(&mut member.init).range(-10..=60).prefix("init "));
+                        let roll = ui.add_enabled(member.joins, egui::Button::new("\u{1F3B2}"))
+                            .on_hover_text("Rolls initiative with the initiative modifier.");
+                        if roll.clicked() {
+                            member.init = tracker.roll(&DiceExpr::d20(member.init_mod.unwrap_or(0))).total;
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+
+                let start = ui.button("Start")
+                    .on_hover_text("Removes every enemy and every condition that ends with the encounter, keeping the party.");
+                if start.clicked() {
+                    let party: Vec<(ChrName, i32)> = self.members.iter()
+                        .filter(|member| member.joins)
+                        .map(|member| (member.name.clone(), member.init))
+                        .collect();
+                    res = tracker.new_encounter(&party);
+                    self.open = false;
+                }
+            });
+        self.open &= open;
+
+        Ok(res?)
+    }
+}
@@ -0,0 +1,71 @@
+use egui::Context;
+
+use crate::{gui::terminalgui, saver::Saver, tracker::Tracker};
+
+/// A prompt for running commands with the same syntax as the terminal
+/// interface, like `dmg 5 gob` or `cond add prone on Sarah`.
+#[derive(Debug, Clone, Default)]
+pub struct PaletteWindow {
+    open: bool,
+    focus: bool,
+    input: String,
+    /// The message of the last command, or its error.
+    output: Option<Result<String, String>>,
+}
+
+impl PaletteWindow {
+    pub const fn open(&mut self) {
+        self.open = true;
+        self.focus = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.input.clear();
+        self.output = None;
+    }
+
+    /// Shows the palette, running the command when enter is pressed. A
+    /// command without a message closes the palette, while one with a message
+    /// or an error keeps it open to show it.
+    pub fn show<S: Saver>(&mut self, tracker: &mut Tracker<S>, ctx: &Context) {
+        if !self.open { return }
+
+        let modal = egui::Modal::new("command palette".into()).show(ctx, |ui| {
+            ui.set_min_width(300.0);
+            let edit = ui.add(egui::TextEdit::singleline(&mut self.input)
+                .hint_text("command, like dmg 5 goblin")
+                .desired_width(f32::INFINITY));
+
+            if self.focus {
+                edit.request_focus();
+                self.focus = false;
+            }
+
+            match &self.output {
+                Some(Ok(msg)) => { ui.label(msg); },
+                Some(Err(err)) => { ui.colored_label(ui.visuals().error_fg_color, err); },
+                None => (),
+            }
+
+            if edit.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                match terminalgui::run_command(tracker, &self.input) {
+                    Ok(None) => self.close(),
+                    Ok(Some(msg)) => {
+                        self.input.clear();
+                        self.output = Some(Ok(msg));
+                        self.focus = true;
+                    },
+                    Err(err) => {
+                        self.output = Some(Err(err.to_string()));
+                        self.focus = true;
+                    },
+                }
+            }
+        });
+
+        if modal.should_close() {
+            self.close();
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use crate::character::ChrName;
+
+/// How clicking the row of a character changes the [`Selection`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectMode {
+    /// A plain click, selecting only the character.
+    Only,
+    /// A ctrl-click, adding or removing the character.
+    Toggle,
+    /// A shift-click, selecting the characters from the last clicked one to
+    /// this one.
+    Range,
+}
+
+/// The characters selected in the character table.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    names: Vec<ChrName>,
+    /// The last clicked character, which a range starts from.
+    anchor: Option<ChrName>,
+}
+
+impl Selection {
+    pub fn names(&self) -> &[ChrName] {
+        &self.names
+    }
+
+    pub fn contains(&self, name: &ChrName) -> bool {
+        self.names.contains(name)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The last clicked character, if it is still selected.
+    pub fn primary(&self) -> Option<&ChrName> {
+        self.anchor.as_ref().filter(|anchor| self.contains(anchor))
+    }
+
+    pub fn clear(&mut self) {
+        self.names.clear();
+        self.anchor = None;
+    }
+
+    /// Changes the selection by clicking [`name`], where [`order`] is the
+    /// order of the characters that ranges follow.
+    pub fn click(&mut self, name: ChrName, mode: SelectMode, order: &[ChrName]) {
+        match mode {
+            SelectMode::Only => {
+                self.names = vec![name.clone()];
+            },
+            SelectMode::Toggle => {
+                if let Some(i) = self.names.iter().position(|selected| *selected == name) {
+                    self.names.remove(i);
+                } else {
+                    self.names.push(name.clone());
+                }
+            },
+            SelectMode::Range => {
+                let from = self.anchor.as_ref().and_then(|anchor| order.iter().position(|chr| chr == anchor));
+                let to = order.iter().position(|chr| *chr == name);
+                let (Some(from), Some(to)) = (from, to) else {
+                    return self.click(name, SelectMode::Only, order)
+                };
+                self.names = order[from.min(to)..=from.max(to)].to_vec();
+                // The anchor stays, so the range can be changed by
+                // shift-clicking another character.
+                return
+            },
+        }
+        self.anchor = Some(name);
+    }
+
+    /// Deselects the characters that aren't in [`existing`] anymore.
+    pub fn retain(&mut self, existing: &[ChrName]) {
+        self.names.retain(|name| existing.contains(name));
+        if self.anchor.as_ref().is_some_and(|anchor| !existing.contains(anchor)) {
+            self.anchor = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::character::ChrName;
+
+    use super::{SelectMode, Selection};
+
+    fn order() -> Vec<ChrName> {
+        ["Alice", "Bob", "Carl", "Dana"].into_iter().map(ChrName::new).collect()
+    }
+
+    fn names(selection: &Selection) -> Vec<&str> {
+        selection.names().iter().map(AsRef::as_ref).collect()
+    }
+
+    #[test]
+    fn click_selects_only_the_character() {
+        let mut selection = Selection::default();
+        selection.click(ChrName::new("Alice"), SelectMode::Only, &order());
+        selection.click(ChrName::new("Carl"), SelectMode::Only, &order());
+        assert_eq!(vec!["Carl"], names(&selection));
+    }
+
+    #[test]
+    fn ctrl_click_toggles_the_character() {
+        let mut selection = Selection::default();
+        selection.click(ChrName::new("Alice"), SelectMode::Only, &order());
+        selection.click(ChrName::new("Carl"), SelectMode::Toggle, &order());
+        assert_eq!(vec!["Alice", "Carl"], names(&selection));
+
+        selection.click(ChrName::new("Alice"), SelectMode::Toggle, &order());
+        assert_eq!(vec!["Carl"], names(&selection));
+    }
+
+    #[test]
+    fn shift_click_selects_the_range_from_the_last_click() {
+        let mut selection = Selection::default();
+        selection.click(ChrName::new("Carl"), SelectMode::Only, &order());
+        selection.click(ChrName::new("Alice"), SelectMode::Range, &order());
+        assert_eq!(vec!["Alice", "Bob", "Carl"], names(&selection));
+
+        selection.click(ChrName::new("Dana"), SelectMode::Range, &order());
+        assert_eq!(vec!["Carl", "Dana"], names(&selection));
+    }
+
+    #[test]
+    fn removed_characters_are_deselected() {
+        let mut selection = Selection::default();
+        selection.click(ChrName::new("Alice"), SelectMode::Only, &order());
+        selection.click(ChrName::new("Bob"), SelectMode::Toggle, &order());
+        selection.retain(&[ChrName::new("Alice")]);
+        assert_eq!(vec!["Alice"], names(&selection));
+        assert_eq!(None, selection.primary());
+    }
+}
@@ -1,12 +1,14 @@
 use egui::Context;
 
-use crate::{gui::windowgui::Confirmation, saver::Saver, settings::Pf2eVersion, tracker::{self, Tracker}};
+use crate::{gui::windowgui::{shortcuts, Confirmation}, saver::Saver, settings::{Action, KeyBindings, Pf2eVersion}, tracker::{self, Tracker}};
 
 #[derive(Debug, Clone, Default)]
 pub struct SettingsWindow {
     open: bool,
     remastered: bool,
     undo_size: usize,
+    /// The keys of each action, separated by commas.
+    key_bindings: Vec<(Action, String)>,
 }
 
 impl SettingsWindow {
@@ -17,6 +19,10 @@ impl SettingsWindow {
             Pf2eVersion::Remastered => true,
         };
         self.undo_size = tracker.get_undo_size_setting();
+        let bindings = tracker.get_key_bindings_setting();
+        self.key_bindings = Action::ALL.into_iter()
+            .map(|action| (action, bindings.get(action).join(", ")))
+            .collect();
     }
 
     pub fn show<S: Saver>(&mut self, tracker: &mut Tracker<S>, ctx: &Context) -> tracker::Result<()> {
@@ -32,6 +38,8 @@ impl SettingsWindow {
                 let undo_size_slider = egui::Slider::new(&mut self.undo_size, 0..=124).text("Undo history size");
                 ui.add(undo_size_slider);
 
+                show_key_bindings(&mut self.key_bindings, ui);
+
                 match super::show_confirmation_bar(ui) {
                     None => Ok(()),
                     Some(Confirmation::Cancel) => {
@@ -46,6 +54,7 @@ impl SettingsWindow {
                         }
 
                         tracker.set_undo_size_setting(self.undo_size);
+                        tracker.set_key_bindings_setting(key_bindings(&self.key_bindings));
 
                         tracker.auto_save()?;
                         ui.close_kind(egui::UiKind::Window);
@@ -57,3 +66,31 @@ impl SettingsWindow {
     }
 }
 
+fn show_key_bindings(key_bindings: &mut [(Action, String)], ui: &mut egui::Ui) {
+    egui::CollapsingHeader::new("Shortcuts").show(ui, |ui| {
+        egui::Grid::new("key bindings").num_columns(2).show(ui, |ui| {
+            for (action, keys) in key_bindings {
+                ui.label(action.to_string());
+                let edit = ui.text_edit_singleline(keys);
+                if split_keys(keys).iter().any(|key| shortcuts::parse(key).is_none()) {
+                    edit.on_hover_text("Keys are written like Ctrl+Z, Shift+N or Space, separated by commas.");
+                    ui.colored_label(ui.visuals().error_fg_color, "unknown key");
+                }
+                ui.end_row();
+            }
+        });
+    });
+}
+
+/// The [`KeyBindings`] of the keys of each action, separated by commas.
+fn key_bindings(key_bindings: &[(Action, String)]) -> KeyBindings {
+    let mut bindings = KeyBindings::default();
+    for (action, keys) in key_bindings {
+        bindings.set(*action, split_keys(keys));
+    }
+    bindings
+}
+
+fn split_keys(keys: &str) -> Vec<String> {
+    keys.split(',').map(str::trim).filter(|key| !key.is_empty()).map(ToString::to_string).collect()
+}
@@ -0,0 +1,70 @@
+use egui::{Context, Key, KeyboardShortcut, Modifiers};
+
+use crate::settings::{Action, KeyBindings};
+
+/// Parses a key binding written like `Ctrl+Shift+Z` or `Space`. `Ctrl` is
+/// the command key on macOS.
+pub fn parse(binding: &str) -> Option<KeyboardShortcut> {
+    let mut parts: Vec<&str> = binding.split('+').map(str::trim).collect();
+    let key = Key::from_name(parts.pop()?)?;
+
+    let mut modifiers = Modifiers::NONE;
+    for part in parts {
+        modifiers |= match part.to_lowercase().as_str() {
+            "ctrl" | "cmd" | "command" => Modifiers::COMMAND,
+            "shift" => Modifiers::SHIFT,
+            "alt" | "option" => Modifiers::ALT,
+            _ => return None,
+        };
+    }
+
+    Some(KeyboardShortcut::new(modifiers, key))
+}
+
+/// The actions whose keys were pressed this frame, consuming the key presses.
+/// Nothing is pressed while a text field has focus or a modal window is open,
+/// so typing and filling in windows doesn't set off actions.
+pub fn pressed(ctx: &Context, bindings: &KeyBindings) -> Vec<Action> {
+    if ctx.wants_keyboard_input() || ctx.memory(|memory| memory.top_modal_layer().is_some()) {
+        return Vec::new()
+    }
+
+    let mut shortcuts: Vec<(Action, KeyboardShortcut)> = Action::ALL.into_iter()
+        .flat_map(|action| bindings.get(action).iter().filter_map(|binding| parse(binding)).map(move |shortcut| (action, shortcut)))
+        .collect();
+    // Shortcuts with more modifiers go first, so `Ctrl+Shift+Z` isn't taken
+    // for `Ctrl+Z`.
+    shortcuts.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut.modifiers)));
+
+    ctx.input_mut(|input| {
+        shortcuts.into_iter()
+            .filter(|(_, shortcut)| input.consume_shortcut(shortcut))
+            .map(|(action, _)| action)
+            .collect()
+    })
+}
+
+fn modifier_count(modifiers: Modifiers) -> usize {
+    [modifiers.alt, modifiers.ctrl || modifiers.command || modifiers.mac_cmd, modifiers.shift].into_iter().filter(|x| *x).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Key, KeyboardShortcut, Modifiers};
+
+    use super::parse;
+
+    #[test]
+    fn parses_keys_with_modifiers() {
+        assert_eq!(Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::Z)), parse("Ctrl+Z"));
+        assert_eq!(Some(KeyboardShortcut::new(Modifiers::COMMAND | Modifiers::SHIFT, Key::Z)), parse("ctrl + shift + Z"));
+        assert_eq!(Some(KeyboardShortcut::new(Modifiers::NONE, Key::Space)), parse("Space"));
+    }
+
+    #[test]
+    fn unknown_keys_and_modifiers_are_invalid() {
+        assert_eq!(None, parse("Ctrl+Banana"));
+        assert_eq!(None, parse("Hyper+Z"));
+        assert_eq!(None, parse(""));
+    }
+}
@@ -0,0 +1,70 @@
+use egui::Context;
+
+use crate::{duration::Duration, gui::format_clock, saver::Saver, tracker::Tracker};
+
+#[derive(Debug, Clone, Default)]
+pub struct TimeWindow {
+    open: bool,
+    minutes: u32,
+    hours: u32,
+    days: u32,
+}
+
+impl TimeWindow {
+    pub const fn open(&mut self) {
+        self.open = true;
+    }
+
+    const fn reset(&mut self) {
+        self.minutes = 0;
+        self.hours = 0;
+        self.days = 0;
+    }
+
+    pub fn show<S: Saver>(&mut self, tracker: &mut Tracker<S>, ctx: &Context) -> super::Result<()> {
+        if !self.open { return Ok(()) }
+
+        let open = &mut self.open;
+        let mut res = Ok(());
+        egui::Window::new("Time")
+            .open(open)
+            .show(ctx, |ui| {
+                ui.label(format!("It is {}.", format_clock(tracker.get_clock())));
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.days).range(0..=99).suffix(" d"));
+                    ui.add(egui::DragValue::new(&mut self.hours).range(0..=23).suffix(" h"));
+                    ui.add(egui::DragValue::new(&mut self.minutes).range(0..=59).suffix(" min"));
+
+                    if ui.button("Advance").on_hover_text("Lets the time pass, counting down conditions.").clicked() {
+                        let dur = Duration::builder()
+                            .with_days(self.days)
+                            .with_hours(self.hours)
+                            .with_minutes(self.minutes)
+                            .build();
+                        res = tracker.advance_time(dur);
+                    }
+                });
+
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button("Rest").on_hover_text("Rests for 8 hours, removing fatigued and reducing drained and doomed.").clicked() {
+                        res = tracker.rest();
+                    }
+
+                    if ui.button("Refocus").on_hover_text("Refocuses for 10 minutes.").clicked() {
+                        res = tracker.refocus();
+                    }
+                });
+            });
+
+        if !self.open {
+            self.reset();
+        }
+
+        Ok(res?)
+    }
+}
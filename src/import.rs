@@ -0,0 +1,30 @@
+use std::{fs, io, path::Path};
+
+use thiserror::Error;
+
+pub mod foundry;
+pub mod pathbuilder;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("couldn't read `{0}` due to I/O error `{1}`")]
+    ReadError(String, #[source] io::Error),
+    #[error("couldn't write `{0}` due to I/O error `{1}`")]
+    WriteError(String, #[source] io::Error),
+    #[error("couldn't import `{0}` due to invalid JSON `{1}`")]
+    InvalidJson(String, #[source] serde_json::Error),
+    #[error("couldn't import `{0}` as the export wasn't successful")]
+    UnsuccessfulExport(String),
+    #[error("couldn't import `{file}`: {reason}")]
+    InvalidField { file: String, reason: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Reads the file at [`path`] into a string, giving the path of the file
+/// along with it for error messages.
+fn read(path: &Path) -> Result<(String, String)> {
+    let file = path.display().to_string();
+    let json = fs::read_to_string(path).map_err(|err| Error::ReadError(file.clone(), err))?;
+    Ok((file, json))
+}
@@ -0,0 +1,508 @@
+//! Importing and exporting encounters as Foundry VTT combat documents, with
+//! the actors of the Pathfinder 2e system.
+//!
+//! Foundry keeps no end to conditions, so conditions are exported without
+//! their termination criteria and imported to be managed manually. Conditions
+//! Foundry doesn't know about, and the other way around, are left out.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{bestiary::Saves, character::{Chr, ChrName, Health, Stats}, conditions::{condition_manager::ConditionManager, Condition, DamageType, NonValuedCondition, NonValuedTerm, ValuedCondition, ValuedTerm}, tracker::TrackerData};
+
+use super::{Error, Result};
+
+/// A Foundry combat document.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct Combat {
+    #[serde(default)]
+    pub round: u32,
+    /// The index of the combatant in turn, if the combat has started.
+    #[serde(default)]
+    pub turn: Option<usize>,
+    pub combatants: Vec<Combatant>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct Combatant {
+    pub name: String,
+    pub initiative: Option<i32>,
+    #[serde(default)]
+    pub defeated: bool,
+    pub actor: Actor,
+    #[serde(default, skip_serializing_if = "Flags::is_empty")]
+    pub flags: Flags,
+}
+
+/// Data Foundry doesn't have a place for, kept under the `pathtracker`
+/// scope.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct Flags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pathtracker: Option<PathtrackerFlags>,
+}
+
+impl Flags {
+    const fn is_empty(&self) -> bool {
+        self.pathtracker.is_none()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct PathtrackerFlags {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct Actor {
+    pub name: String,
+    /// `character` for player characters and `npc` for everyone else.
+    #[serde(rename = "type")]
+    pub actor_type: String,
+    pub system: ActorSystem,
+    #[serde(default)]
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct ActorSystem {
+    #[serde(default)]
+    pub attributes: Attributes,
+    #[serde(default)]
+    pub details: Details,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub perception: Option<Modifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saves: Option<ActorSaves>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct Attributes {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hp: Option<Hp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ac: Option<Value<i32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct Hp {
+    pub value: u32,
+    pub max: u32,
+    #[serde(default)]
+    pub temp: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct Details {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<Value<i32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct Value<T> {
+    pub value: T,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct Modifier {
+    #[serde(rename = "mod")]
+    pub modifier: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct ActorSaves {
+    pub fortitude: Value<i32>,
+    pub reflex: Value<i32>,
+    pub will: Value<i32>,
+}
+
+/// An item of an actor. Only items of the type `condition` are read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct Item {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub system: ItemSystem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct ItemSystem {
+    pub slug: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value<Option<u8>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub persistent: Option<Persistent>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Persistent {
+    pub formula: String,
+    pub damage_type: String,
+}
+
+const CONDITION_ITEM: &str = "condition";
+const PERSISTENT_DAMAGE: &str = "persistent-damage";
+
+const DAMAGE_TYPES: [(DamageType, &str); 16] = [
+    (DamageType::Bleed, "bleed"),
+    (DamageType::Poison, "poison"),
+    (DamageType::Piercing, "piercing"),
+    (DamageType::Bludgeoning, "bludgeoning"),
+    (DamageType::Slashing, "slashing"),
+    (DamageType::Acid, "acid"),
+    (DamageType::Cold, "cold"),
+    (DamageType::Electricity, "electricity"),
+    (DamageType::Sonic, "sonic"),
+    (DamageType::Positive, "vitality"),
+    (DamageType::Negative, "void"),
+    (DamageType::Force, "force"),
+    (DamageType::Chaotic, "chaotic"),
+    (DamageType::Evil, "evil"),
+    (DamageType::Good, "good"),
+    (DamageType::Lawful, "lawful"),
+];
+
+fn damage_type_id(damage: DamageType) -> &'static str {
+    DAMAGE_TYPES.iter().find(|(x, _)| *x == damage).map_or("untyped", |(_, id)| id)
+}
+
+fn damage_type_from_id(id: &str) -> Option<DamageType> {
+    match id {
+        "positive" => Some(DamageType::Positive),
+        "negative" => Some(DamageType::Negative),
+        id => DAMAGE_TYPES.iter().find(|(_, x)| *x == id).map(|(damage, _)| *damage),
+    }
+}
+
+/// The Pathfinder 2e status id of a valued condition.
+const fn valued_id(cond: ValuedCondition) -> &'static str {
+    match cond {
+        ValuedCondition::PersistentDamage(_) => PERSISTENT_DAMAGE,
+        ValuedCondition::Clumsy => "clumsy",
+        ValuedCondition::Doomed => "doomed",
+        ValuedCondition::Drained => "drained",
+        ValuedCondition::Dying => "dying",
+        ValuedCondition::Enfeebled => "enfeebled",
+        ValuedCondition::Frightened => "frightened",
+        ValuedCondition::Sickened => "sickened",
+        ValuedCondition::Slowed => "slowed",
+        ValuedCondition::Stunned => "stunned",
+        ValuedCondition::Stupified => "stupefied",
+        ValuedCondition::Wounded => "wounded",
+    }
+}
+
+/// The Pathfinder 2e status id of a non-valued condition.
+const fn non_valued_id(cond: NonValuedCondition) -> &'static str {
+    match cond {
+        NonValuedCondition::Blinded => "blinded",
+        NonValuedCondition::Broken => "broken",
+        NonValuedCondition::Concealed => "concealed",
+        NonValuedCondition::Confused => "confused",
+        NonValuedCondition::Controlled => "controlled",
+        NonValuedCondition::Dazzled => "dazzled",
+        NonValuedCondition::Deafened => "deafened",
+        NonValuedCondition::Encumbered => "encumbered",
+        NonValuedCondition::Fascinated => "fascinated",
+        NonValuedCondition::Fatigued => "fatigued",
+        NonValuedCondition::FlatFooted => "off-guard",
+        NonValuedCondition::Fleeing => "fleeing",
+        NonValuedCondition::Friendly => "friendly",
+        NonValuedCondition::Grabbed => "grabbed",
+        NonValuedCondition::Helpful => "helpful",
+        NonValuedCondition::Hidden => "hidden",
+        NonValuedCondition::Hostile => "hostile",
+        NonValuedCondition::Immobilized => "immobilized",
+        NonValuedCondition::Indifferent => "indifferent",
+        NonValuedCondition::Invisible => "invisible",
+        NonValuedCondition::Observed => "observed",
+        NonValuedCondition::Paralyzed => "paralyzed",
+        NonValuedCondition::Petrified => "petrified",
+        NonValuedCondition::Prone => "prone",
+        NonValuedCondition::Quickened => "quickened",
+        NonValuedCondition::Restrained => "restrained",
+        NonValuedCondition::Unconscious => "unconscious",
+        NonValuedCondition::Undetected => "undetected",
+        NonValuedCondition::Unfriendly => "unfriendly",
+        NonValuedCondition::Unnoticed => "unnoticed",
+    }
+}
+
+const VALUED: [ValuedCondition; 11] = [
+    ValuedCondition::Clumsy,
+    ValuedCondition::Doomed,
+    ValuedCondition::Drained,
+    ValuedCondition::Dying,
+    ValuedCondition::Enfeebled,
+    ValuedCondition::Frightened,
+    ValuedCondition::Sickened,
+    ValuedCondition::Slowed,
+    ValuedCondition::Stunned,
+    ValuedCondition::Stupified,
+    ValuedCondition::Wounded,
+];
+
+const NON_VALUED: [NonValuedCondition; 30] = [
+    NonValuedCondition::Blinded,
+    NonValuedCondition::Broken,
+    NonValuedCondition::Concealed,
+    NonValuedCondition::Confused,
+    NonValuedCondition::Controlled,
+    NonValuedCondition::Dazzled,
+    NonValuedCondition::Deafened,
+    NonValuedCondition::Encumbered,
+    NonValuedCondition::Fascinated,
+    NonValuedCondition::Fatigued,
+    NonValuedCondition::FlatFooted,
+    NonValuedCondition::Fleeing,
+    NonValuedCondition::Friendly,
+    NonValuedCondition::Grabbed,
+    NonValuedCondition::Helpful,
+    NonValuedCondition::Hidden,
+    NonValuedCondition::Hostile,
+    NonValuedCondition::Immobilized,
+    NonValuedCondition::Indifferent,
+    NonValuedCondition::Invisible,
+    NonValuedCondition::Observed,
+    NonValuedCondition::Paralyzed,
+    NonValuedCondition::Petrified,
+    NonValuedCondition::Prone,
+    NonValuedCondition::Quickened,
+    NonValuedCondition::Restrained,
+    NonValuedCondition::Unconscious,
+    NonValuedCondition::Undetected,
+    NonValuedCondition::Unfriendly,
+    NonValuedCondition::Unnoticed,
+];
+
+/// Capitalises the first letter of a status id for the name of the item.
+fn item_name(id: &str) -> String {
+    let mut chars = id.chars();
+    chars.next().map_or_else(String::new, |first| first.to_uppercase().chain(chars).collect::<String>().replace('-', " "))
+}
+
+fn condition_to_item(cond: &Condition) -> Item {
+    match cond {
+        Condition::Valued { cond: ValuedCondition::PersistentDamage(damage), level, .. } => Item {
+            name: item_name(PERSISTENT_DAMAGE),
+            item_type: CONDITION_ITEM.into(),
+            system: ItemSystem {
+                slug: PERSISTENT_DAMAGE.into(),
+                value: None,
+                persistent: Some(Persistent { formula: level.to_string(), damage_type: damage_type_id(*damage).into() }),
+            },
+        },
+        Condition::Valued { cond, level, .. } => Item {
+            name: item_name(valued_id(*cond)),
+            item_type: CONDITION_ITEM.into(),
+            system: ItemSystem { slug: valued_id(*cond).into(), value: Some(Value { value: Some(*level) }), persistent: None },
+        },
+        Condition::NonValued { cond, .. } => Item {
+            name: item_name(non_valued_id(*cond)),
+            item_type: CONDITION_ITEM.into(),
+            system: ItemSystem { slug: non_valued_id(*cond).into(), value: None, persistent: None },
+        },
+    }
+}
+
+/// The condition of [`item`], if it is a condition the tracker knows.
+fn item_to_condition(item: &Item) -> Option<Condition> {
+    if item.item_type != CONDITION_ITEM {
+        return None
+    }
+
+    let slug = item.system.slug.as_str();
+    let level = item.system.value.and_then(|value| value.value).unwrap_or(1);
+
+    if slug == PERSISTENT_DAMAGE {
+        let persistent = item.system.persistent.as_ref()?;
+        let damage = damage_type_from_id(&persistent.damage_type)?;
+        // Only flat amounts can be tracked, so dice count as their average,
+        // rounded down, like `2d6` as 7.
+        let level = persistent.formula.parse().ok()
+            .or_else(|| average_of(&persistent.formula))
+            .unwrap_or(1);
+        return Some(Condition::Valued { cond: ValuedCondition::PersistentDamage(damage), term: ValuedTerm::Manual, level })
+    }
+
+    let slug = if slug == "flat-footed" { "off-guard" } else { slug };
+    if let Some(cond) = VALUED.into_iter().find(|cond| valued_id(*cond) == slug) {
+        return Some(Condition::Valued { cond, term: ValuedTerm::Manual, level })
+    }
+
+    NON_VALUED.into_iter()
+        .find(|cond| non_valued_id(*cond) == slug)
+        .map(|cond| Condition::NonValued { cond, term: NonValuedTerm::Manual })
+}
+
+/// The average of a dice formula like `2d6`, rounded down, or `None` if it
+/// doesn't fit a condition value.
+fn average_of(formula: &str) -> Option<u8> {
+    let (count, sides) = formula.trim().split_once('d')?;
+    let count: u32 = if count.is_empty() { 1 } else { count.parse().ok()? };
+    let sides: u32 = sides.parse().ok()?;
+    u8::try_from(count.checked_mul(sides.checked_add(1)?)? / 2).ok()
+}
+
+fn chr_to_combatant(chr: &Chr, cm: &ConditionManager) -> Combatant {
+    let mut conditions: Vec<&Condition> = cm.get_conditions(&chr.name).into_iter().collect();
+    conditions.sort();
+
+    let stats = chr.stats.as_ref();
+    Combatant {
+        name: chr.name.to_string(),
+        initiative: Some(chr.init),
+        defeated: chr.is_down(),
+        actor: Actor {
+            name: chr.name.to_string(),
+            actor_type: if chr.player { "character" } else { "npc" }.into(),
+            system: ActorSystem {
+                attributes: Attributes {
+                    hp: chr.health.as_ref().map(|health| Hp { value: health.current, max: health.max, temp: health.temp }),
+                    ac: stats.map(|stats| Value { value: stats.ac }),
+                },
+                details: Details { level: chr.level.map(|level| Value { value: level }) },
+                perception: chr.init_mod.map(|modifier| Modifier { modifier }),
+                saves: stats.map(|stats| ActorSaves {
+                    fortitude: Value { value: stats.saves.fortitude },
+                    reflex: Value { value: stats.saves.reflex },
+                    will: Value { value: stats.saves.will },
+                }),
+            },
+            items: conditions.into_iter().map(condition_to_item).collect(),
+        },
+        flags: Flags {
+            pathtracker: chr.group.clone().map(|group| PathtrackerFlags { group: Some(group) }),
+        },
+    }
+}
+
+fn combatant_to_chr(combatant: &Combatant) -> Chr {
+    let system = &combatant.actor.system;
+    let builder = Chr::builder(combatant.name.clone(), combatant.initiative.unwrap_or(0), combatant.actor.actor_type == "character");
+    let builder = match system.attributes.hp {
+        Some(hp) => builder.with_health(Health { current: hp.value.min(hp.max), max: hp.max, temp: hp.temp, drained: 0 }),
+        None => builder,
+    };
+    let builder = match system.details.level {
+        Some(level) => builder.with_level(level.value),
+        None => builder,
+    };
+    let builder = match system.perception {
+        Some(perception) => builder.with_init_mod(perception.modifier),
+        None => builder,
+    };
+    let builder = match (system.attributes.ac, system.saves) {
+        (Some(ac), Some(saves)) => builder.with_stats(Stats {
+            ac: ac.value,
+            saves: Saves { fortitude: saves.fortitude.value, reflex: saves.reflex.value, will: saves.will.value },
+            perception: system.perception.map_or(0, |perception| perception.modifier),
+            resistances: Vec::new(),
+            weaknesses: Vec::new(),
+        }),
+        _ => builder,
+    };
+    let builder = match combatant.flags.pathtracker.as_ref().and_then(|flags| flags.group.clone()) {
+        Some(group) => builder.with_group(group),
+        None => builder,
+    };
+
+    builder.build()
+}
+
+/// Converts the characters, the character in turn and the conditions of
+/// [`data`] into a [`Combat`].
+#[must_use]
+pub(crate) fn to_combat(data: &TrackerData) -> Combat {
+    Combat {
+        round: u32::from(data.in_turn_index.is_some()),
+        turn: data.in_turn_index,
+        combatants: data.chrs.iter().map(|chr| chr_to_combatant(chr, &data.cm)).collect(),
+    }
+}
+
+/// Converts a [`Combat`] into tracker data with its combatants sorted by
+/// initiative, the combatant in turn and their conditions, with [`file`]
+/// naming the document in errors.
+///
+/// # Errors
+///
+/// This function will return an error if two combatants have the same name.
+pub(crate) fn from_combat(combat: &Combat, file: &str) -> Result<TrackerData> {
+    let mut chrs: Vec<Chr> = Vec::new();
+    let mut cm = ConditionManager::new();
+
+    for combatant in &combat.combatants {
+        let mut chr = combatant_to_chr(combatant);
+        if chrs.iter().any(|other| other.name == chr.name) {
+            return Err(Error::InvalidField { file: file.to_string(), reason: format!("there are several combatants named `{}`", chr.name) })
+        }
+
+        for cond in combatant.actor.items.iter().filter_map(item_to_condition) {
+            // Foundry has already lowered the max HP of drained characters.
+            if let Condition::Valued { cond: ValuedCondition::Drained, level, .. } = cond {
+                chr.mark_drained(level);
+            }
+            cm.add_condition(chr.name.clone(), cond);
+        }
+        chrs.push(chr);
+    }
+
+    let in_turn = combat.turn
+        .and_then(|turn| combat.combatants.get(turn))
+        .map(|combatant| ChrName::new(combatant.name.clone()));
+    chrs.sort();
+    let in_turn_index = in_turn.and_then(|name| chrs.iter().position(|chr| chr.name == name));
+
+    Ok(TrackerData::new(chrs, in_turn_index, cm))
+}
+
+/// Parses a Foundry combat document, with [`file`] naming it in errors.
+///
+/// # Errors
+///
+/// This function will return an error if [`json`] isn't a combat document.
+pub fn parse(json: &str, file: &str) -> Result<Combat> {
+    serde_json::from_str(json).map_err(|err| Error::InvalidJson(file.to_string(), err))
+}
+
+/// Loads the Foundry combat document at [`path`] as tracker data.
+///
+/// # Errors
+///
+/// This function will return an error if the file can't be read or isn't a
+/// valid combat document.
+pub(crate) fn load(path: impl AsRef<Path>) -> Result<TrackerData> {
+    let (file, json) = super::read(path.as_ref())?;
+    from_combat(&parse(&json, &file)?, &file)
+}
+
+/// Saves [`data`] as a Foundry combat document to the file at [`path`].
+///
+/// # Errors
+///
+/// This function will return an error if serialisation or writing the file
+/// fails.
+pub(crate) fn save(data: &TrackerData, path: impl AsRef<Path>) -> Result<()> {
+    let file = path.as_ref().display().to_string();
+    let json = serde_json::to_string_pretty(&to_combat(data)).map_err(|err| Error::WriteError(file.clone(), std::io::Error::other(err)))?;
+    std::fs::write(path, json).map_err(|err| Error::WriteError(file, err))
+}
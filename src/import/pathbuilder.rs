@@ -0,0 +1,159 @@
+//! Importing player characters from the JSON export of Pathbuilder 2e.
+
+use std::{collections::HashMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::{bestiary::{Resistance, Saves}, character::{Chr, Health, Stats}};
+
+use super::{Error, Result};
+
+#[derive(Debug, Deserialize)]
+struct Export {
+    success: bool,
+    build: Build,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Build {
+    name: String,
+    level: i32,
+    attributes: Attributes,
+    abilities: Abilities,
+    proficiencies: Proficiencies,
+    ac_total: AcTotal,
+    /// Bonuses by statistic and then by type, such as item bonuses.
+    #[serde(default)]
+    mods: HashMap<String, HashMap<String, i32>>,
+    #[serde(default)]
+    resistances: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attributes {
+    ancestryhp: u32,
+    classhp: u32,
+    #[serde(default)]
+    bonushp: u32,
+    #[serde(default)]
+    bonushp_per_level: u32,
+}
+
+/// The ability scores, like 18 for a +4 modifier.
+#[derive(Debug, Deserialize)]
+struct Abilities {
+    con: i32,
+    dex: i32,
+    wis: i32,
+}
+
+/// The proficiency ranks as the bonus they give, 0 for untrained up to 8
+/// for legendary.
+#[derive(Debug, Deserialize)]
+struct Proficiencies {
+    perception: i32,
+    fortitude: i32,
+    reflex: i32,
+    will: i32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AcTotal {
+    ac_total: i32,
+}
+
+const fn ability_mod(score: i32) -> i32 {
+    (score - 10).div_euclid(2)
+}
+
+impl Build {
+    /// The proficiency bonus of [`rank`], which includes the level when
+    /// trained or better.
+    const fn proficiency(&self, rank: i32) -> i32 {
+        if rank > 0 { rank + self.level } else { 0 }
+    }
+
+    fn bonus(&self, stat: &str) -> i32 {
+        self.mods.get(stat).map_or(0, |bonuses| bonuses.values().sum())
+    }
+
+    /// The max HP of the character, or [`None`] if it is too large.
+    fn max_hp(&self) -> Option<u32> {
+        let level = self.level.max(1).unsigned_abs();
+        let per_level = (i64::from(self.attributes.classhp)
+            + i64::from(ability_mod(self.abilities.con))
+            + i64::from(self.attributes.bonushp_per_level))
+            .max(0);
+        let per_level = u32::try_from(per_level).ok()?;
+
+        self.attributes.ancestryhp
+            .checked_add(self.attributes.bonushp)?
+            .checked_add(level.checked_mul(per_level)?)
+    }
+
+    fn stats(&self, file: &str) -> Result<Stats> {
+        let resistances = self.resistances.iter()
+            .map(|resistance| resistance.parse::<Resistance>())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|reason| Error::InvalidField { file: file.to_string(), reason })?;
+
+        Ok(Stats {
+            ac: self.ac_total.ac_total,
+            saves: Saves {
+                fortitude: ability_mod(self.abilities.con) + self.proficiency(self.proficiencies.fortitude) + self.bonus("Fortitude"),
+                reflex: ability_mod(self.abilities.dex) + self.proficiency(self.proficiencies.reflex) + self.bonus("Reflex"),
+                will: ability_mod(self.abilities.wis) + self.proficiency(self.proficiencies.will) + self.bonus("Will"),
+            },
+            perception: ability_mod(self.abilities.wis) + self.proficiency(self.proficiencies.perception) + self.bonus("Perception"),
+            resistances,
+            weaknesses: Vec::new(),
+        })
+    }
+}
+
+/// Parses the JSON of a Pathbuilder export into a player character, with
+/// [`file`] naming the export in errors.
+///
+/// The character gets their name, level, max HP, AC, saves and Perception,
+/// which is also their initiative modifier. Their initiative is 0.
+///
+/// # Errors
+///
+/// This function will return an error if
+/// - [`json`] isn't a Pathbuilder export
+/// - The export wasn't successful
+/// - A resistance isn't given like `fire 5`
+/// - The max HP is too large.
+pub fn parse(json: &str, file: &str) -> Result<Chr> {
+    let export: Export = serde_json::from_str(json).map_err(|err| Error::InvalidJson(file.to_string(), err))?;
+    if !export.success {
+        return Err(Error::UnsuccessfulExport(file.to_string()))
+    }
+
+    let build = export.build;
+    let stats = build.stats(file)?;
+    let max_hp = build.max_hp()
+        .ok_or_else(|| Error::InvalidField { file: file.to_string(), reason: "the max HP is too large".into() })?;
+
+    Ok(Chr::builder(build.name.clone(), 0, true)
+        .with_health(Health::new(max_hp))
+        .with_level(build.level)
+        .with_init_mod(stats.perception)
+        .with_stats(stats)
+        .build())
+}
+
+/// Loads a player character from the Pathbuilder export at [`path`], as
+/// with [`parse`].
+///
+/// # Errors
+///
+/// This function will return an error if the file can't be read or
+/// [`parse`] fails.
+pub fn load(path: impl AsRef<Path>) -> Result<Chr> {
+    let (file, json) = super::read(path.as_ref())?;
+    parse(&json, &file)
+}
@@ -1,4 +1,3 @@
-#![feature(iter_intersperse)]
 #![feature(coverage_attribute)]
 #![feature(proc_macro)]
 
@@ -9,3 +8,9 @@ pub mod character;
 pub mod saver;
 pub mod settings;
 pub mod duration;
+pub mod modifiers;
+pub mod dice;
+pub mod bestiary;
+pub mod encounter;
+pub mod import;
+pub mod roster;
@@ -1,19 +1,244 @@
-use pathtracker_rust::character::Chr;
+use std::io::{self, IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Args, Parser, ValueEnum};
+use pathtracker_rust::bestiary::{self, Bestiary};
+use pathtracker_rust::gui::{order::Order, protocol};
+use pathtracker_rust::roster::Roster;
 use pathtracker_rust::saver::{self, FileSaver};
 use pathtracker_rust::{gui, tracker};
 use pathtracker_rust::tracker::Tracker;
-use pathtracker_rust::gui::terminalgui;
+use pathtracker_rust::gui::terminalgui::{self, script};
+
+/// A combat tracker for Pathfinder 2e.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(flatten)]
+    ui: UiArgs,
+
+    /// The save in the saves directory to load and auto save to.
+    #[arg(short, long, default_value = tracker::AUTO_SAVE)]
+    save: String,
+
+    /// The directory with the saves, bestiary and party roster, instead of the
+    /// current directory.
+    #[arg(short = 'C', long)]
+    dir: Option<PathBuf>,
+
+    /// Starts with an empty tracker instead of loading the save.
+    #[arg(long)]
+    fresh: bool,
+
+    /// Runs a script of terminal commands, one per line, or `-` for stdin.
+    /// The script is also read from stdin when it isn't a terminal, unless
+    /// an interface is chosen.
+    script: Option<PathBuf>,
+
+    /// Keeps running the script past the lines with errors.
+    #[arg(long, requires = "script")]
+    keep_going: bool,
+
+    /// Prints the initiative order and exits, after running the script if any.
+    #[arg(long, value_enum)]
+    print: Option<PrintFormat>,
+}
+
+/// The interface to run, which is the window interface unless another is
+/// chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Args)]
+#[group(id = "ui", multiple = false, conflicts_with_all = ["print", "script"])]
+struct UiArgs {
+    /// Runs the full-screen terminal interface.
+    #[arg(long)]
+    tui: bool,
+
+    /// Runs the window interface. This is the default.
+    #[arg(long)]
+    gui: bool,
+
+    /// Answers JSON requests on stdin, one per line, for other programs to
+    /// drive the tracker.
+    #[arg(long)]
+    json: bool,
+}
+
+impl UiArgs {
+    const fn is_chosen(self) -> bool {
+        self.tui || self.gui || self.json
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum PrintFormat {
+    Text,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Some(dir) = &cli.dir {
+        if let Err(e) = std::env::set_current_dir(dir) {
+            eprintln!("couldn't use directory `{}`: {e}", dir.display());
+            return ExitCode::FAILURE
+        }
+    }
+
+    let Some(mut t) = load(&cli) else { return ExitCode::FAILURE };
+
+    let script = match &cli.script {
+        Some(file) if file == Path::new("-") => Some(read_stdin()),
+        Some(file) => Some(std::fs::read_to_string(file).map_err(|e| format!("couldn't read script `{}`: {e}", file.display()))),
+        // Starting from a launcher can leave stdin empty rather than a
+        // terminal, which shouldn't keep the interface from starting.
+        None if !cli.ui.is_chosen() && !io::stdin().is_terminal() => read_stdin()
+            .map(|script| (!script.trim().is_empty()).then_some(script))
+            .transpose(),
+        None => None,
+    };
 
+    match script {
+        Some(Ok(script)) => return run_script(t, &script, cli.keep_going, cli.print.unwrap_or(PrintFormat::Text)),
+        Some(Err(e)) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE
+        },
+        None => (),
+    }
 
-fn main() {
-    let mut t: Tracker<FileSaver> = match Tracker::load(&FileSaver, "auto.save") {
+    if let Some(format) = cli.print {
+        return print_order(&t, format)
+    }
+
+    let res = if cli.ui.tui {
+        terminalgui::run(t).map_err(|e| e.to_string())
+    } else if cli.ui.json {
+        protocol::run(&mut t, io::stdin().lock(), io::stdout().lock()).map_err(|e| e.to_string())
+    } else {
+        gui::windowgui::run(t).map_err(|e| e.to_string())
+    };
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        },
+    }
+}
+
+/// Loads the tracker from the save, or starts a new one if there is no save
+/// or [`Cli::fresh`] is set, along with the bestiary and party roster.
+fn load(cli: &Cli) -> Option<Tracker<FileSaver>> {
+    let loaded = if cli.fresh { Ok(Tracker::default()) } else { Tracker::load(&FileSaver, cli.save.as_str()) };
+    let mut t: Tracker<FileSaver> = match loaded {
         Ok(t) => t,
-        Err(tracker::Error::LoadError(saver::Error::LoadIOError(_, _))) => Tracker::default(),
-        Err(tracker::Error::LoadError(saver::Error::LoadMissingSave(_))) => Tracker::default(),
-        Err(e) => panic!("{e:?}")
+        Err(tracker::Error::LoadError(saver::Error::LoadIOError(_, _) | saver::Error::LoadMissingSave(_))) => Tracker::default(),
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("Start with --fresh to start over, or pick another save with --save.");
+            return None
+        },
     };
+    t.set_save_file(cli.save.as_str());
+
+    match Bestiary::load(bestiary::BESTIARY_DIR) {
+        Ok((bestiary, skipped)) => {
+            for e in skipped {
+                eprintln!("{e}");
+            }
+            t.set_bestiary(bestiary);
+        },
+        Err(e) => eprintln!("{e}"),
+    }
+
+    match Roster::load(&FileSaver) {
+        Ok(roster) => t.set_roster(roster),
+        Err(saver::Error::LoadIOError(_, _) | saver::Error::LoadMissingSave(_)) => (),
+        Err(e) => eprintln!("{e}"),
+    }
+
+    Some(t)
+}
+
+fn read_stdin() -> Result<String, String> {
+    let mut script = String::new();
+    io::stdin().read_to_string(&mut script).map_err(|e| format!("couldn't read script from stdin: {e}"))?;
+    Ok(script)
+}
+
+/// Runs a script of terminal commands and saves the tracker, printing the
+/// resulting initiative order. The messages of the commands go to stderr
+/// with the errors, so only the order is printed to stdout.
+fn run_script(mut t: Tracker<FileSaver>, script: &str, keep_going: bool, format: PrintFormat) -> ExitCode {
+    let outcome = script::run(&mut t, script, keep_going);
+    for msg in &outcome.messages {
+        eprintln!("{msg}");
+    }
+    for err in &outcome.errors {
+        eprintln!("{err}");
+    }
+
+    if let Err(e) = t.auto_save() {
+        eprintln!("{e}");
+        return ExitCode::FAILURE
+    }
+
+    match print_order(&t, format) {
+        _ if !outcome.is_ok() => ExitCode::FAILURE,
+        code => code,
+    }
+}
+
+fn print_order(t: &Tracker<FileSaver>, format: PrintFormat) -> ExitCode {
+    let order = Order::new(t);
+    match format {
+        PrintFormat::Text => println!("{order}"),
+        PrintFormat::Json => match serde_json::to_string_pretty(&order) {
+            Ok(json) => println!("{json}"),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE
+            },
+        },
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, Parser};
+
+    use super::Cli;
+
+    #[test]
+    fn cli_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn ui_is_chosen_by_flag() {
+        let cli = Cli::try_parse_from(["pathtracker", "--tui"]);
+        assert!(cli.is_ok_and(|cli| cli.ui.tui && !cli.ui.gui));
+    }
+
+    #[test]
+    fn stdin_is_left_alone_once_a_ui_is_chosen() {
+        assert!(Cli::try_parse_from(["pathtracker", "--json"]).is_ok_and(|cli| cli.ui.is_chosen()));
+        assert!(Cli::try_parse_from(["pathtracker"]).is_ok_and(|cli| !cli.ui.is_chosen()));
+    }
 
-    //terminalgui::run(t).expect("Tracker Error");
+    #[test]
+    fn only_one_ui_can_be_chosen() {
+        assert!(Cli::try_parse_from(["pathtracker", "--tui", "--gui"]).is_err());
+        assert!(Cli::try_parse_from(["pathtracker", "--gui", "--json"]).is_err());
+    }
 
-    gui::windowgui::run(t);
+    #[test]
+    fn ui_conflicts_with_printing_the_order() {
+        assert!(Cli::try_parse_from(["pathtracker", "--json", "--print", "text"]).is_err());
+        assert!(Cli::try_parse_from(["pathtracker", "--tui", "encounter.txt"]).is_err());
+    }
 }
@@ -0,0 +1,346 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::conditions::{Condition, NonValuedCondition, ValuedCondition};
+
+/// The penalty off-guard gives to AC.
+const OFF_GUARD: u8 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Hash)]
+pub enum Attribute {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Attribute {
+    pub const ALL: [Self; 6] = [
+        Self::Strength,
+        Self::Dexterity,
+        Self::Constitution,
+        Self::Intelligence,
+        Self::Wisdom,
+        Self::Charisma,
+    ];
+
+    #[must_use]
+    pub const fn is_mental(self) -> bool {
+        matches!(self, Self::Intelligence | Self::Wisdom | Self::Charisma)
+    }
+}
+
+impl Display for Attribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Strength => write!(f, "Str"),
+            Self::Dexterity => write!(f, "Dex"),
+            Self::Constitution => write!(f, "Con"),
+            Self::Intelligence => write!(f, "Int"),
+            Self::Wisdom => write!(f, "Wis"),
+            Self::Charisma => write!(f, "Cha"),
+        }
+    }
+}
+
+/// A number on a character that conditions may modify.
+///
+/// Attack rolls and skill checks are distinguished by the attribute they are
+/// based on, as some conditions only affect checks of a given attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Hash)]
+pub enum Statistic {
+    ArmorClass,
+    Fortitude,
+    Reflex,
+    Will,
+    Perception,
+    Attack(Attribute),
+    Skill(Attribute),
+}
+
+impl Statistic {
+    /// Returns the attribute the statistic is based on.
+    #[must_use]
+    pub const fn attribute(self) -> Attribute {
+        match self {
+            Self::ArmorClass | Self::Reflex => Attribute::Dexterity,
+            Self::Fortitude => Attribute::Constitution,
+            Self::Will | Self::Perception => Attribute::Wisdom,
+            Self::Attack(attr) | Self::Skill(attr) => attr,
+        }
+    }
+
+    #[must_use]
+    pub const fn is_save(self) -> bool {
+        matches!(self, Self::Fortitude | Self::Reflex | Self::Will)
+    }
+
+    /// Returns every statistic, attack rolls and skill checks for each attribute.
+    pub fn all() -> impl Iterator<Item = Self> {
+        [Self::ArmorClass, Self::Fortitude, Self::Reflex, Self::Will, Self::Perception].into_iter()
+            .chain(Attribute::ALL.into_iter().map(Self::Attack))
+            .chain(Attribute::ALL.into_iter().map(Self::Skill))
+    }
+}
+
+impl Display for Statistic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ArmorClass => write!(f, "AC"),
+            Self::Fortitude => write!(f, "Fort"),
+            Self::Reflex => write!(f, "Ref"),
+            Self::Will => write!(f, "Will"),
+            Self::Perception => write!(f, "Perc"),
+            Self::Attack(attr) => write!(f, "{attr} attack"),
+            Self::Skill(attr) => write!(f, "{attr} skills"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Hash)]
+pub enum PenaltyType {
+    Status,
+    Circumstance,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Hash)]
+pub struct Penalty {
+    pub ty: PenaltyType,
+    pub value: u8,
+}
+
+impl Penalty {
+    #[must_use]
+    pub const fn status(value: u8) -> Self {
+        Self { ty: PenaltyType::Status, value }
+    }
+
+    #[must_use]
+    pub const fn circumstance(value: u8) -> Self {
+        Self { ty: PenaltyType::Circumstance, value }
+    }
+}
+
+/// Returns the penalties the given condition gives to the given statistic.
+#[must_use]
+pub fn penalties(cond: &Condition, stat: Statistic) -> Vec<Penalty> {
+    match *cond {
+        Condition::Valued { cond, level, .. } => valued_penalty(cond, level, stat).into_iter().collect(),
+        Condition::NonValued { cond, .. } => nonvalued_penalties(cond, stat),
+    }
+}
+
+fn valued_penalty(cond: ValuedCondition, level: u8, stat: Statistic) -> Option<Penalty> {
+    let applies = match cond {
+        ValuedCondition::Frightened |
+        ValuedCondition::Sickened => true,
+        ValuedCondition::Clumsy => stat.attribute() == Attribute::Dexterity,
+        ValuedCondition::Enfeebled => stat.attribute() == Attribute::Strength,
+        ValuedCondition::Drained => stat.attribute() == Attribute::Constitution,
+        ValuedCondition::Stupified => stat.attribute().is_mental(),
+        _ => false,
+    };
+
+    applies.then_some(Penalty::status(level))
+}
+
+fn nonvalued_penalties(cond: NonValuedCondition, stat: Statistic) -> Vec<Penalty> {
+    let off_guard = stat == Statistic::ArmorClass;
+    match cond {
+        NonValuedCondition::FlatFooted |
+        NonValuedCondition::Grabbed |
+        NonValuedCondition::Paralyzed |
+        NonValuedCondition::Restrained if off_guard => vec![Penalty::circumstance(OFF_GUARD)],
+        NonValuedCondition::Prone if off_guard || matches!(stat, Statistic::Attack(_)) => vec![Penalty::circumstance(OFF_GUARD)],
+        NonValuedCondition::Fatigued if stat == Statistic::ArmorClass || stat.is_save() => vec![Penalty::status(1)],
+        NonValuedCondition::Unconscious => match stat {
+            Statistic::ArmorClass => vec![Penalty::status(4), Penalty::circumstance(OFF_GUARD)],
+            Statistic::Perception | Statistic::Reflex => vec![Penalty::status(4)],
+            _ => vec![],
+        },
+        _ => vec![],
+    }
+}
+
+/// Returns the total modifier the given conditions give to a statistic.
+///
+/// Penalties of the same type don't stack, so only the worst status penalty
+/// and the worst circumstance penalty apply.
+pub fn modifier<'a>(conds: impl IntoIterator<Item = &'a Condition>, stat: Statistic) -> i32 {
+    let (status, circumstance) = conds.into_iter()
+        .flat_map(|cond| penalties(cond, stat))
+        .fold((0, 0), |(status, circumstance), penalty| match penalty.ty {
+            PenaltyType::Status => (status.max(penalty.value), circumstance),
+            PenaltyType::Circumstance => (status, circumstance.max(penalty.value)),
+        });
+
+    -(i32::from(status) + i32::from(circumstance))
+}
+
+/// The modifiers conditions give to each statistic of a character.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Modifiers(BTreeMap<Statistic, i32>);
+
+impl Modifiers {
+    pub fn from_conditions<'a>(conds: impl IntoIterator<Item = &'a Condition>) -> Self {
+        let conds: Vec<&Condition> = conds.into_iter().collect();
+        let map = Statistic::all()
+            .map(|stat| (stat, modifier(conds.iter().copied(), stat)))
+            .filter(|(_, m)| *m != 0)
+            .collect();
+
+        Self(map)
+    }
+
+    /// Returns the modifier to the given statistic.
+    #[must_use]
+    pub fn get(&self, stat: Statistic) -> i32 {
+        self.0.get(&stat).copied().unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Writes the nonzero modifiers, collapsing attack rolls or skill checks into
+/// a single entry when every attribute has the same modifier.
+impl Display for Modifiers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut entries: Vec<String> = [Statistic::ArmorClass, Statistic::Fortitude, Statistic::Reflex, Statistic::Will, Statistic::Perception]
+            .into_iter()
+            .filter(|stat| self.get(*stat) != 0)
+            .map(|stat| format!("{stat} {}", self.get(stat)))
+            .collect();
+
+        for (name, stat) in [("attacks", Statistic::Attack as fn(Attribute) -> Statistic), ("skills", Statistic::Skill)] {
+            let mods: Vec<(Attribute, i32)> = Attribute::ALL.into_iter().map(|attr| (attr, self.get(stat(attr)))).collect();
+            if mods.iter().all(|(_, m)| *m == mods[0].1) {
+                if mods[0].1 != 0 {
+                    entries.push(format!("{name} {}", mods[0].1));
+                }
+            } else {
+                entries.extend(mods.into_iter()
+                    .filter(|(_, m)| *m != 0)
+                    .map(|(attr, m)| format!("{} {m}", stat(attr))));
+            }
+        }
+
+        write!(f, "{}", entries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::conditions::{Condition, NonValuedCondition, ValuedCondition};
+
+    use super::{modifier, Attribute, Modifiers, Statistic};
+
+    fn valued(cond: ValuedCondition, level: u8) -> Condition {
+        Condition::builder().condition(cond).value(level).build()
+    }
+
+    fn nonvalued(cond: NonValuedCondition) -> Condition {
+        Condition::builder().condition(cond).build()
+    }
+
+    #[test]
+    fn frightened_2_gives_minus_2_to_everything() {
+        let conds = [valued(ValuedCondition::Frightened, 2)];
+        for stat in Statistic::all() {
+            assert_eq!(-2, modifier(&conds, stat));
+        }
+    }
+
+    #[test]
+    fn frightened_and_sickened_dont_stack() {
+        let conds = [valued(ValuedCondition::Frightened, 2), valued(ValuedCondition::Sickened, 3)];
+        assert_eq!(-3, modifier(&conds, Statistic::Will));
+    }
+
+    #[test]
+    fn clumsy_and_frightened_only_worst_applies_to_ac() {
+        let conds = [valued(ValuedCondition::Clumsy, 1), valued(ValuedCondition::Frightened, 2)];
+        assert_eq!(-2, modifier(&conds, Statistic::ArmorClass));
+    }
+
+    #[test]
+    fn off_guard_stacks_with_status_penalty_on_ac() {
+        let conds = [valued(ValuedCondition::Frightened, 1), nonvalued(NonValuedCondition::FlatFooted)];
+        assert_eq!(-3, modifier(&conds, Statistic::ArmorClass));
+    }
+
+    #[test]
+    fn off_guard_from_several_sources_doesnt_stack() {
+        let conds = [nonvalued(NonValuedCondition::FlatFooted), nonvalued(NonValuedCondition::Prone), nonvalued(NonValuedCondition::Grabbed)];
+        assert_eq!(-2, modifier(&conds, Statistic::ArmorClass));
+    }
+
+    #[test]
+    fn clumsy_doesnt_affect_will() {
+        let conds = [valued(ValuedCondition::Clumsy, 3)];
+        assert_eq!(0, modifier(&conds, Statistic::Will));
+        assert_eq!(-3, modifier(&conds, Statistic::Reflex));
+        assert_eq!(-3, modifier(&conds, Statistic::Attack(Attribute::Dexterity)));
+    }
+
+    #[test]
+    fn enfeebled_only_affects_strength() {
+        let conds = [valued(ValuedCondition::Enfeebled, 2)];
+        assert_eq!(-2, modifier(&conds, Statistic::Attack(Attribute::Strength)));
+        assert_eq!(0, modifier(&conds, Statistic::Attack(Attribute::Dexterity)));
+        assert_eq!(0, modifier(&conds, Statistic::ArmorClass));
+    }
+
+    #[test]
+    fn drained_affects_fortitude() {
+        let conds = [valued(ValuedCondition::Drained, 1)];
+        assert_eq!(-1, modifier(&conds, Statistic::Fortitude));
+        assert_eq!(0, modifier(&conds, Statistic::Reflex));
+    }
+
+    #[test]
+    fn stupified_affects_will_and_perception() {
+        let conds = [valued(ValuedCondition::Stupified, 2)];
+        assert_eq!(-2, modifier(&conds, Statistic::Will));
+        assert_eq!(-2, modifier(&conds, Statistic::Perception));
+        assert_eq!(-2, modifier(&conds, Statistic::Skill(Attribute::Charisma)));
+        assert_eq!(0, modifier(&conds, Statistic::Fortitude));
+    }
+
+    #[test]
+    fn unconscious_stacks_status_and_off_guard_on_ac() {
+        let conds = [nonvalued(NonValuedCondition::Unconscious)];
+        assert_eq!(-6, modifier(&conds, Statistic::ArmorClass));
+        assert_eq!(-4, modifier(&conds, Statistic::Reflex));
+    }
+
+    #[test]
+    fn no_conditions_give_no_modifiers() {
+        let modifiers = Modifiers::from_conditions(&[]);
+        assert!(modifiers.is_empty());
+        assert_eq!("", modifiers.to_string());
+    }
+
+    #[test]
+    fn frightened_summary_collapses_attacks_and_skills() {
+        let conds = [valued(ValuedCondition::Frightened, 1)];
+        let modifiers = Modifiers::from_conditions(&conds);
+        assert_eq!("AC -1, Fort -1, Ref -1, Will -1, Perc -1, attacks -1, skills -1", modifiers.to_string());
+    }
+
+    #[test]
+    fn clumsy_summary_lists_dexterity_checks() {
+        let conds = [valued(ValuedCondition::Clumsy, 2)];
+        let modifiers = Modifiers::from_conditions(&conds);
+        assert_eq!("AC -2, Ref -2, Dex attack -2, Dex skills -2", modifiers.to_string());
+    }
+}
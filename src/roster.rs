@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{character::{Chr, ChrName}, saver::{self, Saver}};
+
+/// The file the party roster is saved to, next to `saves/` so that it is kept
+/// apart from the saves of encounters.
+pub const ROSTER_FILE: &str = "party.roster";
+
+/// The player characters of the party, kept from one encounter to the next
+/// with their current health.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize)]
+pub struct Roster {
+    chrs: Vec<Chr>,
+}
+
+impl Roster {
+    /// Creates a roster of the player characters among [`chrs`].
+    pub fn new(chrs: impl IntoIterator<Item = Chr>) -> Self {
+        let mut chrs: Vec<Chr> = chrs.into_iter().filter(|chr| chr.player).collect();
+        chrs.sort_by(|a, b| a.name.cmp(&b.name));
+        Self { chrs }
+    }
+
+    #[must_use]
+    pub fn get_chrs(&self) -> &[Chr] {
+        &self.chrs
+    }
+
+    #[must_use]
+    pub fn get_chr(&self, name: &ChrName) -> Option<&Chr> {
+        self.chrs.iter().find(|chr| chr.name == *name)
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.chrs.is_empty()
+    }
+
+    /// Saves the roster to [`ROSTER_FILE`] using [`saver`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if [`saver.save`] fails.
+    pub fn save(&self, saver: &impl Saver) -> saver::Result<()> {
+        saver.save(self, ROSTER_FILE)
+    }
+
+    /// Loads the roster from [`ROSTER_FILE`] using [`saver`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if [`saver.load`] fails.
+    pub fn load(saver: &impl Saver) -> saver::Result<Self> {
+        saver.load(ROSTER_FILE)
+    }
+}
@@ -12,7 +12,7 @@ pub enum Error {
     LoadIOError(String, #[source] io::Error),
     #[error("couldn't save savefile at `{0}` due to I/O error `{1}`")]
     InvalidDirPath(String, #[source] io::Error),
-    #[error("couldn't load savefile at `{0}` due to invalid directory path `{1}`")]
+    #[error("couldn't load savefile at `{0}`, because it is corrupt: `{1}`")]
     LoadCorruptSave(String, #[source] serde_json::Error),
     #[error("couldn't save savefile at `{0}` due to serialisation error `{1}`")]
     SerialisationError(String, serde_json::Error),
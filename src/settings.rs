@@ -8,16 +8,115 @@ pub enum Pf2eVersion {
     Remastered,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 #[derive(Serialize, Deserialize, Hash)]
 pub struct Settings {
     pf2e_version: Pf2eVersion,
     undo_size: usize,
+    #[serde(default)]
+    key_bindings: KeyBindings,
 }
 
 impl Default for Settings {
     fn default() -> Self {
-        Self { pf2e_version: Pf2eVersion::default(), undo_size: 64 }
+        Self { pf2e_version: Pf2eVersion::default(), undo_size: 64, key_bindings: KeyBindings::default() }
+    }
+}
+
+/// The actions of the window interface that can be bound to keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    EndTurn,
+    Undo,
+    Redo,
+    Add,
+    Damage,
+    Heal,
+    Conditions,
+    CommandPalette,
+}
+
+impl Action {
+    pub const ALL: [Self; 8] = [
+        Self::EndTurn, Self::Undo, Self::Redo, Self::Add, Self::Damage, Self::Heal, Self::Conditions, Self::CommandPalette,
+    ];
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::EndTurn => "End turn",
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+            Self::Add => "Add character",
+            Self::Damage => "Damage",
+            Self::Heal => "Heal",
+            Self::Conditions => "Conditions",
+            Self::CommandPalette => "Command palette",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The keys bound to each [`Action`], written like `Ctrl+Z` or `Space`. An
+/// action can have several keys, or none to leave it unbound.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, Hash)]
+#[serde(default)]
+pub struct KeyBindings {
+    end_turn: Vec<String>,
+    undo: Vec<String>,
+    redo: Vec<String>,
+    add: Vec<String>,
+    damage: Vec<String>,
+    heal: Vec<String>,
+    conditions: Vec<String>,
+    command_palette: Vec<String>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(ToString::to_string).collect();
+        Self {
+            end_turn: keys(&["Space", "N"]),
+            undo: keys(&["Ctrl+Z"]),
+            redo: keys(&["Ctrl+Y"]),
+            add: keys(&["A"]),
+            damage: keys(&["D"]),
+            heal: keys(&["H"]),
+            conditions: keys(&["C"]),
+            command_palette: keys(&["Ctrl+K"]),
+        }
+    }
+}
+
+impl KeyBindings {
+    #[must_use]
+    pub fn get(&self, action: Action) -> &[String] {
+        match action {
+            Action::EndTurn => &self.end_turn,
+            Action::Undo => &self.undo,
+            Action::Redo => &self.redo,
+            Action::Add => &self.add,
+            Action::Damage => &self.damage,
+            Action::Heal => &self.heal,
+            Action::Conditions => &self.conditions,
+            Action::CommandPalette => &self.command_palette,
+        }
+    }
+
+    pub fn set(&mut self, action: Action, keys: Vec<String>) {
+        let bound = match action {
+            Action::EndTurn => &mut self.end_turn,
+            Action::Undo => &mut self.undo,
+            Action::Redo => &mut self.redo,
+            Action::Add => &mut self.add,
+            Action::Damage => &mut self.damage,
+            Action::Heal => &mut self.heal,
+            Action::Conditions => &mut self.conditions,
+            Action::CommandPalette => &mut self.command_palette,
+        };
+        *bound = keys;
     }
 }
 
@@ -37,4 +136,33 @@ impl Settings {
     pub fn set_pf2e_version(&mut self, value: Pf2eVersion) {
         self.pf2e_version = value
     }
+
+    #[must_use]
+    pub const fn get_key_bindings(&self) -> &KeyBindings {
+        &self.key_bindings
+    }
+
+    pub fn set_key_bindings(&mut self, value: KeyBindings) {
+        self.key_bindings = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+    use super::{Action, Settings};
+
+    #[test]
+    fn settings_saved_without_key_bindings_get_the_default_ones() {
+        let settings: Settings = serde_json::from_str(r#"{"pf2e_version":"Old","undo_size":12}"#).unwrap();
+        assert_eq!(12, settings.get_undo_size());
+        assert_eq!(["Space".to_string(), "N".to_string()], settings.get_key_bindings().get(Action::EndTurn));
+    }
+
+    #[test]
+    fn key_bindings_missing_an_action_get_its_default_keys() {
+        let settings: Settings = serde_json::from_str(r#"{"pf2e_version":"Old","undo_size":12,"key_bindings":{"undo":["Ctrl+U"]}}"#).unwrap();
+        assert_eq!(["Ctrl+U".to_string()], settings.get_key_bindings().get(Action::Undo));
+        assert_eq!(["Ctrl+K".to_string()], settings.get_key_bindings().get(Action::CommandPalette));
+    }
 }
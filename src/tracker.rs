@@ -1,9 +1,9 @@
-use std::{cmp::Ordering, collections::{HashSet, VecDeque}};
+use std::{collections::{HashSet, VecDeque}, ops::Range};
 use serde::{Deserialize, Serialize};
 
 use thiserror::Error;
 
-use crate::{character::{Chr, ChrName, Health}, conditions::{Condition, condition_manager::ConditionManager}, saver::{self, Saver}, settings::{Pf2eVersion, Settings}};
+use crate::{bestiary::{Bestiary, Variant}, character::{Chr, ChrName, Health}, conditions::{Condition, ValuedCondition, condition_manager::ConditionManager}, dice::{DiceExpr, Roll, Roller}, duration::Duration, encounter::{Encounter, Party, Rating}, import::{self, foundry, pathbuilder}, modifiers::{self, Modifiers, Statistic}, roster::Roster, saver::{self, Saver}, settings::{KeyBindings, Pf2eVersion, Settings}};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -27,6 +27,18 @@ pub enum Error {
 
     #[error("nothing to redo")]
     RedoNothingError,
+
+    #[error("cannot add {0} characters at once, the most is {MAX_COUNT}.")]
+    TooManyError(u32),
+
+    #[error("cannot add group `{0}` without any members.")]
+    EmptyGroupError(ChrName),
+
+    #[error("cannot add from template `{0}` as there is no such template in the bestiary.")]
+    NoTemplateError(String),
+
+    #[error("import error: `{0}`")]
+    ImportError(#[from] import::Error),
 }
 
 impl PartialEq for Error {
@@ -34,11 +46,15 @@ impl PartialEq for Error {
         match (self, other) {
             (Self::AddDupError(x), Self::AddDupError(y)) |
             (Self::RmNoneError(x), Self::RmNoneError(y)) |
-            (Self::ChangeNoneError(x), Self::ChangeNoneError(y)) => x == y,
+            (Self::ChangeNoneError(x), Self::ChangeNoneError(y)) |
+            (Self::EmptyGroupError(x), Self::EmptyGroupError(y)) => x == y,
+            (Self::TooManyError(x), Self::TooManyError(y)) => x == y,
             (Self::RenameDupError { old: old1, new: new1 },
                 Self::RenameDupError { old: old2, new: new2 }) =>
                     old1 == old2 && new1 == new2,
+            (Self::NoTemplateError(x), Self::NoTemplateError(y)) => x == y,
             (Self::LoadError(_), Self::LoadError(_)) |
+            (Self::ImportError(_), Self::ImportError(_)) |
             (Self::UndoNothingError, Self::UndoNothingError) |
             (Self::RedoNothingError, Self::RedoNothingError) => true,
             _ => false
@@ -48,6 +64,20 @@ impl PartialEq for Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The save a tracker is auto saved to unless told otherwise.
+pub const AUTO_SAVE: &str = "auto.save";
+
+/// The most characters that can be added at once.
+pub const MAX_COUNT: u32 = 100;
+
+/// Checks that [`count`] characters can be added at once.
+const fn check_count(count: u32) -> Result<()> {
+    if count > MAX_COUNT {
+        return Err(Error::TooManyError(count))
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tracker<S: Saver> {
     chrs: Vec<Chr>,
@@ -57,17 +87,25 @@ pub struct Tracker<S: Saver> {
     undone: BoundedStack<Snapshot>,
     history: BoundedStack<Snapshot>,
     settings: Settings,
+    clock: Duration,
+    roller: Roller,
+    bestiary: Bestiary,
+    roster: Roster,
+    /// The save in `saves/` the tracker is auto saved to.
+    save_file: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[derive(Serialize, Deserialize)]
-struct TrackerData {
-    chrs: Vec<Chr>,
-    in_turn_index: Option<usize>,
-    cm: ConditionManager,
+pub(crate) struct TrackerData {
+    pub(crate) chrs: Vec<Chr>,
+    pub(crate) in_turn_index: Option<usize>,
+    pub(crate) cm: ConditionManager,
     undone: BoundedStack<Snapshot>,
     history: BoundedStack<Snapshot>,
     settings: Settings,
+    #[serde(default)]
+    clock: Duration,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -106,14 +144,36 @@ struct Snapshot {
     chrs: Vec<Chr>,
     in_turn_index: Option<usize>,
     cm: ConditionManager,
+    #[serde(default)]
+    clock: Duration,
 }
 
-impl<S: Saver> From<Tracker<S>> for Snapshot {
-    fn from(value: Tracker<S>) -> Self {
+/// Takes only the state that is undone, so the bestiary, roster and history
+/// aren't copied along with it.
+impl<S: Saver> From<&Tracker<S>> for Snapshot {
+    fn from(value: &Tracker<S>) -> Self {
         Self {
-            chrs: value.chrs,
+            chrs: value.chrs.clone(),
             in_turn_index: value.in_turn_index,
-            cm: value.cm
+            cm: value.cm.clone(),
+            clock: value.clock,
+        }
+    }
+}
+
+impl TrackerData {
+    /// Data of an encounter without any history, such as one imported from
+    /// another tool.
+    pub(crate) fn new(chrs: Vec<Chr>, in_turn_index: Option<usize>, cm: ConditionManager) -> Self {
+        let settings = Settings::default();
+        Self {
+            chrs,
+            in_turn_index,
+            cm,
+            undone: BoundedStack::new(settings.get_undo_size()),
+            history: BoundedStack::new(settings.get_undo_size()),
+            settings,
+            clock: Duration::default(),
         }
     }
 }
@@ -127,6 +187,7 @@ impl<S: Saver> From<Tracker<S>> for TrackerData {
             undone: value.undone,
             history: value.history,
             settings: value.settings,
+            clock: value.clock,
         }
     }
 }
@@ -141,6 +202,11 @@ impl<S: Saver> From<TrackerData> for Tracker<S> {
             undone: value.undone,
             history: value.history,
             settings: value.settings,
+            clock: value.clock,
+            roller: Roller::default(),
+            bestiary: Bestiary::default(),
+            roster: Roster::default(),
+            save_file: AUTO_SAVE.into(),
         }
     }
 }
@@ -162,14 +228,17 @@ pub struct Builder<S: Saver> {
     chrs: Vec<Chr>,
     in_turn_index: Option<usize>,
     saver: S,
-    cm: ConditionManager
+    cm: ConditionManager,
+    seed: Option<u64>,
+    bestiary: Bestiary,
+    roster: Roster,
 }
 
 impl<S: Saver> Builder<S> {
     /// Creates a new [`TrackerBuilder<S>`].
     #[must_use]
     pub fn new(saver: S) -> Self {
-        Self { chrs: vec![], in_turn_index: None, saver, cm: ConditionManager::new() }
+        Self { chrs: vec![], in_turn_index: None, saver, cm: ConditionManager::new(), seed: None, bestiary: Bestiary::default(), roster: Roster::default() }
     }
 
     /// Adds a [`saver`] [`S`] to the [`TrackerBuilder<S>`].
@@ -189,6 +258,28 @@ impl<S: Saver> Builder<S> {
         self
     }
 
+    /// Seeds the dice roller of the [`TrackerBuilder<S>`], making it roll the
+    /// same results every time.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Adds a [`bestiary`] of creature templates to the [`TrackerBuilder<S>`].
+    #[must_use]
+    pub fn with_bestiary(mut self, bestiary: Bestiary) -> Self {
+        self.bestiary = bestiary;
+        self
+    }
+
+    /// Adds the party [`roster`] to the [`TrackerBuilder<S>`].
+    #[must_use]
+    pub fn with_roster(mut self, roster: Roster) -> Self {
+        self.roster = roster;
+        self
+    }
+
     /// Builds a [`Tracker<S>`] from a [`TrackerBuilder<S>`].
     pub fn build(self) -> Tracker<S> {
         let settings = Settings::default();
@@ -200,7 +291,12 @@ impl<S: Saver> Builder<S> {
             cm: self.cm,
             undone: BoundedStack::new(settings.get_undo_size()),
             history: BoundedStack::new(settings.get_undo_size()),
-            settings
+            settings,
+            clock: Duration::default(),
+            roller: self.seed.map_or_else(Roller::default, Roller::seeded),
+            bestiary: self.bestiary,
+            roster: self.roster,
+            save_file: AUTO_SAVE.into(),
         }
     }
 }
@@ -225,6 +321,55 @@ impl<S: Saver> Tracker<S> {
         self.chrs.iter().enumerate().find(|(_,x)| x.name == name).map(|e| e.0)
     }
 
+    /// Returns the range of positions on the tracker order of the
+    /// initiative slot holding the given position.
+    ///
+    /// A slot is either a single character or all the members of a group.
+    fn slot_range(&self, pos: usize) -> Range<usize> {
+        let Some(group) = self.chrs.get(pos).and_then(|chr| chr.group.as_ref()) else {
+            return pos..pos + 1
+        };
+        let in_group = |chr: &Chr| chr.group.as_ref() == Some(group);
+
+        let start = self.chrs[..pos].iter().rposition(|chr| !in_group(chr)).map_or(0, |i| i + 1);
+        let end = self.chrs[pos..].iter().position(|chr| !in_group(chr)).map_or(self.chrs.len(), |i| pos + i);
+
+        start..end
+    }
+
+    /// Returns the position of the slot holding the character with the given
+    /// [`name`], if such a one exists.
+    fn slot_pos(&self, name: &ChrName) -> Option<usize> {
+        self.pos(name).map(|pos| self.slot_range(pos).start)
+    }
+
+    /// Returns the names of the characters sharing a slot with the character
+    /// named [`name`], including themselves.
+    fn slot_names(&self, name: &ChrName) -> Vec<ChrName> {
+        self.pos(name)
+            .map(|pos| self.chrs[self.slot_range(pos)].iter().map(|chr| chr.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Points the turn at the slot of the character named [`name`], if any.
+    fn set_in_turn(&mut self, name: Option<&ChrName>) {
+        if let Some(pos) = name.and_then(|name| self.slot_pos(name)) {
+            self.in_turn_index = Some(pos);
+        }
+    }
+
+    /// Returns the initiative slots of this [`Tracker<S>`] in order.
+    ///
+    /// Each slot is either a single character or all the members of a group.
+    pub fn get_slots(&self) -> Vec<&[Chr]> {
+        self.chrs.chunk_by(|x, y| x.group.is_some() && x.group == y.group).collect()
+    }
+
+    /// Returns the members of the group with the given name.
+    pub fn get_group(&self, group: &str) -> Vec<&Chr> {
+        self.chrs.iter().filter(|chr| chr.group.as_deref() == Some(group)).collect()
+    }
+
     /// Undoes the last change made to the tracker.
     ///
     /// # Errors
@@ -234,7 +379,7 @@ impl<S: Saver> Tracker<S> {
     /// - Auto saving fails.
     pub fn undo(&mut self) -> Result<()> {
         let prev = self.history.pop().ok_or(Error::UndoNothingError)?;
-        let curr: Snapshot = self.clone().into();
+        let curr = Snapshot::from(&*self);
         
         self.recover(&prev);
 
@@ -254,7 +399,7 @@ impl<S: Saver> Tracker<S> {
     /// - Auto saving fails.
     pub fn redo(&mut self) -> Result<()> {
         let next = self.undone.pop().ok_or(Error::RedoNothingError)?;
-        let curr: Snapshot = self.clone().into();
+        let curr = Snapshot::from(&*self);
 
         self.recover(&next);
 
@@ -269,11 +414,12 @@ impl<S: Saver> Tracker<S> {
         self.chrs.clone_from(&snapshot.chrs);
         self.in_turn_index = snapshot.in_turn_index;
         self.cm.clone_from(&snapshot.cm);
+        self.clock = snapshot.clock;
     }
 
     fn take_snap(&mut self) {
         self.undone = BoundedStack::new(self.settings.get_undo_size());
-        self.history.push(self.clone().into());
+        self.history.push(Snapshot::from(&*self));
     }
 
     /// Returns a reference to characters of this [`Tracker<S>`].
@@ -294,7 +440,7 @@ impl<S: Saver> Tracker<S> {
     }
 
     fn end_turn_no_snap(&mut self) -> Result<Option<&Chr>> {
-        if let Some(chr) = self.get_in_turn().cloned() {
+        for chr in self.get_in_turn_slot().to_vec() {
             let damage = self.cm.end_of_turn(chr.name.clone());
             if let Some(damage) = damage {
                 // It can only fail if there is no character by the name,
@@ -306,14 +452,16 @@ impl<S: Saver> Tracker<S> {
         if !self.chrs.is_empty() { 
             self.in_turn_index = Some(match self.in_turn_index {
                 None => 0,
-                Some(i) => (i + 1) % self.chrs.len(),
+                Some(i) => self.slot_range(i).end % self.chrs.len(),
             });
         }
 
-        if let Some(chr) = self.get_in_turn() {
-            self.cm.start_of_turn(chr.name.clone());
+        for chr in self.get_in_turn_slot().to_vec() {
+            self.cm.start_of_turn(chr.name);
         }
 
+        self.apply_drained();
+
         self.auto_save()?;
         Ok(self.get_in_turn())
     }
@@ -322,6 +470,116 @@ impl<S: Saver> Tracker<S> {
         self.in_turn_index.and_then(|i| self.chrs.get(i))
     }
 
+    /// Returns the characters whose turn it is, which is all the members if
+    /// it is the turn of a group.
+    pub fn get_in_turn_slot(&self) -> &[Chr] {
+        self.in_turn_index
+            .filter(|i| *i < self.chrs.len())
+            .map_or(&[], |i| &self.chrs[self.slot_range(i)])
+    }
+
+    /// Returns how much time has passed in the world of this [`Tracker<S>`].
+    pub const fn get_clock(&self) -> Duration {
+        self.clock
+    }
+
+    /// Lets the given amount of time pass outside of combat.
+    ///
+    /// The world clock is moved forward by [`dur`] and every time based
+    /// condition is moved forward with it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if auto saving fails.
+    pub fn advance_time(&mut self, dur: Duration) -> Result<()> {
+        self.take_snap();
+        self.advance_time_no_snap(dur);
+        self.auto_save()
+    }
+
+    fn advance_time_no_snap(&mut self, dur: Duration) {
+        self.clock = self.clock + dur;
+        self.cm.advance_time(dur);
+        self.apply_drained();
+    }
+
+    /// Takes a full night's rest of 8 hours.
+    ///
+    /// Besides letting the time pass, this removes fatigued and reduces
+    /// drained and doomed by 1 for every character.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if auto saving fails.
+    pub fn rest(&mut self) -> Result<()> {
+        self.take_snap();
+        self.advance_time_no_snap(Duration::from_hours(8));
+        self.cm.rest();
+        self.apply_drained();
+        self.auto_save()
+    }
+
+    /// Takes 10 minutes to refocus, letting the time pass.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if auto saving fails.
+    pub fn refocus(&mut self) -> Result<()> {
+        self.advance_time(Duration::from_minutes(10))
+    }
+
+    /// Rolls the given dice expression using the dice roller of this
+    /// [`Tracker<S>`].
+    pub fn roll(&mut self, expr: &DiceExpr) -> Roll {
+        self.roller.roll(expr)
+    }
+
+    /// Rolls initiative for the characters with the given [`names`] as a
+    /// single change, setting their initiative to the result.
+    ///
+    /// Each character rolls a d20 plus their initiative modifier, or plus
+    /// nothing if they have none. A group rolls once for all its members.
+    /// Returns the roll of each slot, named by the first of [`names`] in it.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no character by one of the names in [`names`]
+    /// - Auto saving fails.
+    pub fn roll_initiative(&mut self, names: &[ChrName]) -> Result<Vec<(ChrName, Roll)>> {
+        self.check_names(names, Error::ChangeNoneError)?;
+        self.take_snap();
+
+        let mut rolled: Vec<ChrName> = Vec::new();
+        let mut rolls = Vec::new();
+        for name in names {
+            let slot = self.slot_names(name);
+            if slot.iter().any(|member| rolled.contains(member)) {
+                continue
+            }
+
+            let modifier = self.get_chr(name).and_then(|chr| chr.init_mod).unwrap_or(0);
+            let roll = self.roller.roll(&DiceExpr::d20(modifier));
+            self.change(name, |chr| chr.init = roll.total)?;
+
+            rolled.extend(slot);
+            rolls.push((name.clone(), roll));
+        }
+
+        Ok(rolls)
+    }
+
+    /// Rolls initiative for every character that isn't a player character, as
+    /// with [`Tracker::roll_initiative`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if auto saving fails.
+    pub fn roll_enemy_initiative(&mut self) -> Result<Vec<(ChrName, Roll)>> {
+        let names: Vec<ChrName> = self.chrs.iter().filter(|chr| !chr.player).map(|chr| chr.name.clone()).collect();
+        self.roll_initiative(&names)
+    }
+
     /// Adds a character [`chr`] to this [`Tracker<S>`].
     ///
     /// # Errors
@@ -335,15 +593,289 @@ impl<S: Saver> Tracker<S> {
             // return Err(format!("Cannot add character {:?} since there is already a character by this name.", chr)) 
         }
 
-        if let Some(i) = self.in_turn_index {
-            if chr.init > self.chrs[i].init {
-                self.in_turn_index = Some(i + 1);
+        let in_turn = self.get_in_turn().map(|chr| chr.name.clone());
+
+        self.chrs.push(chr);
+        self.chrs.sort();
+
+        self.set_in_turn(in_turn.as_ref());
+
+        self.auto_save()?;
+
+        Ok(())
+    }
+
+    /// Adds [`chr`] to this [`Tracker<S>`], or if there already is a player
+    /// character by its name, updates that character in place with
+    /// [`Chr::update_from`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There already is a non-player character by the name of [`chr`]
+    /// - Auto saving fails.
+    pub fn add_or_update_chr(&mut self, chr: Chr) -> Result<()> {
+        let old = self.chrs.iter().position(|old| old.name == chr.name);
+        if let Some(i) = old {
+            if !self.chrs[i].player {
+                return Err(Error::AddDupError(chr.name))
             }
         }
 
-        self.chrs.push(chr);
+        self.take_snap();
+
+        let in_turn = self.get_in_turn().map(|chr| chr.name.clone());
+
+        match old {
+            Some(i) => self.chrs[i].update_from(chr),
+            None => self.chrs.push(chr),
+        }
+        self.chrs.sort();
+
+        self.set_in_turn(in_turn.as_ref());
+
+        self.auto_save()
+    }
+
+    /// Imports a player character from the Pathbuilder 2e JSON export at
+    /// [`path`], updating them in place if they are already in the tracker.
+    /// Returns the name of the character.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - The export can't be read or isn't a valid Pathbuilder export
+    /// - There already is a non-player character by the name of the player
+    ///   character
+    /// - Auto saving fails.
+    pub fn import_pathbuilder(&mut self, path: impl AsRef<std::path::Path>) -> Result<ChrName> {
+        let chr = pathbuilder::load(path)?;
+        let name = chr.name.clone();
+        self.add_or_update_chr(chr)?;
+        Ok(name)
+    }
+
+    /// Replaces the encounter with the one in the Foundry VTT combat document
+    /// at [`path`], as a single change. The settings are kept.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - The document can't be read or isn't a valid combat document
+    /// - Auto saving fails.
+    pub fn import_foundry(&mut self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let data = foundry::load(path)?;
+
+        self.take_snap();
+
+        self.chrs = data.chrs;
+        self.in_turn_index = data.in_turn_index;
+        self.cm = data.cm;
+
+        self.auto_save()
+    }
+
+    /// Exports the encounter as a Foundry VTT combat document to [`path`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the document can't be written.
+    pub fn export_foundry(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let data: TrackerData = self.to_owned().into();
+        Ok(foundry::save(&data, path)?)
+    }
+
+    /// Adds [`count`] characters made from [`template`] as a single change.
+    ///
+    /// The characters are given unique names from the name of the template,
+    /// such as `Wolf`, `Wolf 2`, `Wolf 3` and so on, skipping names that are
+    /// already taken. Returns the names of the added characters.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - [`count`] is over [`MAX_COUNT`]
+    /// - Auto saving fails.
+    pub fn add_many(&mut self, template: Chr, count: u32) -> Result<Vec<ChrName>> {
+        self.add_many_with(template, count, |_, _| ())
+    }
+
+    /// Adds [`count`] characters made from [`template`] as a single change,
+    /// like [`Tracker::add_many`], letting [`f`] give each of them their own
+    /// initiative, health and so on.
+    ///
+    /// [`f`] is given the index of each character, counting from 0, and the
+    /// character with its unique name. Changing the name has no effect.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - [`count`] is over [`MAX_COUNT`]
+    /// - Auto saving fails.
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn add_many_with<F>(&mut self, template: Chr, count: u32, mut f: F) -> Result<Vec<ChrName>> where
+        F: FnMut(u32, &mut Chr)
+    {
+        check_count(count)?;
+        self.take_snap();
+
+        let in_turn = self.get_in_turn().map(|chr| chr.name.clone());
+
+        let names = self.push_many_no_snap(&template, count, &mut f);
+        self.chrs.sort();
+
+        self.set_in_turn(in_turn.as_ref());
+
+        self.auto_save()?;
+
+        Ok(names)
+    }
+
+    /// Adds every creature of [`encounter`] as a single change, each rolling
+    /// their own initiative with their initiative modifier.
+    ///
+    /// The creatures of each entry are named like with [`Tracker::add_many`].
+    /// Returns the names of the added characters.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There are over [`MAX_COUNT`] creatures of an entry
+    /// - Auto saving fails.
+    pub fn add_encounter(&mut self, encounter: &Encounter) -> Result<Vec<ChrName>> {
+        for (_, count) in encounter.get_entries() {
+            check_count(*count)?;
+        }
+        self.take_snap();
+
+        let in_turn = self.get_in_turn().map(|chr| chr.name.clone());
+
+        let mut names = Vec::new();
+        for (template, count) in encounter.get_entries() {
+            let expr = DiceExpr::d20(template.init_mod.unwrap_or(0));
+            let inits: Vec<i32> = (0..*count).map(|_| self.roller.roll(&expr).total).collect();
+            names.extend(self.push_many_no_snap(template, *count, |i, chr| {
+                if let Some(init) = usize::try_from(i).ok().and_then(|i| inits.get(i)) {
+                    chr.init = *init;
+                }
+            }));
+        }
         self.chrs.sort();
 
+        self.set_in_turn(in_turn.as_ref());
+
+        self.auto_save()?;
+
+        Ok(names)
+    }
+
+    /// Pushes [`count`] copies of [`template`] with unique numbered names,
+    /// without sorting the characters.
+    fn push_many_no_snap<F>(&mut self, template: &Chr, count: u32, mut f: F) -> Vec<ChrName> where
+        F: FnMut(u32, &mut Chr)
+    {
+        let base = template.name.to_string();
+        let names: Vec<ChrName> = (1..)
+            .map(|i: u32| if i == 1 { ChrName::new(base.clone()) } else { ChrName::new(format!("{base} {i}")) })
+            .filter(|name| self.get_chr(name).is_none())
+            .take(usize::try_from(count).unwrap_or(usize::MAX))
+            .collect();
+
+        for (i, name) in (0..count).zip(names.iter()) {
+            let mut chr = template.clone();
+            f(i, &mut chr);
+            chr.name = name.clone();
+            self.chrs.push(chr);
+        }
+
+        names
+    }
+
+    /// Adds [`count`] characters made from the bestiary template by the id
+    /// [`template_id`] as a single change, each rolling their own initiative.
+    ///
+    /// The characters are named like with [`Tracker::add_many`]. Returns the
+    /// names of the added characters.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no template by the id [`template_id`] in the bestiary
+    /// - [`count`] is over [`MAX_COUNT`]
+    /// - Auto saving fails.
+    pub fn add_from_template(&mut self, template_id: &str, count: u32) -> Result<Vec<ChrName>> {
+        self.add_variant_from_template(template_id, None, count)
+    }
+
+    /// Adds [`count`] characters made from the bestiary template by the id
+    /// [`template_id`], like [`Tracker::add_from_template`], applying the
+    /// elite or weak adjustments of [`variant`] if given.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no template by the id [`template_id`] in the bestiary
+    /// - [`count`] is over [`MAX_COUNT`]
+    /// - Auto saving fails.
+    pub fn add_variant_from_template(&mut self, template_id: &str, variant: Option<Variant>, count: u32) -> Result<Vec<ChrName>> {
+        check_count(count)?;
+        let template = self.bestiary.get(template_id)
+            .ok_or_else(|| Error::NoTemplateError(template_id.to_string()))?;
+        let chr = variant.map_or_else(|| template.to_chr(0), |variant| {
+            let mut chr = template.adjusted(variant).to_chr(0);
+            chr.variant = Some(variant);
+            chr
+        });
+        let expr = DiceExpr::d20(chr.init_mod.unwrap_or(0));
+
+        let inits: Vec<i32> = (0..count).map(|_| self.roller.roll(&expr).total).collect();
+        self.add_many_with(chr, count, |i, chr| {
+            if let Some(init) = usize::try_from(i).ok().and_then(|i| inits.get(i)) {
+                chr.init = *init;
+            }
+        })
+    }
+
+    /// Adds a group of [`count`] characters sharing a single initiative slot.
+    ///
+    /// Every member is a copy of [`chr`] with their own health and conditions,
+    /// named after it and numbered from 1, such as `Goblin 1` to `Goblin 4`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - [`count`] is 0 or over [`MAX_COUNT`]
+    /// - There already is a group by the name of [`chr`]
+    /// - There already is a character by the name of one of the members
+    /// - Auto saving fails.
+    pub fn add_group(&mut self, chr: Chr, count: u32) -> Result<()> {
+        check_count(count)?;
+        self.take_snap();
+
+        if count == 0 {
+            return Err(Error::EmptyGroupError(chr.name))
+        }
+
+        let group = chr.name.to_string();
+        if !self.get_group(&group).is_empty() {
+            return Err(Error::AddDupError(chr.name))
+        }
+
+        let members: Vec<Chr> = (1..=count)
+            .map(|i| Chr { name: ChrName::new(format!("{group} {i}")), group: Some(group.clone()), ..chr.clone() })
+            .collect();
+
+        if let Some(member) = members.iter().find(|member| self.get_chr(&member.name).is_some()) {
+            return Err(Error::AddDupError(member.name.clone()))
+        }
+
+        let in_turn = self.get_in_turn().map(|chr| chr.name.clone());
+
+        self.chrs.extend(members);
+        self.chrs.sort();
+
+        self.set_in_turn(in_turn.as_ref());
+
         self.auto_save()?;
 
         Ok(())
@@ -363,12 +895,42 @@ impl<S: Saver> Tracker<S> {
             None => Err(Error::ChangeNoneError(name.clone())),
             Some(_) => {
                 self.cm.add_condition(name, cond);
+                self.apply_drained();
                 self.auto_save()?;
                 Ok(())
             }
         }
     }
 
+    /// Adds each [`Condition`] in [`conds`] to the character it is paired
+    /// with, as a single change.
+    ///
+    /// Each character gets their own condition, so a condition given to
+    /// several characters can wait for the turn of each of them.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no character by one of the names in [`conds`]
+    /// - Auto saving fails.
+    pub fn add_condition_many(&mut self, conds: Vec<(ChrName, Condition)>) -> Result<()> {
+        self.take_snap();
+
+        if let Some((name, _)) = conds.iter().find(|(name, _)| self.get_chr(name).is_none()) {
+            return Err(Error::ChangeNoneError(name.clone()))
+        }
+
+        for (name, cond) in conds {
+            self.cm.add_condition(name, cond);
+        }
+
+        self.apply_drained();
+
+        self.auto_save()?;
+
+        Ok(())
+    }
+
     /// Returns the conditions of a character with the given name.
     ///
     /// Provided the name of a character, a [`HashSet<&Condition>`] of
@@ -378,6 +940,20 @@ impl<S: Saver> Tracker<S> {
         self.cm.get_conditions(character)
     }
 
+    /// Returns the modifiers the conditions of the character with the given
+    /// name give to each of their statistics.
+    ///
+    /// If there is no character with the given name, no modifiers are returned.
+    pub fn get_modifiers(&self, character: &ChrName) -> Modifiers {
+        Modifiers::from_conditions(self.cm.get_conditions(character))
+    }
+
+    /// Returns the modifier the conditions of the character with the given
+    /// name give to the given statistic.
+    pub fn get_modifier(&self, character: &ChrName, stat: Statistic) -> i32 {
+        modifiers::modifier(self.cm.get_conditions(character), stat)
+    }
+
     /// Removes the given condition type from the character with the givne name.
     ///
     /// If there is no character with the given name, or the character has no
@@ -385,6 +961,54 @@ impl<S: Saver> Tracker<S> {
     pub fn rm_condition(&mut self, character: &ChrName, condition: &Condition) {
         self.take_snap();
         self.cm.remove_condition(character, condition);
+        self.apply_drained();
+    }
+
+    /// Brings the max HP of every character in line with their drained
+    /// condition.
+    fn apply_drained(&mut self) {
+        for chr in &mut self.chrs {
+            let drained = self.cm.get_conditions(&chr.name).into_iter()
+                .find_map(|cond| match cond {
+                    Condition::Valued { cond: ValuedCondition::Drained, level, .. } => Some(*level),
+                    _ => None
+                })
+                .unwrap_or(0);
+
+            chr.set_drained(drained);
+        }
+    }
+
+    /// The party of the player characters in this [`Tracker<S>`], if any of
+    /// them have a level.
+    #[must_use]
+    pub fn get_party(&self) -> Option<Party> {
+        Party::from_chrs(&self.chrs)
+    }
+
+    /// The XP and threat of the non-player characters in this [`Tracker<S>`]
+    /// to the party, if there is one.
+    #[must_use]
+    pub fn get_threat(&self) -> Option<Rating> {
+        self.get_party().map(|party| Rating::new(party, &self.chrs))
+    }
+
+    #[must_use]
+    pub const fn get_bestiary(&self) -> &Bestiary {
+        &self.bestiary
+    }
+
+    pub fn set_bestiary(&mut self, bestiary: Bestiary) {
+        self.bestiary = bestiary;
+    }
+
+    #[must_use]
+    pub const fn get_roster(&self) -> &Roster {
+        &self.roster
+    }
+
+    pub fn set_roster(&mut self, roster: Roster) {
+        self.roster = roster;
     }
 
     pub fn set_undo_size_setting(&mut self, value: usize) {
@@ -404,11 +1028,20 @@ impl<S: Saver> Tracker<S> {
     pub fn get_pf2e_version_setting(&self) -> Pf2eVersion {
         self.settings.get_pf2e_version()
     }
+
+    pub fn set_key_bindings_setting(&mut self, value: KeyBindings) {
+        self.settings.set_key_bindings(value);
+    }
+
+    pub const fn get_key_bindings_setting(&self) -> &KeyBindings {
+        self.settings.get_key_bindings()
+    }
     
     /// Removes a character with the given [`name`] from this [`Tracker<S>`].
     ///
     /// If the removed character is the one in turn, this ends the given
-    /// characters turn.
+    /// characters turn. If the character is the last member of a group that
+    /// isn't down, the rest of the group is removed as well.
     ///
     /// # Errors
     ///
@@ -418,41 +1051,111 @@ impl<S: Saver> Tracker<S> {
     pub fn rm_chr(&mut self, name: &ChrName) -> Result<()> {
         self.take_snap();
 
-        let rm_index = self.chrs.iter()
-            .position(|chr| chr.name == name)
-            .ok_or_else(|| Error::RmNoneError(name.clone()))?;
+        let removed = self.get_chr(name).ok_or_else(|| Error::RmNoneError(name.clone()))?;
 
-        let removed = self.chrs.remove(rm_index);
+        let names: Vec<ChrName> = match &removed.group {
+            Some(group) if self.get_group(group).iter().all(|chr| chr.name == name || chr.is_down()) =>
+                self.get_group(group).iter().map(|chr| chr.name.clone()).collect(),
+            _ => vec![name.clone()],
+        };
 
-        self.cm.remove_character(&removed.name);
+        self.rm_chrs_no_snap(&names)?;
 
-        if self.chrs.is_empty() {
-            self.in_turn_index = None;
-            self.auto_save()?;
-            return Ok(())
-        } 
+        self.auto_save()?;
 
-        if let Some(in_turn) = self.in_turn_index {
-            match rm_index.cmp(&in_turn) {
-                Ordering::Less => {
-                    self.in_turn_index = Some(in_turn - 1);
-                }
-                Ordering::Equal => {
-                    // While it may seem redundant to decrement `in_turn_index`
-                    // only to then end the turn (which increments it), 
-                    // ending the turn has other effects which should occur in this situation.
-                    self.in_turn_index = in_turn.checked_sub(1);
-                    self.end_turn_no_snap()?;
+        Ok(())
+    }
+
+    /// Removes the characters named in [`names`] from this [`Tracker<S>`] as a
+    /// single change.
+    ///
+    /// As with [`Tracker::rm_chr`], a group is removed as a whole when the
+    /// members left after the removal are all down.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no character by one of the names in [`names`]
+    /// - Auto saving fails.
+    pub fn rm_chrs(&mut self, names: &[ChrName]) -> Result<()> {
+        self.check_names(names, Error::RmNoneError)?;
+        self.take_snap();
+
+        let mut removed = names.to_vec();
+        for name in names {
+            let Some(group) = self.get_chr(name).and_then(|chr| chr.group.clone()) else { continue };
+            let members = self.get_group(&group);
+            if members.iter().all(|chr| names.contains(&chr.name) || chr.is_down()) {
+                for member in members {
+                    if !removed.contains(&member.name) {
+                        removed.push(member.name.clone());
+                    }
                 }
-                Ordering::Greater => ()
             }
         }
 
+        self.rm_chrs_no_snap(&removed)?;
+
         self.auto_save()?;
 
         Ok(())
     }
 
+    /// Removes all the members of the group with the given name from this
+    /// [`Tracker<S>`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if 
+    /// - There is no group with the given name
+    /// - Auto saving fails.
+    pub fn rm_group(&mut self, group: &str) -> Result<()> {
+        self.take_snap();
+
+        let names: Vec<ChrName> = self.get_group(group).iter().map(|chr| chr.name.clone()).collect();
+        if names.is_empty() {
+            return Err(Error::RmNoneError(ChrName::new(group)))
+        }
+
+        self.rm_chrs_no_snap(&names)?;
+
+        self.auto_save()?;
+
+        Ok(())
+    }
+
+    fn rm_chrs_no_snap(&mut self, names: &[ChrName]) -> Result<()> {
+        let in_turn = self.in_turn_index.map(|i| (i, self.slot_range(i)));
+        let removed_before = |i: usize| self.chrs[..i].iter().filter(|chr| names.contains(&chr.name)).count();
+        let turn_ends = in_turn.clone().is_some_and(|(_, slot)| self.chrs[slot].iter().all(|chr| names.contains(&chr.name)));
+        let in_turn = in_turn.map(|(i, _)| i - removed_before(i));
+
+        self.chrs.retain(|chr| !names.contains(&chr.name));
+
+        for name in names {
+            self.cm.remove_character(name);
+        }
+
+        if self.chrs.is_empty() {
+            self.in_turn_index = None;
+            return Ok(())
+        }
+
+        if let Some(in_turn) = in_turn {
+            if turn_ends {
+                // While it may seem redundant to decrement `in_turn_index`
+                // only to then end the turn (which increments it), 
+                // ending the turn has other effects which should occur in this situation.
+                self.in_turn_index = in_turn.checked_sub(1);
+                self.end_turn_no_snap()?;
+            } else {
+                self.in_turn_index = Some(in_turn);
+            }
+        }
+
+        Ok(())
+    }
+
     /// Renames a the character named [`old`], giving it the name [`new`].
     ///
     /// # Errors
@@ -493,6 +1196,8 @@ impl<S: Saver> Tracker<S> {
     /// Marks a character named as a player character.
     ///
     /// Marks the character given by the name [`name`] as a player character.
+    /// If they are in a group, the whole group is marked with them, as the
+    /// group shares a single turn.
     ///
     /// # Errors
     ///
@@ -501,7 +1206,59 @@ impl<S: Saver> Tracker<S> {
     /// - Auto saving fails.
     pub fn set_player(&mut self, name: &ChrName, player: bool) -> Result<()> {
         self.take_snap();
-        self.unchecked_change(name, |chr| chr.player = player)
+        self.change(name, |chr| chr.player = player).map(|_| ())
+    }
+
+    /// Marks each of the characters named in [`names`] as player characters or
+    /// not, as a single change.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no character by one of the names in [`names`]
+    /// - Auto saving fails.
+    pub fn set_player_many(&mut self, names: &[ChrName], player: bool) -> Result<()> {
+        self.check_names(names, Error::ChangeNoneError)?;
+        self.take_snap();
+
+        // Like with `set_player`, the whole group of each character is marked.
+        let slots: Vec<ChrName> = names.iter().flat_map(|name| self.slot_names(name)).collect();
+        self.unchecked_change_many(&slots, |chr| chr.player = player)
+    }
+
+    /// Sets the level of a character.
+    ///
+    /// Setting the level to [`None`] marks the level as unknown.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There's no character with the given [`name`]
+    /// - Auto saving fails.
+    pub fn set_level(&mut self, name: &ChrName, level: Option<i32>) -> Result<()> {
+        self.take_snap();
+
+        let chr = self.chrs.iter_mut().find(|chr| chr.name == name).ok_or_else(|| Error::ChangeNoneError(name.clone()))?;
+        chr.level = level;
+        // Drained reduces max HP by the level, so it is applied again before
+        // the only save.
+        self.apply_drained();
+
+        self.auto_save()
+    }
+
+    /// Sets the initiative modifier of a character.
+    ///
+    /// Setting the modifier to [`None`] marks it as unknown.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There's no character with the given [`name`]
+    /// - Auto saving fails.
+    pub fn set_init_mod(&mut self, name: &ChrName, init_mod: Option<i32>) -> Result<()> {
+        self.take_snap();
+        self.unchecked_change(name, |chr| chr.init_mod = init_mod)
     }
 
     /// Changes the max health of the character.
@@ -538,6 +1295,56 @@ impl<S: Saver> Tracker<S> {
         self.cm = ConditionManager::new();
     }
 
+    /// The members of the party, which are the player characters in the
+    /// tracker along with those on the roster who aren't, sorted by name.
+    #[must_use]
+    pub fn get_party_members(&self) -> Vec<Chr> {
+        let players = self.chrs.iter().filter(|chr| chr.player);
+        let absent = self.roster.get_chrs().iter().filter(|chr| self.get_chr(&chr.name).is_none());
+        Roster::new(players.chain(absent).cloned()).get_chrs().to_vec()
+    }
+
+    /// Starts a new encounter with the members of the party named in
+    /// [`party`] at the given initiatives, as a single change.
+    ///
+    /// Every other character is removed along with the conditions that don't
+    /// last beyond the encounter, while the party members keep their current
+    /// health and long conditions. Members on the roster who aren't in the
+    /// tracker are brought back, and the party is saved as the new roster.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There's no party member by one of the names in [`party`]
+    /// - Saving the roster or auto saving fails.
+    pub fn new_encounter(&mut self, party: &[(ChrName, i32)]) -> Result<()> {
+        let members = self.get_party_members();
+        let mut chrs: Vec<Chr> = Vec::new();
+        for (name, init) in party {
+            if chrs.iter().any(|chr| chr.name == *name) {
+                continue
+            }
+            let mut chr = members.iter()
+                .find(|chr| chr.name == *name)
+                .cloned()
+                .ok_or_else(|| Error::ChangeNoneError(name.clone()))?;
+            chr.init = *init;
+            chrs.push(chr);
+        }
+
+        self.take_snap();
+
+        self.cm.end_encounter(|name| chrs.iter().any(|chr| chr.name == *name));
+        chrs.sort();
+        self.chrs = chrs;
+        self.in_turn_index = None;
+
+        self.roster = Roster::new(self.chrs.clone());
+        self.roster.save(&self.saver)?;
+
+        self.auto_save()
+    }
+
     /// Sets the current health of a character.
     ///
     /// # Errors
@@ -598,6 +1405,61 @@ impl<S: Saver> Tracker<S> {
         self.unchecked_change(name, |chr| { chr.heal(heal); })
     }
 
+    /// Damages each of the characters named in [`names`] by the given
+    /// [`amount`], as a single change.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no character by one of the names in [`names`]
+    /// - Auto saving fails.
+    pub fn damage_many(&mut self, names: &[ChrName], amount: u32) -> Result<()> {
+        self.check_names(names, Error::ChangeNoneError)?;
+        self.take_snap();
+        self.unchecked_change_many(names, |chr| { chr.damage(amount); })
+    }
+
+    /// Heals each of the characters named in [`names`] by the given
+    /// [`amount`], as a single change.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if
+    /// - There is no character by one of the names in [`names`]
+    /// - Auto saving fails.
+    pub fn heal_many(&mut self, names: &[ChrName], amount: u32) -> Result<()> {
+        self.check_names(names, Error::ChangeNoneError)?;
+        self.take_snap();
+        self.unchecked_change_many(names, |chr| { chr.heal(amount); })
+    }
+
+    /// Checks that there is a character by each of the names in [`names`],
+    /// giving the error made by [`err`] for the first missing one.
+    fn check_names(&self, names: &[ChrName], err: impl FnOnce(ChrName) -> Error) -> Result<()> {
+        names.iter().find(|name| self.get_chr(name).is_none()).map_or(Ok(()), |name| Err(err(name.clone())))
+    }
+
+    /// Changes each of the characters named in [`names`], which must all
+    /// exist.
+    fn unchecked_change_many<F>(&mut self, names: &[ChrName], mut f: F) -> Result<()> where
+        F: FnMut(&mut Chr)
+    {
+        let in_turn = self.get_in_turn().map(|chr| chr.name.clone());
+
+        for chr in &mut self.chrs {
+            if names.contains(&chr.name) {
+                f(chr);
+            }
+        }
+        self.chrs.sort();
+
+        self.set_in_turn(in_turn.as_ref());
+
+        self.auto_save()?;
+
+        Ok(())
+    }
+
     fn unchecked_change<F>(&mut self, name: &ChrName, f: F) -> Result<()> where
         F: FnOnce(&mut Chr)
     {
@@ -613,31 +1475,51 @@ impl<S: Saver> Tracker<S> {
         Err(Error::ChangeNoneError(name.clone()))
     }
 
-    fn change<F>(&mut self, name: &ChrName, f: F) -> Result<Option<MovedStatus>> where
-        F: FnOnce(&mut Chr)
+    /// Changes the character named [`name`] along with the rest of their slot,
+    /// keeping track of how the change moves the slot in the tracker order.
+    fn change<F>(&mut self, name: &ChrName, mut f: F) -> Result<Option<MovedStatus>> where
+        F: FnMut(&mut Chr)
     {
-        let before = self.pos(name).ok_or_else(|| Error::ChangeNoneError(name.clone()))?;
+        let before = self.slot_pos(name).ok_or_else(|| Error::ChangeNoneError(name.clone()))?;
         let in_turn = self.in_turn_index;
+        let in_turn_name = self.get_in_turn().map(|chr| chr.name.clone());
 
-        self.unchecked_change(name, f)?;
-
-        let after = self.pos(name).ok_or_else(|| Error::ChangeNoneError(name.clone()))?;
-
-        if let Some(in_turn) = in_turn {
-            if before == in_turn && after < in_turn {
-                return Ok(Some(MovedStatus::TwoTurns(self.chrs[after].clone())))
+        let names = self.slot_names(name);
+        for chr in &mut self.chrs {
+            if names.contains(&chr.name) {
+                f(chr);
             }
-            if before < in_turn && in_turn <= after  {
-                self.in_turn_index = Some(in_turn - 1);
-                return Ok(Some(MovedStatus::TwoTurns(self.chrs[after].clone())))
-            } 
-            if before > in_turn && in_turn >= after  {
-                self.in_turn_index = Some(in_turn + 1);
-                return Ok(Some(MovedStatus::Skipped(self.chrs[after].clone())))
-            }                
         }
+        self.chrs.sort();
 
-        Ok(None)
+        let after = self.slot_pos(name).ok_or_else(|| Error::ChangeNoneError(name.clone()))?;
+
+        let moved = match (in_turn, in_turn_name) {
+            (Some(in_turn), _) if before == in_turn => {
+                // The turn stays at the same position, so it may now be in
+                // the middle of another group.
+                self.in_turn_index = Some(self.slot_range(in_turn).start);
+                (after < in_turn).then(|| MovedStatus::TwoTurns(self.chrs[after].clone()))
+            },
+            (Some(in_turn), Some(in_turn_name)) => {
+                // The slot in turn is found again by name, as the moved slot
+                // may be longer or shorter than it.
+                self.set_in_turn(Some(&in_turn_name));
+                let now_in_turn = self.in_turn_index.unwrap_or(in_turn);
+                if before < in_turn && after > now_in_turn {
+                    Some(MovedStatus::TwoTurns(self.chrs[after].clone()))
+                } else if before > in_turn && after < now_in_turn {
+                    Some(MovedStatus::Skipped(self.chrs[after].clone()))
+                } else {
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        self.auto_save()?;
+
+        Ok(moved)
     }
 
     /// Saves this [`Tracker<S>`] to the file by the given [`file_name`].
@@ -651,11 +1533,27 @@ impl<S: Saver> Tracker<S> {
         Ok(())
     }
 
+    /// Saves this [`Tracker<S>`] to its save file, which is `auto.save`
+    /// unless set with [`Tracker::set_save_file`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if [`saver.save`] fails.
     pub fn auto_save(&self) -> Result<()> {
-        self.save("auto.save")?;
+        self.save(self.save_file.clone())?;
         Ok(())
     }
 
+    #[must_use]
+    pub fn get_save_file(&self) -> &str {
+        &self.save_file
+    }
+
+    /// Sets the save in `saves/` this [`Tracker<S>`] is auto saved to.
+    pub fn set_save_file(&mut self, file_name: impl Into<String>) {
+        self.save_file = file_name.into();
+    }
+
     /// Loads a [`Tracker<S>`] from a file by the given [`file_name`].
     ///
     /// # Errors
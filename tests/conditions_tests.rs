@@ -191,6 +191,26 @@ fn three_turn_valued_condition_duration_reduced_to_2_turns_after_character_end_o
     }
 }
 
+#[test]
+fn three_round_condition_duration_reduced_to_2_rounds_after_character_end_of_turn() {
+    let mut cm = ConditionManager::new();
+    let blinded = Condition::builder()
+        .condition(NonValuedCondition::Blinded)
+        .term(NonValuedTerm::For(Duration::from_rounds(3)))
+        .build();
+
+    let alice = ChrName::new("Alice");
+
+    cm.add_condition(alice.clone(), blinded.clone());
+
+    cm.end_of_turn(alice.clone());
+    match cm.get_conditions(&alice).get(&blinded) {
+        Some(Condition::NonValued { term: NonValuedTerm::For(dur), .. }) =>
+            assert_eq!(&Duration::from_rounds(2), dur),
+        _ => panic!(),
+    }
+}
+
 #[test]
 fn one_turn_nonvalued_condition_duration_removed_after_character_end_of_turn() {
     let mut cm = ConditionManager::new();
@@ -392,3 +412,213 @@ fn clumsy_reduced_end_of_next_turn_reduces_after_end_of_other_turn_and_then_affe
         _ => panic!()
     }
 }
+
+#[test]
+fn add_condition_many_adds_to_every_target() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Goblin 1", 20, false).build(),
+        Chr::builder("Goblin 2", 18, false).build(),
+        Chr::builder("Orc", 12, false).build(),
+        Chr::builder("Alice", 15, true).build(),
+    ]).build();
+
+    let targets = [ChrName::new("Goblin 1"), ChrName::new("Goblin 2"), ChrName::new("Orc")];
+    let frightened = Condition::builder().condition(ValuedCondition::Frightened).value(2).build();
+
+    t.add_condition_many(targets.iter().map(|target| (target.clone(), frightened.clone())).collect())?;
+
+    for target in &targets {
+        assert!(t.get_conditions(target).contains(&frightened));
+    }
+    assert!(!t.get_conditions(&ChrName::new("Alice")).contains(&frightened));
+
+    Ok(())
+}
+
+#[test]
+fn add_condition_many_gives_each_target_their_own_condition() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+        Chr::builder("Bob", 18, true).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let bob = ChrName::new("Bob");
+    let dazzled_until_end_of = |name: &ChrName| Condition::builder()
+        .condition(NonValuedCondition::Dazzled)
+        .term(NonValuedTerm::Until(TurnEvent::EndOfNextTurn(name.clone())))
+        .build();
+
+    t.add_condition_many(vec![(alice.clone(), dazzled_until_end_of(&alice)), (bob.clone(), dazzled_until_end_of(&bob))])?;
+
+    match t.get_conditions(&bob).get(&dazzled_until_end_of(&bob)) {
+        Some(Condition::NonValued { term: NonValuedTerm::Until(event), .. }) =>
+            assert_eq!(&TurnEvent::EndOfNextTurn(bob.clone()), event),
+        _ => panic!(),
+    }
+    match t.get_conditions(&alice).get(&dazzled_until_end_of(&alice)) {
+        Some(Condition::NonValued { term: NonValuedTerm::Until(event), .. }) =>
+            assert_eq!(&TurnEvent::EndOfNextTurn(alice.clone()), event),
+        _ => panic!(),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn add_condition_many_keeps_term_referring_to_first_target() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+        Chr::builder("Bob", 18, true).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let bob = ChrName::new("Bob");
+    let slowed = Condition::builder()
+        .condition(ValuedCondition::Slowed)
+        .value(1)
+        .term(ValuedTerm::Reduced(TurnEvent::StartOfNextTurn(alice.clone()), 1))
+        .build();
+
+    t.add_condition_many(vec![(alice.clone(), slowed.clone()), (bob.clone(), slowed.clone())])?;
+
+    for target in [&alice, &bob] {
+        match t.get_conditions(target).get(&slowed) {
+            Some(Condition::Valued { term: ValuedTerm::Reduced(event, _), .. }) =>
+                assert_eq!(&TurnEvent::StartOfNextTurn(alice.clone()), event),
+            _ => panic!(),
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn add_condition_many_with_unknown_target_adds_to_none() {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let nobody = ChrName::new("Nobody");
+    let blinded = Condition::builder().condition(NonValuedCondition::Blinded).build();
+
+    let res = t.add_condition_many(vec![(alice.clone(), blinded.clone()), (nobody.clone(), blinded.clone())]);
+
+    assert_eq!(Err(tracker::Error::ChangeNoneError(nobody)), res);
+    assert!(!t.get_conditions(&alice).contains(&blinded));
+}
+
+#[test]
+fn drained_lowers_max_health_and_current_health() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_health(Health::new(40)).with_level(3).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let drained = Condition::builder().condition(ValuedCondition::Drained).value(2).build();
+
+    t.add_condition(alice.clone(), drained)?;
+
+    let health = t.get_chr(&alice).unwrap().health.as_ref().unwrap();
+    assert_eq!(34, health.max);
+    assert_eq!(34, health.current);
+
+    Ok(())
+}
+
+#[test]
+fn raising_drained_loses_only_the_difference() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_health(Health::new(40)).with_level(3).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let drained_1 = Condition::builder().condition(ValuedCondition::Drained).value(1).build();
+    let drained_2 = Condition::builder().condition(ValuedCondition::Drained).value(2).build();
+
+    t.add_condition(alice.clone(), drained_1)?;
+    t.add_condition(alice.clone(), drained_2)?;
+
+    let health = t.get_chr(&alice).unwrap().health.as_ref().unwrap();
+    assert_eq!(34, health.max);
+    assert_eq!(34, health.current);
+
+    Ok(())
+}
+
+#[test]
+fn removing_drained_restores_max_health_only() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_health(Health::new(40)).with_level(3).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let drained = Condition::builder().condition(ValuedCondition::Drained).value(2).build();
+
+    t.add_condition(alice.clone(), drained.clone())?;
+    t.rm_condition(&alice, &drained);
+
+    let health = t.get_chr(&alice).unwrap().health.as_ref().unwrap();
+    assert_eq!(40, health.max);
+    assert_eq!(34, health.current);
+
+    Ok(())
+}
+
+#[test]
+fn drained_expiring_restores_max_health() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_health(Health::new(40)).with_level(2).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let drained = Condition::builder()
+        .condition(ValuedCondition::Drained)
+        .value(1)
+        .term(ValuedTerm::For(Duration::from_turns(1)))
+        .build();
+
+    t.end_turn()?;
+    t.add_condition(alice.clone(), drained)?;
+    assert_eq!(38, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+
+    t.end_turn()?;
+    assert_eq!(40, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+
+    Ok(())
+}
+
+#[test]
+fn setting_level_applies_existing_drained() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_health(Health::new(40)).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let drained = Condition::builder().condition(ValuedCondition::Drained).value(2).build();
+
+    t.add_condition(alice.clone(), drained)?;
+    assert_eq!(40, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+
+    t.set_level(&alice, Some(4))?;
+    assert_eq!(32, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+
+    Ok(())
+}
+
+#[test]
+fn adding_higher_value_raises_condition() {
+    let mut cm = ConditionManager::new();
+    let alice = ChrName::new("Alice");
+    let frightened_1 = Condition::builder().condition(ValuedCondition::Frightened).value(1).build();
+    let frightened_3 = Condition::builder().condition(ValuedCondition::Frightened).value(3).build();
+
+    cm.add_condition(alice.clone(), frightened_1);
+    cm.add_condition(alice.clone(), frightened_3.clone());
+
+    match cm.get_conditions(&alice).get(&frightened_3) {
+        Some(Condition::Valued { level, .. }) => assert_eq!(3, *level),
+        _ => panic!(),
+    }
+}
@@ -0,0 +1,54 @@
+use pathtracker_rust::duration::Duration;
+use proptest::prelude::*;
+
+fn any_duration() -> impl Strategy<Value = Duration> {
+    (0..100u32, 0..100u32, 0..100u32, 0..1000u32, 0..100u32, 0..1000u32, 0..100u32)
+        .prop_map(|(seconds, actions, turns, minutes, hours, days, rounds)| Duration::builder()
+            .with_seconds(seconds)
+            .with_actions(actions)
+            .with_turns(turns)
+            .with_minutes(minutes)
+            .with_hours(hours)
+            .with_days(days)
+            .with_rounds(rounds)
+            .build())
+}
+
+proptest! {
+    #[test]
+    fn normalizing_keeps_the_time(seconds in 0..100_000u32, turns in 0..100_000u32, days in 0..1000u32) {
+        let dur = Duration::builder().with_seconds(seconds).with_turns(turns).with_days(days).build();
+        let expected = u64::from(seconds) + 6 * u64::from(turns) + 86_400 * u64::from(days);
+        prop_assert_eq!(expected / 6, u64::from(dur.in_turns()));
+    }
+
+    #[test]
+    fn normalizing_is_idempotent(dur in any_duration()) {
+        let time = Duration::from_seconds(dur.in_seconds() - 6 * dur.in_rounds());
+        prop_assert_eq!(dur, time + Duration::from_rounds(dur.in_rounds()));
+    }
+
+    #[test]
+    fn display_parses_back(dur in any_duration()) {
+        prop_assert_eq!(Ok(dur), dur.to_string().parse());
+    }
+
+    #[test]
+    fn adding_then_subtracting_gives_back_duration(x in any_duration(), y in any_duration()) {
+        prop_assert_eq!(Some(x), x.checked_add(y).and_then(|sum| sum.checked_sub(y)));
+    }
+
+    #[test]
+    fn subtracting_never_gains_time(x in any_duration(), y in any_duration()) {
+        match x.checked_sub(y) {
+            Some(left) => prop_assert_eq!(x.in_seconds() - y.in_seconds(), left.in_seconds()),
+            None => prop_assert!(y.in_seconds() > x.in_seconds()),
+        }
+    }
+
+    #[test]
+    fn saturating_add_never_panics(days in any::<u32>(), rounds in any::<u32>()) {
+        let dur = Duration::builder().with_days(days).with_rounds(rounds).build();
+        prop_assert!((dur + dur).in_seconds() >= dur.in_seconds());
+    }
+}
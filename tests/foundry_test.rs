@@ -0,0 +1,171 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName, Health}, conditions::{Condition, DamageType, NonValuedCondition, ValuedCondition, ValuedTerm}, import::{self, foundry}, saver::NoSaver, tracker::{self, Tracker}};
+
+const COMBAT_PATH: &str = "tests/fixtures/foundry_combat.json";
+const COMBAT: &str = include_str!("fixtures/foundry_combat.json");
+
+fn export_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("pathtracker_foundry_{name}_{}.json", std::process::id()))
+}
+
+fn level_of(t: &Tracker<NoSaver>, name: &str, cond: &Condition) -> Option<u8> {
+    t.get_conditions(&ChrName::new(name)).into_iter()
+        .find(|other| *other == cond)
+        .and_then(|cond| match cond {
+            Condition::Valued { level, .. } => Some(*level),
+            Condition::NonValued { .. } => None,
+        })
+}
+
+#[test]
+fn import_foundry_reads_combatants() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    t.import_foundry(COMBAT_PATH)?;
+
+    let names: Vec<&str> = t.get_chrs().iter().map(|chr| chr.name.as_ref()).collect();
+    assert_eq!(vec!["Sarah", "Goblin Warrior", "Goblin Commando"], names);
+    assert_eq!(Some(ChrName::new("Goblin Warrior")), t.get_in_turn().map(|chr| chr.name.clone()));
+
+    let sarah = t.get_chr(&ChrName::new("Sarah")).unwrap();
+    assert!(sarah.player);
+    assert_eq!(21, sarah.init);
+    assert_eq!(Some(Health { current: 30, max: 44, temp: 5, drained: 0 }), sarah.health);
+    assert_eq!(Some(8), sarah.init_mod);
+    assert_eq!(Some(19), sarah.stats.as_ref().map(|stats| stats.ac));
+
+    let warrior = t.get_chr(&ChrName::new("Goblin Warrior")).unwrap();
+    assert!(!warrior.player);
+    assert_eq!(Some(-1), warrior.level);
+    assert_eq!(Some("Goblins"), warrior.group.as_deref());
+
+    Ok(())
+}
+
+#[test]
+fn import_foundry_maps_conditions() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    t.import_foundry(COMBAT_PATH)?;
+
+    let frightened = Condition::builder().condition(ValuedCondition::Frightened).value(1).build();
+    let bleed = Condition::builder().condition(ValuedCondition::PersistentDamage(DamageType::Bleed)).value(1).build();
+    let off_guard = Condition::builder().condition(NonValuedCondition::FlatFooted).build();
+    assert_eq!(Some(2), level_of(&t, "Sarah", &frightened));
+    assert_eq!(Some(2), level_of(&t, "Goblin Warrior", &bleed));
+    assert!(t.get_conditions(&ChrName::new("Goblin Warrior")).contains(&off_guard));
+    assert_eq!(1, t.get_conditions(&ChrName::new("Goblin Commando")).len());
+
+    Ok(())
+}
+
+#[test]
+fn import_foundry_is_one_change() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![Chr::builder("Bob", 10, true).build()]).build();
+
+    t.import_foundry(COMBAT_PATH)?;
+    assert!(t.get_chr(&ChrName::new("Bob")).is_none());
+
+    t.undo()?;
+    assert_eq!(1, t.get_chrs().len());
+    assert!(t.get_chr(&ChrName::new("Bob")).is_some());
+
+    Ok(())
+}
+
+#[test]
+fn foundry_round_trip_keeps_document() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+    t.import_foundry(COMBAT_PATH).unwrap();
+
+    let path = export_path("round_trip");
+    t.export_foundry(&path).unwrap();
+    let exported = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let expected: serde_json::Value = serde_json::from_str(COMBAT).unwrap();
+    let actual: serde_json::Value = serde_json::from_str(&exported).unwrap();
+    assert_eq!(expected, actual);
+}
+
+#[test]
+fn foundry_round_trip_keeps_tracker() {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Sarah", 21, true).with_health(Health::new(44)).with_level(3).build(),
+        Chr::builder("Orc", 14, false).with_health(Health::new(30)).with_group("Orcs").build(),
+    ]).build();
+    t.end_turn().unwrap();
+    t.add_condition(ChrName::new("Orc"), Condition::builder()
+        .condition(ValuedCondition::Stupified)
+        .value(3)
+        .term(ValuedTerm::Manual)
+        .build()).unwrap();
+    t.add_condition(ChrName::new("Sarah"), Condition::builder()
+        .condition(ValuedCondition::Drained)
+        .value(2)
+        .build()).unwrap();
+
+    let path = export_path("tracker");
+    t.export_foundry(&path).unwrap();
+    let mut imported: Tracker<NoSaver> = Tracker::default();
+    imported.import_foundry(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(t.get_chrs(), imported.get_chrs());
+    assert_eq!(t.get_in_turn(), imported.get_in_turn());
+    let stupified = Condition::builder().condition(ValuedCondition::Stupified).value(1).build();
+    assert_eq!(Some(3), level_of(&imported, "Orc", &stupified));
+}
+
+#[test]
+fn import_foundry_keeps_drained_max_health() {
+    let path = export_path("drained");
+    let json = COMBAT.replacen("\"slug\": \"frightened\"", "\"slug\": \"drained\"", 1);
+    std::fs::write(&path, json).unwrap();
+
+    let mut t: Tracker<NoSaver> = Tracker::default();
+    t.import_foundry(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let sarah = ChrName::new("Sarah");
+    assert_eq!(Some(Health { current: 30, max: 44, temp: 5, drained: 6 }), t.get_chr(&sarah).unwrap().health);
+
+    let drained = Condition::builder().condition(ValuedCondition::Drained).value(2).build();
+    t.rm_condition(&sarah, &drained);
+    assert_eq!(Some(50), t.get_chr(&sarah).unwrap().health.as_ref().map(|health| health.max));
+}
+
+#[test]
+fn import_foundry_skips_unknown_conditions() {
+    let path = export_path("unknown");
+    let json = COMBAT.replacen("\"slug\": \"unconscious\"", "\"slug\": \"encouraged\"", 1);
+    std::fs::write(&path, json).unwrap();
+
+    let mut t: Tracker<NoSaver> = Tracker::default();
+    t.import_foundry(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(t.get_conditions(&ChrName::new("Goblin Commando")).is_empty());
+}
+
+#[test]
+fn import_foundry_with_huge_persistent_damage_uses_value_1() {
+    let path = export_path("huge");
+    let json = COMBAT.replacen("\"formula\": \"2\"", "\"formula\": \"4000000000d4000000000\"", 1);
+    std::fs::write(&path, json).unwrap();
+
+    let mut t: Tracker<NoSaver> = Tracker::default();
+    t.import_foundry(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let bleed = Condition::builder().condition(ValuedCondition::PersistentDamage(DamageType::Bleed)).value(1).build();
+    assert_eq!(Some(1), level_of(&t, "Goblin Warrior", &bleed));
+}
+
+#[test]
+fn foundry_invalid_json_gives_error() {
+    let res = foundry::parse("{\"round\": 1}", "combat.json");
+
+    assert!(matches!(res, Err(import::Error::InvalidJson(..))));
+}
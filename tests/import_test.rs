@@ -0,0 +1,105 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{bestiary::{Resistance, Saves}, character::{Chr, ChrName, Health, Stats}, conditions::{Condition, NonValuedCondition}, import::{self, pathbuilder}, saver::NoSaver, tracker::{self, Tracker}};
+
+const SARAH: &str = include_str!("fixtures/pathbuilder_sarah.json");
+
+#[test]
+fn pathbuilder_import_reads_stats() {
+    let chr = pathbuilder::parse(SARAH, "sarah.json").unwrap();
+
+    assert_eq!(ChrName::new("Sarah"), chr.name);
+    assert!(chr.player);
+    assert_eq!(Some(3), chr.level);
+    assert_eq!(Some(Health::new(44)), chr.health);
+    assert_eq!(Some(8), chr.init_mod);
+    assert_eq!(Some(Stats {
+        ac: 19,
+        saves: Saves { fortitude: 9, reflex: 10, will: 7 },
+        perception: 8,
+        resistances: vec![Resistance { kind: "fire".into(), value: 3 }],
+        weaknesses: vec![],
+    }), chr.stats);
+}
+
+#[test]
+fn pathbuilder_unsuccessful_export_gives_error() {
+    let json = SARAH.replacen("\"success\": true", "\"success\": false", 1);
+
+    let res = pathbuilder::parse(&json, "sarah.json");
+
+    assert!(matches!(res, Err(import::Error::UnsuccessfulExport(_))));
+}
+
+#[test]
+fn pathbuilder_invalid_json_gives_error() {
+    let res = pathbuilder::parse("{\"success\": true}", "sarah.json");
+
+    assert!(matches!(res, Err(import::Error::InvalidJson(..))));
+}
+
+#[test]
+fn pathbuilder_huge_max_hp_gives_error() {
+    let json = SARAH
+        .replacen("\"level\": 3", "\"level\": 2000000000", 1)
+        .replacen("\"ancestryhp\": 8", "\"ancestryhp\": 4294967295", 1);
+
+    let res = pathbuilder::parse(&json, "sarah.json");
+
+    assert!(matches!(res, Err(import::Error::InvalidField { .. })));
+}
+
+#[test]
+fn import_pathbuilder_adds_player() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let name = t.import_pathbuilder("tests/fixtures/pathbuilder_sarah.json")?;
+
+    assert_eq!(ChrName::new("Sarah"), name);
+    assert!(t.get_chr(&name).unwrap().player);
+
+    Ok(())
+}
+
+#[test]
+fn import_missing_file_gives_error() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let res = t.import_pathbuilder("tests/fixtures/no_such_export.json");
+
+    assert!(matches!(res, Err(tracker::Error::ImportError(import::Error::ReadError(..)))));
+}
+
+#[test]
+fn reimport_updates_in_place() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Sarah", 17, true).with_health(Health::new(44)).with_level(3).build(),
+    ]).build();
+    let name = ChrName::new("Sarah");
+    t.damage(&name, 10)?;
+    t.add_condition(name.clone(), Condition::builder().condition(NonValuedCondition::Blinded).build())?;
+
+    let leveled = SARAH.replacen("\"level\": 3", "\"level\": 4", 1);
+    t.add_or_update_chr(pathbuilder::parse(&leveled, "sarah.json")?)?;
+
+    let sarah = t.get_chr(&name).unwrap();
+    assert_eq!(1, t.get_chrs().len());
+    assert_eq!(17, sarah.init);
+    assert_eq!(Some(4), sarah.level);
+    assert_eq!(Some(56), sarah.health.as_ref().map(|health| health.max));
+    assert_eq!(Some(46), sarah.health.as_ref().map(|health| health.current));
+    assert_eq!(1, t.get_conditions(&name).len());
+
+    Ok(())
+}
+
+#[test]
+fn import_over_enemy_by_same_name_gives_error() {
+    let enemy = Chr::builder("Sarah", 12, false).with_health(Health::new(20)).build();
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![enemy.clone()]).build();
+
+    let res = t.import_pathbuilder("tests/fixtures/pathbuilder_sarah.json");
+
+    assert_eq!(Err(tracker::Error::AddDupError(ChrName::new("Sarah"))), res);
+    assert_eq!(vec![enemy], t.get_chrs());
+}
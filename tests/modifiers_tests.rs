@@ -0,0 +1,55 @@
+use pathtracker_rust::{character::{Chr, ChrName}, conditions::{Condition, NonValuedCondition, ValuedCondition}, modifiers::{Attribute, Statistic}, saver::NoSaver, tracker::{self, Tracker}};
+
+#[test]
+fn tracker_modifier_of_character_without_conditions_is_zero() {
+    let t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+
+    assert_eq!(0, t.get_modifier(&alice, Statistic::ArmorClass));
+    assert!(t.get_modifiers(&alice).is_empty());
+}
+
+#[test]
+fn tracker_modifiers_only_worst_status_penalty_applies() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let frightened = Condition::builder().condition(ValuedCondition::Frightened).value(1).build();
+    let clumsy = Condition::builder().condition(ValuedCondition::Clumsy).value(2).build();
+    let off_guard = Condition::builder().condition(NonValuedCondition::FlatFooted).build();
+
+    t.add_condition(alice.clone(), frightened)?;
+    t.add_condition(alice.clone(), clumsy)?;
+    t.add_condition(alice.clone(), off_guard)?;
+
+    let modifiers = t.get_modifiers(&alice);
+    assert_eq!(-4, modifiers.get(Statistic::ArmorClass));
+    assert_eq!(-2, modifiers.get(Statistic::Reflex));
+    assert_eq!(-1, modifiers.get(Statistic::Will));
+    assert_eq!(-2, modifiers.get(Statistic::Attack(Attribute::Dexterity)));
+    assert_eq!(-1, modifiers.get(Statistic::Attack(Attribute::Strength)));
+
+    Ok(())
+}
+
+#[test]
+fn tracker_modifiers_follow_reduced_condition() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+    ]).build();
+
+    let alice = ChrName::new("Alice");
+    let sickened = Condition::builder().condition(ValuedCondition::Sickened).value(2).build();
+    t.add_condition(alice.clone(), sickened.clone())?;
+    assert_eq!(-2, t.get_modifier(&alice, Statistic::Perception));
+
+    t.rm_condition(&alice, &sickened);
+    assert_eq!(0, t.get_modifier(&alice, Statistic::Perception));
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName, Health}, gui::protocol::{self, ErrorCode, Message}, saver::NoSaver, tracker::Tracker};
+use serde_json::{json, Value};
+
+fn tracker() -> Tracker<NoSaver> {
+    Tracker::builder().with_chrs(vec![
+        Chr::builder("Sarah", 21, true).with_health(Health::new(44)).build(),
+        Chr::builder("Goblin", 15, false).with_health(Health::new(6)).build(),
+    ]).build()
+}
+
+fn handle(t: &mut Tracker<NoSaver>, request: &Value) -> Vec<Value> {
+    protocol::handle(t, &request.to_string()).iter()
+        .map(|msg| serde_json::to_value(msg).unwrap())
+        .collect()
+}
+
+#[test]
+fn damage_answers_with_state() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "id": 1, "op": "damage", "name": "Goblin", "amount": 5 }));
+
+    assert_eq!(1, msgs.len());
+    assert_eq!("result", msgs[0]["type"]);
+    assert_eq!(1, msgs[0]["id"]);
+    assert_eq!(1, msgs[0]["state"]["chrs"][1]["health"]["current"]);
+    assert_eq!(Some(1), t.get_chr(&ChrName::new("Goblin")).unwrap().health.as_ref().map(|health| health.current));
+}
+
+#[test]
+fn unknown_character_gives_error_code() {
+    let mut t = tracker();
+
+    let msgs = protocol::handle(&mut t, r#"{"id":"a","op":"heal","name":"Orc","amount":5}"#);
+
+    match &msgs[..] {
+        [Message::Error { id, code, .. }] => {
+            assert_eq!(&Some(json!("a")), id);
+            assert_eq!(&ErrorCode::NoSuchCharacter, code);
+        },
+        other => panic!("expected an error, got {other:?}"),
+    }
+}
+
+#[test]
+fn invalid_request_keeps_id() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "id": 7, "op": "explode" }));
+
+    assert_eq!(json!({ "type": "error", "id": 7, "code": "invalid_request", "message": msgs[0]["message"] }), msgs[0]);
+}
+
+#[test]
+fn end_turn_pushes_turn_event() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "op": "end_turn" }));
+
+    assert_eq!(2, msgs.len());
+    assert_eq!("Sarah", msgs[0]["value"]);
+    assert_eq!(json!({ "type": "turn", "slot": ["Sarah"] }), msgs[1]);
+}
+
+#[test]
+fn state_pushes_no_event() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "op": "state" }));
+
+    assert_eq!(1, msgs.len());
+    assert_eq!(2, msgs[0]["state"]["chrs"].as_array().unwrap().len());
+}
+
+#[test]
+fn add_condition_takes_saved_form() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({
+        "op": "add_condition",
+        "names": ["Goblin"],
+        "condition": { "Valued": { "cond": "Frightened", "term": "Manual", "level": 2 } },
+    }));
+
+    assert_eq!("frightened 2", msgs[0]["state"]["chrs"][1]["conditions"][0]);
+}
+
+#[test]
+fn add_many_gives_names() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "op": "add", "name": "Wolf", "init": 12, "health": 24, "count": 2 }));
+
+    assert_eq!(json!(["Wolf", "Wolf 2"]), msgs[0]["value"]);
+}
+
+#[test]
+fn advance_time_takes_units() {
+    let mut t = tracker();
+
+    handle(&mut t, &json!({ "op": "advance_time", "duration": { "hours": 1, "minutes": 30 } }));
+
+    assert_eq!(90, t.get_clock().in_minutes());
+}
+
+#[test]
+fn command_runs_terminal_syntax() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "op": "command", "text": "cond add prone on Sarah" }));
+    assert_eq!("prone", msgs[0]["state"]["chrs"][0]["conditions"][0]);
+
+    let msgs = handle(&mut t, &json!({ "op": "command", "text": "cond add" }));
+    assert_eq!("invalid_command", msgs[0]["code"]);
+}
+
+#[test]
+fn undo_without_changes_gives_error_code() {
+    let mut t = tracker();
+
+    let msgs = handle(&mut t, &json!({ "op": "undo" }));
+
+    assert_eq!("nothing_to_undo", msgs[0]["code"]);
+}
+
+#[test]
+fn run_answers_each_line() {
+    let mut t = tracker();
+    let input = "{\"op\":\"end_turn\"}\n\n{\"op\":\"threat\"}\n";
+    let mut output = Vec::new();
+
+    protocol::run(&mut t, input.as_bytes(), &mut output).unwrap();
+
+    let lines: Vec<Value> = String::from_utf8(output).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(vec!["result", "turn", "result"], lines.iter().map(|line| line["type"].as_str().unwrap()).collect::<Vec<_>>());
+}
@@ -0,0 +1,94 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::ChrName, conditions::CondFormat, gui::{order::Order, terminalgui::script}, saver::NoSaver, tracker::Tracker};
+
+const AMBUSH: &str = include_str!("fixtures/ambush.script");
+
+#[test]
+fn script_sets_up_encounter() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let outcome = script::run(&mut t, AMBUSH, false);
+
+    assert!(outcome.is_ok(), "{:?}", outcome.errors);
+    assert_eq!(5, t.get_chrs().len());
+    assert_eq!(Some(2), t.get_chr(&ChrName::new("Goblin")).unwrap().health.as_ref().map(|health| health.current));
+    assert_eq!(1, t.get_conditions(&ChrName::new("Goblin 2")).len());
+    assert_eq!(Some("Sarah"), t.get_in_turn().map(|chr| chr.name.as_ref()));
+}
+
+#[test]
+fn script_stops_at_first_error() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let outcome = script::run(&mut t, "add 21 Sarah\nadd Bob\n\nadd 17 Bob", false);
+
+    assert_eq!(vec![2], outcome.errors.iter().map(|err| err.line).collect::<Vec<_>>());
+    assert_eq!(1, t.get_chrs().len());
+}
+
+#[test]
+fn script_keeps_going_past_errors() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let outcome = script::run(&mut t, "add 21 Sarah\nadd Bob\n# comment\nfoo\nadd 17 Bob", true);
+
+    assert_eq!(vec![2, 4], outcome.errors.iter().map(|err| err.line).collect::<Vec<_>>());
+    assert_eq!(2, t.get_chrs().len());
+}
+
+#[test]
+fn script_condition_on_many_waits_for_each_target_unless_named() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let outcome = script::run(&mut t, "add 21 Sarah\nadd 17 Bob\n\
+        cond add dazzled until end of turn on Sarah, Bob\n\
+        cond add slowed 1 until end of Sarah turn on Sarah, Bob", false);
+
+    assert!(outcome.is_ok(), "{:?}", outcome.errors);
+    let events = |name: &str| -> Vec<String> {
+        let mut events: Vec<String> = t.get_conditions(&ChrName::new(name)).iter()
+            .map(|cond| cond.to_string(CondFormat::default()))
+            .collect();
+        events.sort();
+        events
+    };
+    assert_eq!(vec!["dazzled until end of next turn of Bob", "slowed 1 until end of next turn of Sarah"], events("Bob"));
+    assert_eq!(vec!["dazzled until end of next turn of Sarah", "slowed 1 until end of next turn of Sarah"], events("Sarah"));
+}
+
+#[test]
+fn script_gives_messages() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let outcome = script::run(&mut t, "help undo", false);
+
+    assert_eq!(1, outcome.messages.len());
+    assert!(outcome.messages[0].starts_with("undo"));
+}
+
+#[test]
+fn order_text_lists_characters() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+    script::run(&mut t, AMBUSH, false);
+
+    let text = Order::new(&t).to_string();
+
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(7, lines.len());
+    assert!(lines[1].starts_with(">   21 * Sarah"));
+    assert!(lines[3].contains("2/6"));
+}
+
+#[test]
+fn order_json_has_conditions() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+    script::run(&mut t, AMBUSH, false);
+
+    let json = serde_json::to_value(Order::new(&t)).unwrap();
+
+    assert_eq!("Goblin 2", json["chrs"][3]["name"]);
+    assert_eq!("frightened 1", json["chrs"][3]["conditions"][0]);
+    assert_eq!(true, json["chrs"][0]["in_turn"]);
+    assert_eq!(6, json["chrs"][2]["health"]["max"]);
+}
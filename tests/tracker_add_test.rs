@@ -1,5 +1,5 @@
 use pathtracker_rust::{
-    character::{Chr, ChrName}, saver::NoSaver, tracker::{self, Tracker}
+    character::{Chr, ChrName, Health}, saver::NoSaver, tracker::{self, Tracker}
 };
 
 #[test]
@@ -77,3 +77,79 @@ fn add_twice() -> tracker::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn add_many_numbers_names() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let names = t.add_many(Chr::builder("Wolf", 15, false).build(), 3)?;
+
+    assert_eq!(vec![ChrName::new("Wolf"), ChrName::new("Wolf 2"), ChrName::new("Wolf 3")], names);
+    assert_eq!(3, t.get_chrs().len());
+
+    Ok(())
+}
+
+#[test]
+fn add_many_up_to_max_count() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    t.add_many(Chr::builder("Wolf", 15, false).build(), tracker::MAX_COUNT)?;
+
+    assert_eq!(usize::try_from(tracker::MAX_COUNT).ok(), Some(t.get_chrs().len()));
+
+    Ok(())
+}
+
+#[test]
+fn add_many_over_max_count_fails() {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    let res = t.add_many(Chr::builder("Wolf", 15, false).build(), 4_000_000_000);
+
+    assert_eq!(Err(tracker::Error::TooManyError(4_000_000_000)), res);
+    assert!(t.get_chrs().is_empty());
+    assert_eq!(Err(tracker::Error::UndoNothingError), t.undo());
+}
+
+#[test]
+fn add_many_skips_taken_names() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Wolf", 15, false).build(),
+        Chr::builder("Wolf 3", 15, false).build(),
+    ]).build();
+
+    let names = t.add_many(Chr::builder("Wolf", 15, false).build(), 2)?;
+
+    assert_eq!(vec![ChrName::new("Wolf 2"), ChrName::new("Wolf 4")], names);
+
+    Ok(())
+}
+
+#[test]
+fn add_many_with_gives_each_own_init_and_health() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    t.add_many_with(Chr::builder("Wolf", 15, false).build(), 2, |i, chr| {
+        chr.init = 10 + i32::try_from(i).unwrap_or(0);
+        chr.set_health(Health::new(20 + i));
+    })?;
+
+    let wolf_2 = t.get_chr(&ChrName::new("Wolf 2"));
+    assert_eq!(Some(11), wolf_2.map(|chr| chr.init));
+    assert_eq!(Some(21), wolf_2.and_then(|chr| chr.health.as_ref()).map(|health| health.max));
+
+    Ok(())
+}
+
+#[test]
+fn add_many_is_one_undo_step() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::default();
+
+    t.add_many(Chr::builder("Wolf", 15, false).build(), 4)?;
+    t.undo()?;
+
+    assert!(t.get_chrs().is_empty());
+
+    Ok(())
+}
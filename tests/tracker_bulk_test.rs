@@ -0,0 +1,150 @@
+#![allow(clippy::unwrap_used)]
+use pathtracker_rust::{character::{Chr, ChrName, Health}, conditions::{Condition, NonValuedCondition}, saver::NoSaver, tracker::{self, Tracker}};
+
+fn tracker() -> Tracker<NoSaver> {
+    Tracker::builder().with_chrs(vec![
+        Chr::builder("Goblin 1", 20, false).with_health(Health::new(10)).build(),
+        Chr::builder("Goblin 2", 18, false).with_health(Health::new(10)).build(),
+        Chr::builder("Alice", 15, false).with_health(Health::new(20)).build(),
+    ]).build()
+}
+
+fn current_health(t: &Tracker<NoSaver>, name: &str) -> u32 {
+    t.get_chr(&ChrName::new(name)).unwrap().health.as_ref().unwrap().current
+}
+
+#[test]
+fn damage_many_damages_every_target_in_one_undo_step() -> tracker::Result<()> {
+    let mut t = tracker();
+    let before = t.clone();
+
+    t.damage_many(&[ChrName::new("Goblin 1"), ChrName::new("Goblin 2")], 4)?;
+
+    assert_eq!(6, current_health(&t, "Goblin 1"));
+    assert_eq!(6, current_health(&t, "Goblin 2"));
+    assert_eq!(20, current_health(&t, "Alice"));
+
+    t.undo()?;
+    assert_eq!(before.get_chrs(), t.get_chrs());
+
+    Ok(())
+}
+
+#[test]
+fn heal_many_heals_every_target() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.damage_many(&[ChrName::new("Goblin 1"), ChrName::new("Alice")], 8)?;
+
+    t.heal_many(&[ChrName::new("Goblin 1"), ChrName::new("Alice")], 5)?;
+
+    assert_eq!(7, current_health(&t, "Goblin 1"));
+    assert_eq!(17, current_health(&t, "Alice"));
+
+    Ok(())
+}
+
+#[test]
+fn damage_many_with_missing_target_changes_nothing() {
+    let mut t = tracker();
+
+    let res = t.damage_many(&[ChrName::new("Goblin 1"), ChrName::new("Bob")], 4);
+
+    assert_eq!(Err(tracker::Error::ChangeNoneError(ChrName::new("Bob"))), res);
+    assert_eq!(10, current_health(&t, "Goblin 1"));
+    assert_eq!(Err(tracker::Error::UndoNothingError), t.undo());
+}
+
+#[test]
+fn failed_bulk_actions_leave_nothing_to_undo() {
+    let mut t = tracker();
+    let missing = [ChrName::new("Goblin 1"), ChrName::new("Bob")];
+
+    assert!(t.heal_many(&missing, 4).is_err());
+    assert!(t.set_player_many(&missing, true).is_err());
+    assert_eq!(Err(tracker::Error::RmNoneError(ChrName::new("Bob"))), t.rm_chrs(&missing));
+
+    assert_eq!(Err(tracker::Error::UndoNothingError), t.undo());
+}
+
+#[test]
+fn rm_chrs_removes_every_target_in_one_undo_step() -> tracker::Result<()> {
+    let mut t = tracker();
+    let before = t.clone();
+
+    t.rm_chrs(&[ChrName::new("Goblin 1"), ChrName::new("Alice")])?;
+
+    let names: Vec<&ChrName> = t.get_chrs().iter().map(|chr| &chr.name).collect();
+    assert_eq!(vec![&ChrName::new("Goblin 2")], names);
+
+    t.undo()?;
+    assert_eq!(before.get_chrs(), t.get_chrs());
+
+    Ok(())
+}
+
+#[test]
+fn rm_chrs_removes_group_when_the_rest_is_down() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Wolf 1", 12, false).with_group("Wolf").with_health(Health::new(10)).build(),
+        Chr::builder("Wolf 2", 12, false).with_group("Wolf").with_health(Health::new(10)).build(),
+        Chr::builder("Wolf 3", 12, false).with_group("Wolf").with_health(Health::new(10)).build(),
+        Chr::builder("Alice", 15, true).build(),
+    ]).build();
+    t.damage(&ChrName::new("Wolf 3"), 10)?;
+
+    t.rm_chrs(&[ChrName::new("Wolf 1"), ChrName::new("Wolf 2")])?;
+
+    assert!(t.get_group("Wolf").is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn set_player_many_marks_every_target_in_one_undo_step() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.set_player_many(&[ChrName::new("Goblin 2"), ChrName::new("Alice")], true)?;
+
+    assert!(t.get_chr(&ChrName::new("Goblin 2")).unwrap().player);
+    assert!(t.get_chr(&ChrName::new("Alice")).unwrap().player);
+    assert!(!t.get_chr(&ChrName::new("Goblin 1")).unwrap().player);
+
+    t.undo()?;
+    assert!(t.get_chrs().iter().all(|chr| !chr.player));
+
+    Ok(())
+}
+
+#[test]
+fn set_player_many_keeps_the_turn_on_the_same_character() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Bob", 10, false).build(),
+        Chr::builder("Carl", 10, false).build(),
+        Chr::builder("Dana", 5, false).build(),
+    ]).build();
+    while t.get_in_turn().is_none_or(|chr| chr.name != ChrName::new("Carl")) {
+        t.end_turn()?;
+    }
+
+    t.set_player_many(&[ChrName::new("Bob")], true)?;
+
+    assert_eq!(Some(&ChrName::new("Carl")), t.get_in_turn().map(|chr| &chr.name));
+    t.undo()?;
+    assert!(t.get_chrs().iter().all(|chr| !chr.player));
+
+    Ok(())
+}
+
+#[test]
+fn add_condition_many_is_one_undo_step() -> tracker::Result<()> {
+    let mut t = tracker();
+    let prone = Condition::builder().condition(NonValuedCondition::Prone).build();
+
+    t.add_condition_many(vec![(ChrName::new("Goblin 1"), prone.clone()), (ChrName::new("Goblin 2"), prone.clone())])?;
+    t.undo()?;
+
+    assert!(!t.get_conditions(&ChrName::new("Goblin 1")).contains(&prone));
+    assert!(!t.get_conditions(&ChrName::new("Goblin 2")).contains(&prone));
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName}, encounter::{Encounter, Party, Threat}, saver::NoSaver, tracker::{self, Tracker}};
+
+fn party() -> Vec<Chr> {
+    (1..=4).map(|i| Chr::builder(format!("Player {i}"), 20, true).with_level(3).build()).collect()
+}
+
+#[test]
+fn tracker_without_leveled_players_has_no_threat() {
+    let t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).build(),
+        Chr::builder("Orc", 10, false).with_level(3).build(),
+    ]).build();
+
+    assert_eq!(None, t.get_threat());
+}
+
+#[test]
+fn threat_rates_enemies_against_party() {
+    let mut chrs = party();
+    chrs.push(Chr::builder("Ogre", 10, false).with_level(5).build());
+    chrs.push(Chr::builder("Goblin", 10, false).with_level(1).build());
+    let t: Tracker<NoSaver> = Tracker::builder().with_chrs(chrs).build();
+
+    let rating = t.get_threat().unwrap();
+
+    assert_eq!(Party { level: 3, size: 4 }, rating.party);
+    assert_eq!(100, rating.xp);
+    assert_eq!(Threat::Moderate, rating.threat);
+}
+
+#[test]
+fn threat_updates_with_tracker() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(party()).build();
+    assert_eq!(Threat::Trivial, t.get_threat().unwrap().threat);
+
+    t.add_many(Chr::builder("Wolf", 10, false).with_level(3).build(), 3)?;
+
+    assert_eq!(Threat::Severe, t.get_threat().unwrap().threat);
+
+    Ok(())
+}
+
+#[test]
+fn planned_encounter_rates_every_creature() {
+    let encounter = Encounter::new()
+        .with(Chr::builder("Wolf", 0, false).with_level(3).build(), 2);
+
+    let rating = encounter.rate(Party { level: 3, size: 4 });
+
+    assert_eq!(80, rating.xp);
+    assert_eq!(Threat::Moderate, rating.threat);
+}
+
+#[test]
+fn add_encounter_adds_every_creature_as_one_change() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_seed(1).with_chrs(party()).build();
+    let encounter = Encounter::new()
+        .with(Chr::builder("Wolf", 0, false).with_level(1).with_init_mod(7).build(), 2)
+        .with(Chr::builder("Wolf", 0, false).with_level(3).build(), 1);
+
+    let names = t.add_encounter(&encounter)?;
+
+    assert_eq!(vec![ChrName::new("Wolf"), ChrName::new("Wolf 2"), ChrName::new("Wolf 3")], names);
+    assert!(names.iter().all(|name| t.get_chr(name).is_some()));
+    assert!((8..=27).contains(&t.get_chr(&ChrName::new("Wolf")).unwrap().init));
+
+    t.undo()?;
+    assert_eq!(4, t.get_chrs().len());
+
+    Ok(())
+}
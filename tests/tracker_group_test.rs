@@ -0,0 +1,247 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName, Health}, conditions::{Condition, NonValuedCondition, NonValuedTerm, TurnEvent}, saver::NoSaver, tracker::{self, Error, MovedStatus, Tracker}};
+
+fn tracker() -> Tracker<NoSaver> {
+    Tracker::builder().with_chrs(vec![
+        Chr::builder("Bucky", 30, true).build(),
+        Chr::builder("Skelly Boy", 3, false).build(),
+    ]).build()
+}
+
+fn goblin() -> Chr {
+    Chr::builder("Goblin", 15, false).with_health(Health::new(6)).build()
+}
+
+fn names(chrs: &[Chr]) -> Vec<String> {
+    chrs.iter().map(|chr| chr.name.to_string()).collect()
+}
+
+#[test]
+fn add_group_numbers_members() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_group(goblin(), 3)?;
+
+    let members: Vec<String> = t.get_group("Goblin").iter().map(|chr| chr.name.to_string()).collect();
+    assert_eq!(vec!["Goblin 1", "Goblin 2", "Goblin 3"], members);
+    assert_eq!(3, t.get_slots().len());
+
+    Ok(())
+}
+
+#[test]
+fn add_group_twice_fails() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_group(goblin(), 3)?;
+
+    assert_eq!(Err(Error::AddDupError(ChrName::new("Goblin"))), t.add_group(goblin(), 2));
+
+    Ok(())
+}
+
+#[test]
+fn add_group_with_taken_member_name_fails() {
+    let mut t = tracker();
+    t.add_chr(Chr::builder("Goblin 2", 1, false).build()).unwrap();
+
+    assert_eq!(Err(Error::AddDupError(ChrName::new("Goblin 2"))), t.add_group(goblin(), 3));
+    assert!(t.get_group("Goblin").is_empty());
+}
+
+#[test]
+fn add_group_without_members_fails() {
+    let mut t = tracker();
+
+    assert_eq!(Err(Error::EmptyGroupError(ChrName::new("Goblin"))), t.add_group(goblin(), 0));
+    assert_eq!(2, t.get_chrs().len());
+}
+
+#[test]
+fn add_group_over_max_count_fails() {
+    let mut t = tracker();
+
+    assert_eq!(Err(Error::TooManyError(tracker::MAX_COUNT + 1)), t.add_group(goblin(), tracker::MAX_COUNT + 1));
+    assert!(t.get_group("Goblin").is_empty());
+}
+
+#[test]
+fn set_player_marks_the_whole_group() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 3)?;
+
+    t.set_player(&ChrName::new("Goblin 2"), true)?;
+
+    assert!(t.get_group("Goblin").iter().all(|chr| chr.player));
+    assert!(!t.get_chr(&ChrName::new("Skelly Boy")).unwrap().player);
+
+    Ok(())
+}
+
+#[test]
+fn group_members_share_a_turn() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 3)?;
+
+    t.end_turn()?;
+    t.end_turn()?;
+    assert_eq!(vec!["Goblin 1", "Goblin 2", "Goblin 3"], names(t.get_in_turn_slot()));
+
+    t.end_turn()?;
+    assert_eq!(vec!["Skelly Boy"], names(t.get_in_turn_slot()));
+
+    Ok(())
+}
+
+#[test]
+fn group_members_have_own_health() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 2)?;
+
+    t.damage(&ChrName::new("Goblin 1"), 4)?;
+
+    assert_eq!(2, t.get_chr(&ChrName::new("Goblin 1")).unwrap().health.as_ref().unwrap().current);
+    assert_eq!(6, t.get_chr(&ChrName::new("Goblin 2")).unwrap().health.as_ref().unwrap().current);
+
+    Ok(())
+}
+
+#[test]
+fn group_members_conditions_end_on_group_turn() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 2)?;
+    let goblin_2 = ChrName::new("Goblin 2");
+    let dazzled = Condition::builder()
+        .condition(NonValuedCondition::Dazzled)
+        .term(NonValuedTerm::Until(TurnEvent::StartOfNextTurn(goblin_2.clone())))
+        .build();
+
+    t.add_condition(goblin_2.clone(), dazzled.clone())?;
+    t.end_turn()?;
+    t.end_turn()?;
+
+    assert!(!t.get_conditions(&goblin_2).contains(&dazzled));
+
+    Ok(())
+}
+
+#[test]
+fn rm_member_keeps_rest_of_group() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 3)?;
+
+    t.rm_chr(&ChrName::new("Goblin 2"))?;
+
+    assert_eq!(2, t.get_group("Goblin").len());
+
+    Ok(())
+}
+
+#[test]
+fn rm_last_living_member_removes_slot() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 3)?;
+
+    t.damage(&ChrName::new("Goblin 1"), 6)?;
+    t.damage(&ChrName::new("Goblin 3"), 6)?;
+    t.rm_chr(&ChrName::new("Goblin 2"))?;
+
+    assert!(t.get_group("Goblin").is_empty());
+    assert_eq!(2, t.get_slots().len());
+
+    Ok(())
+}
+
+#[test]
+fn rm_first_member_in_turn_keeps_group_in_turn() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 3)?;
+    t.end_turn()?;
+    t.end_turn()?;
+
+    t.rm_chr(&ChrName::new("Goblin 1"))?;
+
+    assert_eq!(vec!["Goblin 2", "Goblin 3"], names(t.get_in_turn_slot()));
+
+    Ok(())
+}
+
+#[test]
+fn rm_group_in_turn_ends_turn() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 3)?;
+    t.end_turn()?;
+    t.end_turn()?;
+
+    t.rm_group("Goblin")?;
+
+    assert_eq!(vec!["Skelly Boy"], names(t.get_in_turn_slot()));
+
+    Ok(())
+}
+
+#[test]
+fn add_chr_with_same_init_does_not_split_group() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 2)?;
+
+    t.add_chr(Chr::builder("Wolf", 15, false).build())?;
+
+    assert_eq!(4, t.get_slots().len());
+
+    Ok(())
+}
+
+#[test]
+fn change_init_of_member_moves_group() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 2)?;
+
+    t.change_init(&ChrName::new("Goblin 2"), 40)?;
+
+    assert_eq!(vec!["Goblin 1", "Goblin 2"], names(t.get_slots()[0]));
+
+    Ok(())
+}
+
+#[test]
+fn group_moved_past_character_in_turn_gets_two_turns() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![Chr::builder("Bob", 10, true).build()]).build();
+    t.add_group(Chr::builder("Goblin", 20, false).build(), 3)?;
+    t.end_turn()?;
+    t.end_turn()?;
+
+    let moved = t.change_init(&ChrName::new("Goblin 1"), 5)?;
+
+    assert!(matches!(moved, Some(MovedStatus::TwoTurns(chr)) if chr.name == ChrName::new("Goblin 1")));
+    assert_eq!(vec!["Bob"], names(t.get_in_turn_slot()));
+
+    Ok(())
+}
+
+#[test]
+fn group_moved_before_character_in_turn_is_skipped() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_chrs(vec![Chr::builder("Bob", 10, true).build()]).build();
+    t.add_group(Chr::builder("Goblin", 5, false).build(), 3)?;
+    t.end_turn()?;
+
+    let moved = t.change_init(&ChrName::new("Goblin 1"), 20)?;
+
+    assert!(matches!(moved, Some(MovedStatus::Skipped(chr)) if chr.name == ChrName::new("Goblin 1")));
+    assert_eq!(vec!["Bob"], names(t.get_in_turn_slot()));
+
+    Ok(())
+}
+
+#[test]
+fn undo_add_group_removes_it() -> tracker::Result<()> {
+    let mut t = tracker();
+    t.add_group(goblin(), 2)?;
+
+    t.undo()?;
+
+    assert!(t.get_group("Goblin").is_empty());
+
+    Ok(())
+}
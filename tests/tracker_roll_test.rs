@@ -0,0 +1,94 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName}, dice::DiceExpr, saver::NoSaver, tracker::{self, Tracker}};
+
+fn tracker(seed: u64) -> Tracker<NoSaver> {
+    Tracker::builder().with_seed(seed).with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_init_mod(7).build(),
+        Chr::builder("Orc", 10, false).with_init_mod(-1).build(),
+        Chr::builder("Bat", 5, false).build(),
+    ]).build()
+}
+
+#[test]
+fn roll_initiative_adds_modifier_to_d20() -> tracker::Result<()> {
+    let mut t = tracker(1);
+
+    let rolls = t.roll_initiative(&[ChrName::new("Alice")])?;
+
+    let init = t.get_chr(&ChrName::new("Alice")).unwrap().init;
+    assert!((8..=27).contains(&init));
+    assert_eq!(rolls[0].1.total, init);
+
+    Ok(())
+}
+
+#[test]
+fn roll_initiative_missing_name_gives_error() {
+    let mut t = tracker(1);
+
+    let res = t.roll_initiative(&[ChrName::new("Alice"), ChrName::new("Carl")]);
+
+    assert_eq!(Err(tracker::Error::ChangeNoneError(ChrName::new("Carl"))), res.map(|_| ()));
+    assert_eq!(Err(tracker::Error::UndoNothingError), t.undo());
+}
+
+#[test]
+fn roll_enemy_initiative_skips_players() -> tracker::Result<()> {
+    let mut t = tracker(2);
+
+    let rolls = t.roll_enemy_initiative()?;
+
+    let names: Vec<ChrName> = rolls.into_iter().map(|(name, _)| name).collect();
+    assert_eq!(2, names.len());
+    assert!(!names.contains(&ChrName::new("Alice")));
+    assert_eq!(20, t.get_chr(&ChrName::new("Alice")).unwrap().init);
+
+    Ok(())
+}
+
+#[test]
+fn group_rolls_initiative_once() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder().with_seed(3).build();
+    t.add_group(Chr::builder("Goblin", 10, false).with_init_mod(2).build(), 4)?;
+
+    let rolls = t.roll_enemy_initiative()?;
+
+    assert_eq!(1, rolls.len());
+    let init = rolls[0].1.total;
+    assert!(t.get_group("Goblin").iter().all(|chr| chr.init == init));
+
+    Ok(())
+}
+
+#[test]
+fn roll_initiative_is_one_undo_step() -> tracker::Result<()> {
+    let mut t = tracker(4);
+    let before: Vec<Chr> = t.get_chrs().to_vec();
+
+    t.roll_enemy_initiative()?;
+    t.undo()?;
+
+    assert_eq!(before, t.get_chrs());
+
+    Ok(())
+}
+
+#[test]
+fn same_seed_rolls_the_same() -> tracker::Result<()> {
+    let mut t1 = tracker(5);
+    let mut t2 = tracker(5);
+
+    assert_eq!(t1.roll_enemy_initiative()?, t2.roll_enemy_initiative()?);
+
+    Ok(())
+}
+
+#[test]
+fn same_seed_rolls_the_same_dice() {
+    let mut t1 = tracker(5);
+    let mut t2 = tracker(5);
+
+    let expr: DiceExpr = "4d8 fire".parse().unwrap();
+    assert_eq!(t1.roll(&expr), t2.roll(&expr));
+}
@@ -0,0 +1,114 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName, Health}, conditions::{Condition, NonValuedCondition, NonValuedTerm, TurnEvent, ValuedCondition, ValuedTerm}, duration::Duration, roster::Roster, saver::NoSaver, tracker::{self, Tracker}};
+
+fn tracker() -> Tracker<NoSaver> {
+    Tracker::builder().with_chrs(vec![
+        Chr::builder("Sarah", 21, true).with_health(Health::new(44)).build(),
+        Chr::builder("Bob", 12, true).build(),
+        Chr::builder("Goblin", 15, false).with_health(Health::new(6)).build(),
+    ]).build()
+}
+
+#[test]
+fn new_encounter_removes_enemies() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.new_encounter(&[(ChrName::new("Sarah"), 8), (ChrName::new("Bob"), 17)])?;
+
+    let names: Vec<&str> = t.get_chrs().iter().map(|chr| chr.name.as_ref()).collect();
+    assert_eq!(vec!["Bob", "Sarah"], names);
+    assert_eq!(None, t.get_in_turn());
+
+    Ok(())
+}
+
+#[test]
+fn new_encounter_keeps_current_health() -> tracker::Result<()> {
+    let mut t = tracker();
+    let sarah = ChrName::new("Sarah");
+    t.damage(&sarah, 10)?;
+
+    t.new_encounter(&[(sarah.clone(), 8)])?;
+
+    assert_eq!(Some(34), t.get_chr(&sarah).unwrap().health.as_ref().map(|health| health.current));
+
+    Ok(())
+}
+
+#[test]
+fn new_encounter_keeps_only_long_conditions() -> tracker::Result<()> {
+    let mut t = tracker();
+    let sarah = ChrName::new("Sarah");
+    t.add_condition(sarah.clone(), Condition::builder().condition(ValuedCondition::Drained).value(1).build())?;
+    t.add_condition(sarah.clone(), Condition::builder()
+        .condition(NonValuedCondition::Fatigued)
+        .term(NonValuedTerm::For(Duration::from_hours(1)))
+        .build())?;
+    t.add_condition(sarah.clone(), Condition::builder()
+        .condition(ValuedCondition::Frightened)
+        .value(2)
+        .term(ValuedTerm::Reduced(TurnEvent::EndOfCurrentTurn(sarah.clone()), 1))
+        .build())?;
+    t.add_condition(sarah.clone(), Condition::builder()
+        .condition(NonValuedCondition::Dazzled)
+        .term(NonValuedTerm::For(Duration::from_rounds(2)))
+        .build())?;
+    t.add_condition(ChrName::new("Goblin"), Condition::builder().condition(NonValuedCondition::Prone).build())?;
+
+    t.new_encounter(&[(sarah.clone(), 8)])?;
+
+    assert_eq!(2, t.get_conditions(&sarah).len());
+    assert!(t.get_conditions(&ChrName::new("Goblin")).is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn new_encounter_saves_roster() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.new_encounter(&[(ChrName::new("Sarah"), 8)])?;
+
+    let names: Vec<&str> = t.get_roster().get_chrs().iter().map(|chr| chr.name.as_ref()).collect();
+    assert_eq!(vec!["Sarah"], names);
+
+    Ok(())
+}
+
+#[test]
+fn new_encounter_brings_back_roster() -> tracker::Result<()> {
+    let mut t: Tracker<NoSaver> = Tracker::builder()
+        .with_roster(Roster::new(vec![Chr::builder("Sarah", 0, true).with_health(Health::new(44)).build()]))
+        .build();
+    t.clear();
+
+    assert_eq!(1, t.get_party_members().len());
+    t.new_encounter(&[(ChrName::new("Sarah"), 14)])?;
+
+    assert_eq!(Some(14), t.get_chr(&ChrName::new("Sarah")).map(|chr| chr.init));
+
+    Ok(())
+}
+
+#[test]
+fn new_encounter_unknown_member_gives_error() {
+    let mut t = tracker();
+
+    let res = t.new_encounter(&[(ChrName::new("Goblin"), 8)]);
+
+    assert_eq!(Err(tracker::Error::ChangeNoneError(ChrName::new("Goblin"))), res);
+    assert_eq!(3, t.get_chrs().len());
+}
+
+#[test]
+fn new_encounter_is_one_change() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.new_encounter(&[(ChrName::new("Sarah"), 8)])?;
+    t.undo()?;
+
+    assert_eq!(3, t.get_chrs().len());
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{bestiary::{self, Bestiary, Template, Variant}, character::{Chr, ChrName, Health}, saver::NoSaver, tracker::{self, Tracker}};
+
+fn tracker() -> Tracker<NoSaver> {
+    let bestiary = Bestiary::new(vec![
+        Template::new("goblin-warrior", "Goblin Warrior", -1, 6, 16, 2),
+        Template::new("wolf", "Wolf", 1, 24, 15, 7),
+    ]);
+
+    Tracker::builder()
+        .with_seed(1)
+        .with_bestiary(bestiary)
+        .with_chrs(vec![Chr::builder("Alice", 20, true).build()])
+        .build()
+}
+
+#[test]
+fn add_from_template_adds_count_characters() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    let names = t.add_from_template("wolf", 3)?;
+
+    assert_eq!(vec![ChrName::new("Wolf"), ChrName::new("Wolf 2"), ChrName::new("Wolf 3")], names);
+    assert_eq!(4, t.get_chrs().len());
+
+    Ok(())
+}
+
+#[test]
+fn add_from_template_copies_stats() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_from_template("goblin-warrior", 1)?;
+
+    let goblin = t.get_chr(&ChrName::new("Goblin Warrior")).unwrap();
+    assert_eq!(Some(6), goblin.health.as_ref().map(|health| health.max));
+    assert_eq!(Some(-1), goblin.level);
+    assert_eq!(Some(2), goblin.init_mod);
+    assert_eq!(Some("goblin-warrior"), goblin.template.as_deref());
+    assert!(!goblin.player);
+    assert!((3..=22).contains(&goblin.init));
+
+    Ok(())
+}
+
+#[test]
+fn add_from_missing_template_gives_error() {
+    let mut t = tracker();
+
+    let res = t.add_from_template("dragon", 1);
+
+    assert_eq!(Err(tracker::Error::NoTemplateError("dragon".into())), res.map(|_| ()));
+    assert_eq!(1, t.get_chrs().len());
+}
+
+#[test]
+fn add_from_template_is_one_undo_step() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_from_template("wolf", 4)?;
+    t.undo()?;
+
+    assert_eq!(1, t.get_chrs().len());
+
+    Ok(())
+}
+
+#[test]
+fn add_elite_from_template_adjusts_stats() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_variant_from_template("wolf", Some(Variant::Elite), 1)?;
+
+    let wolf = t.get_chr(&ChrName::new("Wolf")).unwrap();
+    assert_eq!(Some(Health::new(34)), wolf.health);
+    assert_eq!(Some(2), wolf.level);
+    assert_eq!(Some(9), wolf.init_mod);
+    assert_eq!(Some(Variant::Elite), wolf.variant);
+
+    Ok(())
+}
+
+#[test]
+fn add_weak_from_template_adjusts_stats() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_variant_from_template("goblin-warrior", Some(Variant::Weak), 1)?;
+
+    let goblin = t.get_chr(&ChrName::new("Goblin Warrior")).unwrap();
+    assert_eq!(Some(Health::new(1)), goblin.health);
+    assert_eq!(Some(-2), goblin.level);
+    assert_eq!(Some(0), goblin.init_mod);
+
+    Ok(())
+}
+
+#[test]
+fn add_elite_from_template_adjusts_ac_and_saves() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.add_variant_from_template("wolf", Some(Variant::Elite), 1)?;
+
+    let wolf = t.get_chr(&ChrName::new("Wolf")).unwrap();
+    let template = Template::new("wolf", "Wolf", 1, 24, 15, 7).adjusted(Variant::Elite);
+    assert_eq!(Some(template.stats()), wolf.stats);
+    assert_eq!(Some(Health::new(34)), wolf.health);
+    assert_eq!(Some(Variant::Elite), wolf.variant);
+
+    Ok(())
+}
+
+#[test]
+fn variant_is_only_applied_once() {
+    let mut chr = Chr::builder("Orc", 10, false).with_health(Health::new(20)).with_level(2).build();
+
+    assert!(chr.apply_variant(Variant::Elite));
+    assert!(!chr.apply_variant(Variant::Elite));
+
+    assert_eq!(Some(Health::new(35)), chr.health);
+    assert_eq!(Some(3), chr.level);
+}
+
+#[test]
+fn variant_without_level_keeps_hp() {
+    let mut chr = Chr::builder("Ghoul", 10, false).with_health(Health::new(20)).build();
+
+    chr.apply_variant(Variant::Elite);
+
+    assert_eq!(Some(Health::new(20)), chr.health);
+    assert_eq!(Some(Variant::Elite), chr.variant);
+}
+
+#[test]
+fn saved_templates_load_back() {
+    let dir = std::env::temp_dir().join(format!("pathtracker-bestiary-{}", std::process::id()));
+    let mut template = Template::new("wolf", "Wolf", 1, 24, 15, 7);
+    template.notes = "Knockdown on a hit".into();
+
+    Bestiary::save_template(&dir, &template).unwrap();
+    let (bestiary, skipped) = Bestiary::load(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(Some(&template), bestiary.get("wolf"));
+    assert!(skipped.is_empty());
+}
+
+#[test]
+fn corrupt_templates_are_skipped_on_load() {
+    let dir = std::env::temp_dir().join(format!("pathtracker-corrupt-bestiary-{}", std::process::id()));
+    Bestiary::save_template(&dir, &Template::new("wolf", "Wolf", 1, 24, 15, 7)).unwrap();
+    std::fs::write(dir.join("broken.json"), "{ not a template").unwrap();
+
+    let (bestiary, skipped) = Bestiary::load(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(bestiary.get("wolf").is_some());
+    assert!(matches!(skipped.as_slice(), [bestiary::Error::LoadCorruptTemplate(file, _)] if file.ends_with("broken.json")));
+}
+
+#[test]
+fn template_ids_cant_leave_the_bestiary_directory() {
+    let dir = std::env::temp_dir().join(format!("pathtracker-unsafe-bestiary-{}", std::process::id()));
+    let template = Template { id: "../wolf".into(), ..Template::new("wolf", "Wolf", 1, 24, 15, 7) };
+
+    Bestiary::save_template(&dir, &template).unwrap();
+    let (bestiary, _) = Bestiary::load(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(vec!["---wolf"], bestiary.get_templates().iter().map(|t| t.id.as_str()).collect::<Vec<_>>());
+}
+
+#[test]
+fn missing_bestiary_directory_is_empty() {
+    let (bestiary, skipped) = Bestiary::load("no-such-bestiary-directory").unwrap();
+
+    assert!(bestiary.get_templates().is_empty());
+    assert!(skipped.is_empty());
+}
@@ -0,0 +1,172 @@
+#![allow(clippy::unwrap_used)]
+
+use pathtracker_rust::{character::{Chr, ChrName, Health}, conditions::{Condition, NonValuedCondition, NonValuedTerm, TurnEvent, ValuedCondition, ValuedTerm}, duration::Duration, saver::NoSaver, tracker::{self, Tracker}};
+
+fn tracker() -> Tracker<NoSaver> {
+    Tracker::builder().with_chrs(vec![
+        Chr::builder("Alice", 20, true).with_health(Health::new(40)).with_level(2).build(),
+        Chr::builder("Bob", 10, true).build(),
+    ]).build()
+}
+
+#[test]
+fn advance_time_moves_clock_forward() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.advance_time(Duration::from_hours(2))?;
+    t.advance_time(Duration::from_minutes(30))?;
+
+    assert_eq!(Duration::builder().with_hours(2).with_minutes(30).build(), t.get_clock());
+
+    Ok(())
+}
+
+#[test]
+fn advance_time_counts_down_duration() -> tracker::Result<()> {
+    let mut t = tracker();
+    let alice = ChrName::new("Alice");
+    let blinded = Condition::builder()
+        .condition(NonValuedCondition::Blinded)
+        .term(NonValuedTerm::For(Duration::from_hours(1)))
+        .build();
+
+    t.add_condition(alice.clone(), blinded.clone())?;
+    t.advance_time(Duration::from_minutes(20))?;
+
+    match t.get_conditions(&alice).get(&blinded) {
+        Some(Condition::NonValued { term: NonValuedTerm::For(dur), .. }) =>
+            assert_eq!(&Duration::from_minutes(40), dur),
+        _ => panic!(),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn advance_time_ends_expired_duration() -> tracker::Result<()> {
+    let mut t = tracker();
+    let alice = ChrName::new("Alice");
+    let drained = Condition::builder()
+        .condition(ValuedCondition::Drained)
+        .value(1)
+        .term(ValuedTerm::For(Duration::from_days(1)))
+        .build();
+
+    t.add_condition(alice.clone(), drained.clone())?;
+    assert_eq!(38, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+
+    t.advance_time(Duration::from_days(1))?;
+
+    assert!(!t.get_conditions(&alice).contains(&drained));
+    assert_eq!(40, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+
+    Ok(())
+}
+
+#[test]
+fn advance_time_ends_until_and_reduces_reduced() -> tracker::Result<()> {
+    let mut t = tracker();
+    let alice = ChrName::new("Alice");
+    let bob = ChrName::new("Bob");
+    let dazzled = Condition::builder()
+        .condition(NonValuedCondition::Dazzled)
+        .term(NonValuedTerm::Until(TurnEvent::EndOfNextTurn(alice.clone())))
+        .build();
+    let frightened = Condition::builder()
+        .condition(ValuedCondition::Frightened)
+        .value(3)
+        .term(ValuedTerm::Reduced(TurnEvent::EndOfNextTurn(bob.clone()), 1))
+        .build();
+
+    t.add_condition(alice.clone(), dazzled.clone())?;
+    t.add_condition(bob.clone(), frightened.clone())?;
+    t.advance_time(Duration::from_turns(2))?;
+
+    assert!(!t.get_conditions(&alice).contains(&dazzled));
+    match t.get_conditions(&bob).get(&frightened) {
+        Some(Condition::Valued { level, .. }) => assert_eq!(1, *level),
+        _ => panic!(),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn advance_time_keeps_manual_conditions() -> tracker::Result<()> {
+    let mut t = tracker();
+    let alice = ChrName::new("Alice");
+    let prone = Condition::builder().condition(NonValuedCondition::Prone).build();
+
+    t.add_condition(alice.clone(), prone.clone())?;
+    t.advance_time(Duration::from_days(3))?;
+
+    assert!(t.get_conditions(&alice).contains(&prone));
+
+    Ok(())
+}
+
+#[test]
+fn rest_removes_fatigued_and_reduces_drained() -> tracker::Result<()> {
+    let mut t = tracker();
+    let alice = ChrName::new("Alice");
+    let fatigued = Condition::builder().condition(NonValuedCondition::Fatigued).build();
+    let drained = Condition::builder().condition(ValuedCondition::Drained).value(2).build();
+
+    t.add_condition(alice.clone(), fatigued.clone())?;
+    t.add_condition(alice.clone(), drained.clone())?;
+    t.rest()?;
+
+    assert!(!t.get_conditions(&alice).contains(&fatigued));
+    match t.get_conditions(&alice).get(&drained) {
+        Some(Condition::Valued { level, .. }) => assert_eq!(1, *level),
+        _ => panic!(),
+    }
+    assert_eq!(38, t.get_chr(&alice).unwrap().health.as_ref().unwrap().max);
+    assert_eq!(Duration::from_hours(8), t.get_clock());
+
+    Ok(())
+}
+
+#[test]
+fn refocus_takes_10_minutes() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.refocus()?;
+
+    assert_eq!(Duration::from_minutes(10), t.get_clock());
+
+    Ok(())
+}
+
+#[test]
+fn undo_advance_time_restores_clock() -> tracker::Result<()> {
+    let mut t = tracker();
+
+    t.advance_time(Duration::from_hours(1))?;
+    t.undo()?;
+
+    assert_eq!(Duration::default(), t.get_clock());
+
+    Ok(())
+}
+
+#[test]
+fn advance_time_counts_rounds_as_turns() -> tracker::Result<()> {
+    let mut t = tracker();
+    let alice = ChrName::new("Alice");
+    let blinded = Condition::builder()
+        .condition(NonValuedCondition::Blinded)
+        .term(NonValuedTerm::For(Duration::from_rounds(3)))
+        .build();
+
+    t.add_condition(alice.clone(), blinded.clone())?;
+    t.advance_time(Duration::from_seconds(12))?;
+
+    match t.get_conditions(&alice).get(&blinded) {
+        Some(Condition::NonValued { term: NonValuedTerm::For(dur), .. }) =>
+            assert_eq!(&Duration::from_rounds(1), dur),
+        _ => panic!(),
+    }
+
+    Ok(())
+}
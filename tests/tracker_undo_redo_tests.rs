@@ -168,3 +168,27 @@ fn undo_undo_redo() -> tracker::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn undo_add_condition_many_is_one_step() -> tracker::Result<()> {
+    let jevil = Chr::builder("Jevil", 20, false).build();
+    let chris = Chr::builder("Chris", 17, true).build();
+    let ralsei = Chr::builder("Ralsei", 12, false).build();
+    let mut t = Tracker::builder()
+        .with_saver(NoSaver)
+        .with_chrs(vec![jevil, chris, ralsei])
+        .build();
+
+    let before = t.clone();
+    let frightened = Condition::builder()
+        .condition(ValuedCondition::Frightened)
+        .value(1)
+        .build();
+    t.add_condition_many(vec![(ChrName::new("Jevil"), frightened.clone()), (ChrName::new("Ralsei"), frightened)])?;
+    t.undo()?;
+
+    t.get_chrs().iter().for_each(|c| assert_eq!(t.get_conditions(&c.name), before.get_conditions(&c.name)));
+    assert_eq!(Err(tracker::Error::UndoNothingError), t.undo());
+
+    Ok(())
+}